@@ -1,3 +1,8 @@
 fn main() {
+    // Expose the target triple to the crate for get_app_version
+    println!(
+        "cargo:rustc-env=TARGET={}",
+        std::env::var("TARGET").unwrap_or_default()
+    );
     tauri_build::build()
 }
@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Source of a cooperative cancellation request, polled between files.
+pub trait CancelSignal {
+    fn is_cancelled(&self) -> bool;
+}
+
+impl CancelSignal for Arc<AtomicBool> {
+    fn is_cancelled(&self) -> bool {
+        self.load(Ordering::Relaxed)
+    }
+}
+
+impl CancelSignal for Arc<Mutex<bool>> {
+    fn is_cancelled(&self) -> bool {
+        // A poisoned lock still holds the last flag value
+        match self.lock() {
+            Ok(flag) => *flag,
+            Err(poisoned) => *poisoned.into_inner(),
+        }
+    }
+}
+
+/// Signal for operations that cannot be cancelled. Test-only until a
+/// non-cancellable caller exists; batch commands poll `CancellationToken`.
+#[cfg(test)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoCancelSignal;
+
+#[cfg(test)]
+impl CancelSignal for NoCancelSignal {
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_flag_reports_cancellation() {
+        let flag = Arc::new(AtomicBool::new(false));
+        assert!(!flag.is_cancelled());
+        flag.store(true, Ordering::Relaxed);
+        assert!(flag.is_cancelled());
+    }
+
+    #[test]
+    fn mutex_flag_reports_cancellation() {
+        let flag = Arc::new(Mutex::new(false));
+        assert!(!flag.is_cancelled());
+        if let Ok(mut guard) = flag.lock() {
+            *guard = true;
+        }
+        assert!(flag.is_cancelled());
+    }
+
+    #[test]
+    fn no_cancel_signal_never_cancels() {
+        assert!(!NoCancelSignal.is_cancelled());
+    }
+}
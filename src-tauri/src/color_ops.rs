@@ -1,5 +1,12 @@
+use ab_glyph::PxScale;
+use image::Rgba;
+use imageproc::drawing::{draw_text_mut, text_size};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+
+use crate::image_ops::{load_system_font, MAX_GENERATED_DIMENSION};
+use crate::utils::ensure_output_dir;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ColorInfo {
@@ -16,9 +23,19 @@ pub struct PaletteResult {
     pub source_path: String,
 }
 
-/// Extract dominant colors using histogram-based quantization.
-/// Downscales the image, buckets pixel colors, then picks the top N.
-pub fn extract_palette(image_path: &str, num_colors: usize) -> Result<PaletteResult, String> {
+/// Extract dominant colors from an image.
+/// Downscales the image, then quantizes with the requested algorithm:
+/// `histogram` (default), `median_cut` or `kmeans`.
+pub fn extract_palette(
+    image_path: &str,
+    num_colors: usize,
+    algorithm: Option<&str>,
+) -> Result<PaletteResult, String> {
+    let algorithm = algorithm.unwrap_or("histogram");
+    if !matches!(algorithm, "histogram" | "median_cut" | "kmeans") {
+        return Err(format!("Unknown palette algorithm: {}", algorithm));
+    }
+
     let img =
         image::open(image_path).map_err(|e| format!("Cannot open '{}': {}", image_path, e))?;
 
@@ -27,62 +44,763 @@ pub fn extract_palette(image_path: &str, num_colors: usize) -> Result<PaletteRes
     let rgba = thumb.to_rgba8();
     let total_pixels = (rgba.width() * rgba.height()) as f64;
 
-    // Quantize each pixel to 4-bit per channel (16 levels) to reduce noise
-    let mut buckets: HashMap<(u8, u8, u8), u32> = HashMap::new();
+    // Skip fully transparent pixels
+    let pixels: Vec<[u8; 3]> = rgba
+        .pixels()
+        .filter(|p| p.0[3] >= 128)
+        .map(|p| [p.0[0], p.0[1], p.0[2]])
+        .collect();
 
-    for pixel in rgba.pixels() {
-        let [r, g, b, a] = pixel.0;
-        // Skip fully transparent pixels
-        if a < 128 {
-            continue;
-        }
-        // Quantize to 16 levels per channel
-        let qr = (r >> 4) << 4;
-        let qg = (g >> 4) << 4;
-        let qb = (b >> 4) << 4;
-        *buckets.entry((qr, qg, qb)).or_insert(0) += 1;
+    let weighted = match algorithm {
+        "median_cut" => median_cut_palette(&pixels, num_colors),
+        "kmeans" => kmeans_palette(&pixels, num_colors),
+        _ => histogram_palette(&pixels, num_colors),
+    };
+
+    let colors: Vec<ColorInfo> = weighted
+        .iter()
+        .map(|([r, g, b], count)| {
+            let percentage = (*count as f64 / total_pixels) * 100.0;
+            ColorInfo {
+                hex: format!("#{:02X}{:02X}{:02X}", r, g, b),
+                r: *r,
+                g: *g,
+                b: *b,
+                percentage: (percentage * 10.0).round() / 10.0,
+            }
+        })
+        .collect();
+
+    Ok(PaletteResult {
+        colors,
+        source_path: image_path.to_string(),
+    })
+}
+
+/// Bucket pixels at 4 bits per channel and keep the most frequent,
+/// sufficiently distinct buckets.
+fn histogram_palette(pixels: &[[u8; 3]], num_colors: usize) -> Vec<([u8; 3], u32)> {
+    // Quantize each pixel to 4-bit per channel (16 levels) to reduce noise
+    let mut buckets: HashMap<[u8; 3], u32> = HashMap::new();
+    for [r, g, b] in pixels {
+        let quantized = [(r >> 4) << 4, (g >> 4) << 4, (b >> 4) << 4];
+        *buckets.entry(quantized).or_insert(0) += 1;
     }
 
     // Sort buckets by frequency (descending)
-    let mut sorted: Vec<((u8, u8, u8), u32)> = buckets.into_iter().collect();
+    let mut sorted: Vec<([u8; 3], u32)> = buckets.into_iter().collect();
     sorted.sort_by(|a, b| b.1.cmp(&a.1));
 
     // Merge similar colors that are too close together
-    let mut final_colors: Vec<((u8, u8, u8), u32)> = Vec::new();
-
+    let mut final_colors: Vec<([u8; 3], u32)> = Vec::new();
     for (color, count) in &sorted {
+        if final_colors.len() >= num_colors {
+            break;
+        }
         let too_close = final_colors.iter().any(|(existing, _)| {
-            let dr = (color.0 as i32 - existing.0 as i32).abs();
-            let dg = (color.1 as i32 - existing.1 as i32).abs();
-            let db = (color.2 as i32 - existing.2 as i32).abs();
+            let dr = (color[0] as i32 - existing[0] as i32).abs();
+            let dg = (color[1] as i32 - existing[1] as i32).abs();
+            let db = (color[2] as i32 - existing[2] as i32).abs();
             dr + dg + db < 60
         });
-
         if !too_close {
             final_colors.push((*color, *count));
         }
+    }
+    final_colors
+}
 
-        if final_colors.len() >= num_colors {
-            break;
+fn mean_color(pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    for p in pixels {
+        for (total, channel) in sum.iter_mut().zip(p) {
+            *total += *channel as u64;
         }
     }
+    let n = pixels.len().max(1) as u64;
+    [
+        ((sum[0] + n / 2) / n) as u8,
+        ((sum[1] + n / 2) / n) as u8,
+        ((sum[2] + n / 2) / n) as u8,
+    ]
+}
 
-    let colors: Vec<ColorInfo> = final_colors
+/// Returns (channel, range) of the widest channel in a box of pixels.
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    let mut best = (0, 0);
+    for c in 0..3 {
+        let min = pixels.iter().map(|p| p[c]).min().unwrap_or(0);
+        let max = pixels.iter().map(|p| p[c]).max().unwrap_or(0);
+        if max - min > best.1 {
+            best = (c, max - min);
+        }
+    }
+    best
+}
+
+/// Median cut: repeatedly split the box with the widest channel range at its
+/// median along that channel, then average each box.
+fn median_cut_palette(pixels: &[[u8; 3]], num_colors: usize) -> Vec<([u8; 3], u32)> {
+    if pixels.is_empty() || num_colors == 0 {
+        return Vec::new();
+    }
+    let mut boxes: Vec<Vec<[u8; 3]>> = vec![pixels.to_vec()];
+
+    while boxes.len() < num_colors {
+        let candidate = boxes
+            .iter()
+            .enumerate()
+            .map(|(i, b)| (i, widest_channel(b)))
+            .filter(|(_, (_, range))| *range > 0)
+            .max_by_key(|(_, (_, range))| *range);
+        let Some((index, (channel, _))) = candidate else {
+            break;
+        };
+
+        let mut current = boxes.swap_remove(index);
+        current.sort_unstable_by_key(|p| p[channel]);
+
+        // Move the split off the median onto the nearest value boundary so
+        // identical colors never straddle two boxes.
+        let mid = current.len() / 2;
+        let split = (mid..current.len())
+            .find(|&i| i > 0 && current[i][channel] != current[i - 1][channel])
+            .or_else(|| {
+                (1..mid)
+                    .rev()
+                    .find(|&i| current[i][channel] != current[i - 1][channel])
+            })
+            .unwrap_or(mid);
+
+        let upper = current.split_off(split);
+        boxes.push(current);
+        boxes.push(upper);
+    }
+
+    let mut result: Vec<([u8; 3], u32)> = boxes
         .iter()
-        .map(|((r, g, b), count)| {
-            let percentage = (*count as f64 / total_pixels) * 100.0;
-            ColorInfo {
-                hex: format!("#{:02X}{:02X}{:02X}", r, g, b),
-                r: *r,
-                g: *g,
-                b: *b,
-                percentage: (percentage * 10.0).round() / 10.0,
+        .map(|b| (mean_color(b), b.len() as u32))
+        .collect();
+    result.sort_by(|a, b| b.1.cmp(&a.1));
+    result
+}
+
+const KMEANS_MAX_ITERATIONS: usize = 20;
+
+fn distance_sq(a: [f64; 3], b: [f64; 3]) -> f64 {
+    (0..3).map(|c| (a[c] - b[c]).powi(2)).sum()
+}
+
+/// K-means over unique colors weighted by frequency. Centroids are seeded
+/// deterministically with farthest-point selection from the most common color.
+fn kmeans_palette(pixels: &[[u8; 3]], num_colors: usize) -> Vec<([u8; 3], u32)> {
+    let mut counts: HashMap<[u8; 3], u32> = HashMap::new();
+    for p in pixels {
+        *counts.entry(*p).or_insert(0) += 1;
+    }
+    let mut unique: Vec<([f64; 3], u32)> = counts
+        .into_iter()
+        .map(|(c, n)| ([c[0] as f64, c[1] as f64, c[2] as f64], n))
+        .collect();
+    // Deterministic ordering regardless of HashMap iteration order
+    unique.sort_by(|a, b| {
+        b.1.cmp(&a.1)
+            .then_with(|| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal))
+    });
+
+    let k = num_colors.min(unique.len());
+    if k == 0 {
+        return Vec::new();
+    }
+
+    let mut centroids: Vec<[f64; 3]> = vec![unique[0].0];
+    while centroids.len() < k {
+        let farthest = unique
+            .iter()
+            .map(|(c, _)| {
+                let d = centroids
+                    .iter()
+                    .map(|centroid| distance_sq(*c, *centroid))
+                    .fold(f64::MAX, f64::min);
+                (*c, d)
+            })
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        match farthest {
+            Some((c, d)) if d > 0.0 => centroids.push(c),
+            _ => break,
+        }
+    }
+
+    let mut assignment = vec![0usize; unique.len()];
+    for _ in 0..KMEANS_MAX_ITERATIONS {
+        let mut changed = false;
+        for (i, (c, _)) in unique.iter().enumerate() {
+            let nearest = centroids
+                .iter()
+                .enumerate()
+                .map(|(j, centroid)| (j, distance_sq(*c, *centroid)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(j, _)| j)
+                .unwrap_or(0);
+            if assignment[i] != nearest {
+                assignment[i] = nearest;
+                changed = true;
+            }
+        }
+
+        let mut sums = vec![([0.0f64; 3], 0u64); centroids.len()];
+        for (i, (c, n)) in unique.iter().enumerate() {
+            let entry = &mut sums[assignment[i]];
+            for (total, channel) in entry.0.iter_mut().zip(c) {
+                *total += channel * *n as f64;
             }
+            entry.1 += *n as u64;
+        }
+        for (centroid, (sum, n)) in centroids.iter_mut().zip(&sums) {
+            if *n > 0 {
+                *centroid = [sum[0] / *n as f64, sum[1] / *n as f64, sum[2] / *n as f64];
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let mut totals = vec![0u32; centroids.len()];
+    for (i, (_, n)) in unique.iter().enumerate() {
+        totals[assignment[i]] += n;
+    }
+    let mut result: Vec<([u8; 3], u32)> = centroids
+        .iter()
+        .zip(totals)
+        .filter(|(_, n)| *n > 0)
+        .map(|(c, n)| {
+            (
+                [
+                    c[0].round().clamp(0.0, 255.0) as u8,
+                    c[1].round().clamp(0.0, 255.0) as u8,
+                    c[2].round().clamp(0.0, 255.0) as u8,
+                ],
+                n,
+            )
         })
         .collect();
+    result.sort_by(|a, b| b.1.cmp(&a.1));
+    result
+}
 
-    Ok(PaletteResult {
-        colors,
-        source_path: image_path.to_string(),
+// --- Palette export ---
+
+const PALETTE_LABEL_HEIGHT: u32 = 20;
+const PALETTE_LABEL_FONT_SIZE: f32 = 12.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PaletteImageResult {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Lay out palette colors as a horizontal strip of swatches.
+/// The optional label row sits under the swatches on a white background.
+fn render_palette_strip(
+    colors: &[ColorInfo],
+    swatch_width: u32,
+    swatch_height: u32,
+    label_height: u32,
+) -> image::RgbaImage {
+    let width = swatch_width * colors.len() as u32;
+    let mut canvas =
+        image::RgbaImage::from_pixel(width, swatch_height + label_height, Rgba([255; 4]));
+    for (i, color) in colors.iter().enumerate() {
+        let x0 = i as u32 * swatch_width;
+        for y in 0..swatch_height {
+            for x in x0..x0 + swatch_width {
+                canvas.put_pixel(x, y, Rgba([color.r, color.g, color.b, 255]));
+            }
+        }
+    }
+    canvas
+}
+
+/// Export palette colors as a PNG swatch strip, optionally with hex labels.
+pub fn export_palette_image(
+    colors: &[ColorInfo],
+    swatch_width: u32,
+    swatch_height: u32,
+    label: bool,
+    output_path: &str,
+) -> Result<PaletteImageResult, String> {
+    if colors.is_empty() {
+        return Err("No colors to export".to_string());
+    }
+    let label_height = if label { PALETTE_LABEL_HEIGHT } else { 0 };
+    let width = swatch_width as u64 * colors.len() as u64;
+    let height = swatch_height as u64 + label_height as u64;
+    if swatch_width == 0
+        || swatch_height == 0
+        || width > MAX_GENERATED_DIMENSION as u64
+        || height > MAX_GENERATED_DIMENSION as u64
+    {
+        return Err(format!(
+            "Palette image dimensions must be between 1 and {} px",
+            MAX_GENERATED_DIMENSION
+        ));
+    }
+
+    let mut canvas = render_palette_strip(colors, swatch_width, swatch_height, label_height);
+
+    if label {
+        let font = load_system_font()?;
+        let scale = PxScale::from(PALETTE_LABEL_FONT_SIZE);
+        let text_color = Rgba([40, 40, 40, 255]);
+        for (i, color) in colors.iter().enumerate() {
+            let (text_w, text_h) = text_size(scale, &font, &color.hex);
+            let x = (i as u32 * swatch_width) as i32 + (swatch_width as i32 - text_w as i32) / 2;
+            let y = swatch_height as i32 + (label_height as i32 - text_h as i32) / 2;
+            draw_text_mut(&mut canvas, text_color, x, y, scale, &font, &color.hex);
+        }
+    }
+
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    canvas
+        .save_with_format(output_path, image::ImageFormat::Png)
+        .map_err(|e| format!("Cannot save PNG: {}", e))?;
+
+    Ok(PaletteImageResult {
+        output_path: output_path.to_string(),
+        width: canvas.width(),
+        height: canvas.height(),
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AverageColorResult {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+    pub hex: String,
+}
+
+/// Average the RGBA channels of a pixel set.
+/// Returns `None` when every pixel was skipped (e.g. a fully transparent image).
+fn average_rgba<'a, I>(pixels: I, ignore_transparent: bool) -> Option<[u8; 4]>
+where
+    I: Iterator<Item = &'a image::Rgba<u8>>,
+{
+    let mut sums = [0u64; 4];
+    let mut count: u64 = 0;
+
+    for pixel in pixels {
+        if ignore_transparent && pixel[3] < 128 {
+            continue;
+        }
+        for (sum, &channel) in sums.iter_mut().zip(pixel.0.iter()) {
+            *sum += channel as u64;
+        }
+        count += 1;
+    }
+
+    if count == 0 {
+        return None;
+    }
+
+    Some(sums.map(|sum| ((sum as f64 / count as f64).round()) as u8))
+}
+
+/// Compute the mean color of an image.
+/// The image is downscaled to 10×10 first — plenty for a background/placeholder tint.
+pub fn average_color(
+    image_path: &str,
+    ignore_transparent: bool,
+) -> Result<AverageColorResult, String> {
+    let img =
+        image::open(image_path).map_err(|e| format!("Cannot open '{}': {}", image_path, e))?;
+
+    let thumb = img
+        .resize_exact(10, 10, image::imageops::FilterType::Triangle)
+        .to_rgba8();
+
+    let [r, g, b, a] = average_rgba(thumb.pixels(), ignore_transparent)
+        .ok_or_else(|| "Image has no opaque pixels to average".to_string())?;
+
+    Ok(AverageColorResult {
+        r,
+        g,
+        b,
+        a,
+        hex: format!("#{:02X}{:02X}{:02X}", r, g, b),
     })
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistogramResult {
+    pub red: Vec<u64>,
+    pub green: Vec<u64>,
+    pub blue: Vec<u64>,
+    pub luminance: Vec<u64>,
+}
+
+/// Bin counts accepted by `image_histogram`.
+const HISTOGRAM_BINS: &[u32] = &[8, 16, 32, 64, 128, 256];
+/// Above this many pixels, only every Nth pixel is sampled to keep the histogram fast.
+const HISTOGRAM_SAMPLE_BUDGET: u64 = 10_000_000;
+
+/// BT.601 luma, rounded to the nearest 8-bit level.
+fn luminance_bt601(r: u8, g: u8, b: u8) -> u8 {
+    (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64)
+        .round()
+        .min(255.0) as u8
+}
+
+/// Accumulate per-channel histograms with `bins` buckets each.
+/// Every `step`-th pixel is counted, so each vector sums to the number of sampled pixels.
+fn compute_histogram<'a, I>(pixels: I, bins: u32, step: usize) -> HistogramResult
+where
+    I: Iterator<Item = &'a image::Rgb<u8>>,
+{
+    let bins = bins as usize;
+    let bin_of = |value: u8| value as usize * bins / 256;
+
+    let mut result = HistogramResult {
+        red: vec![0; bins],
+        green: vec![0; bins],
+        blue: vec![0; bins],
+        luminance: vec![0; bins],
+    };
+
+    for pixel in pixels.step_by(step.max(1)) {
+        let [r, g, b] = pixel.0;
+        result.red[bin_of(r)] += 1;
+        result.green[bin_of(g)] += 1;
+        result.blue[bin_of(b)] += 1;
+        result.luminance[bin_of(luminance_bt601(r, g, b))] += 1;
+    }
+
+    result
+}
+
+/// Compute red/green/blue/luminance histograms for an image.
+/// Very large images are sampled every `total_pixels / 10_000_000` pixels.
+pub fn image_histogram(image_path: &str, bins: u32) -> Result<HistogramResult, String> {
+    if !HISTOGRAM_BINS.contains(&bins) {
+        return Err(format!(
+            "Invalid bin count {} (expected one of {:?})",
+            bins, HISTOGRAM_BINS
+        ));
+    }
+
+    let img =
+        image::open(image_path).map_err(|e| format!("Cannot open '{}': {}", image_path, e))?;
+    let rgb = img.to_rgb8();
+
+    let total_pixels = rgb.width() as u64 * rgb.height() as u64;
+    let step = (total_pixels / HISTOGRAM_SAMPLE_BUDGET).max(1) as usize;
+
+    Ok(compute_histogram(rgb.pixels(), bins, step))
+}
+
+// --- Color distance ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ColorDistanceResult {
+    pub distance: f64,
+    pub perceptually_similar: bool,
+}
+
+/// Just-noticeable difference in Lab space (applies to both CIE76 and CIEDE2000).
+const DEFAULT_LAB_JND: f64 = 2.3;
+/// Rough RGB-space equivalent of the JND for mid-tone colors.
+const DEFAULT_RGB_THRESHOLD: f64 = 10.0;
+
+/// Parse "#RRGGBB", "RRGGBB", or "[r, g, b]" into an RGB triple.
+fn parse_color_input(input: &str) -> Result<[u8; 3], String> {
+    let trimmed = input.trim();
+    let invalid = || format!("Invalid color '{}' (expected #RRGGBB or [r, g, b])", input);
+
+    if let Some(inner) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+        let channels: Vec<u8> = inner
+            .split(',')
+            .map(|c| c.trim().parse::<u8>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| invalid())?;
+        return <[u8; 3]>::try_from(channels).map_err(|_| invalid());
+    }
+
+    let hex = trimmed.trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let mut rgb = [0u8; 3];
+    for (i, channel) in rgb.iter_mut().enumerate() {
+        *channel = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(rgb)
+}
+
+/// Convert sRGB to CIELAB using the sRGB transfer curve and D65 white point.
+fn srgb_to_lab(rgb: [u8; 3]) -> [f64; 3] {
+    let linear = rgb.map(|c| {
+        let c = c as f64 / 255.0;
+        if c <= 0.04045 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    let [r, g, b] = linear;
+    let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+
+    // D65 reference white
+    let f = |t: f64| {
+        if t > 216.0 / 24389.0 {
+            t.cbrt()
+        } else {
+            (24389.0 / 27.0 * t + 16.0) / 116.0
+        }
+    };
+    let (fx, fy, fz) = (f(x / 0.95047), f(y), f(z / 1.08883));
+    [116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz)]
+}
+
+/// CIEDE2000 color difference (kL = kC = kH = 1), per Sharma, Wu & Dalal (2005).
+fn ciede2000(lab1: [f64; 3], lab2: [f64; 3]) -> f64 {
+    let [l1, a1, b1] = lab1;
+    let [l2, a2, b2] = lab2;
+    let pow7 = |v: f64| v.powi(7);
+    let twenty_five_pow7 = 6_103_515_625.0; // 25^7
+
+    let c_bar = ((a1.hypot(b1)) + (a2.hypot(b2))) / 2.0;
+    let g = 0.5 * (1.0 - (pow7(c_bar) / (pow7(c_bar) + twenty_five_pow7)).sqrt());
+    let (a1p, a2p) = ((1.0 + g) * a1, (1.0 + g) * a2);
+    let (c1p, c2p) = (a1p.hypot(b1), a2p.hypot(b2));
+    let hue = |b: f64, a: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            b.atan2(a).to_degrees().rem_euclid(360.0)
+        }
+    };
+    let (h1p, h2p) = (hue(b1, a1p), hue(b2, a2p));
+
+    let dl = l2 - l1;
+    let dc = c2p - c1p;
+    let dh_angle = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p <= h1p {
+        h2p - h1p + 360.0
+    } else {
+        h2p - h1p - 360.0
+    };
+    let dh = 2.0 * (c1p * c2p).sqrt() * (dh_angle.to_radians() / 2.0).sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let cp_bar = (c1p + c2p) / 2.0;
+    let hp_bar = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (hp_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * hp_bar).to_radians().cos()
+        + 0.32 * (3.0 * hp_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * hp_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((hp_bar - 275.0) / 25.0).powi(2)).exp();
+    let rc = 2.0 * (pow7(cp_bar) / (pow7(cp_bar) + twenty_five_pow7)).sqrt();
+    let sl = 1.0 + 0.015 * (l_bar - 50.0).powi(2) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * cp_bar;
+    let sh = 1.0 + 0.015 * cp_bar * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    ((dl / sl).powi(2) + (dc / sc).powi(2) + (dh / sh).powi(2) + rt * (dc / sc) * (dh / sh)).sqrt()
+}
+
+fn euclidean(a: [f64; 3], b: [f64; 3]) -> f64 {
+    distance_sq(a, b).sqrt()
+}
+
+/// Measure how different two colors are. `method` is "euclidean_rgb", "euclidean_lab"
+/// (CIE76), or "ciede2000"; `threshold` overrides the "similar" cutoff (default: the
+/// Lab JND of 2.3, or 10 for RGB distance).
+pub fn color_distance(
+    color_a: &str,
+    color_b: &str,
+    method: &str,
+    threshold: Option<f64>,
+) -> Result<ColorDistanceResult, String> {
+    let a = parse_color_input(color_a)?;
+    let b = parse_color_input(color_b)?;
+
+    let (distance, default_threshold) = match method {
+        "euclidean_rgb" => (
+            euclidean(a.map(f64::from), b.map(f64::from)),
+            DEFAULT_RGB_THRESHOLD,
+        ),
+        "euclidean_lab" => (euclidean(srgb_to_lab(a), srgb_to_lab(b)), DEFAULT_LAB_JND),
+        "ciede2000" => (ciede2000(srgb_to_lab(a), srgb_to_lab(b)), DEFAULT_LAB_JND),
+        other => return Err(format!("Unknown distance method: {}", other)),
+    };
+
+    Ok(ColorDistanceResult {
+        distance,
+        perceptually_similar: distance < threshold.unwrap_or(default_threshold),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::Rgba;
+
+    fn two_color_pixels() -> Vec<[u8; 3]> {
+        let mut pixels = vec![[200, 30, 40]; 70];
+        pixels.extend(vec![[10, 90, 220]; 30]);
+        pixels
+    }
+
+    fn color(r: u8, g: u8, b: u8) -> ColorInfo {
+        ColorInfo {
+            hex: format!("#{:02X}{:02X}{:02X}", r, g, b),
+            r,
+            g,
+            b,
+            percentage: 0.0,
+        }
+    }
+
+    #[test]
+    fn palette_strip_fills_each_column() {
+        let colors = vec![color(255, 0, 0), color(0, 0, 255)];
+        let strip = render_palette_strip(&colors, 10, 5, PALETTE_LABEL_HEIGHT);
+        assert_eq!(strip.dimensions(), (20, 5 + PALETTE_LABEL_HEIGHT));
+        assert_eq!(strip.get_pixel(9, 4).0, [255, 0, 0, 255]);
+        assert_eq!(strip.get_pixel(10, 0).0, [0, 0, 255, 255]);
+        assert_eq!(strip.get_pixel(0, 5).0, [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn median_cut_two_colors_returns_exactly_those() {
+        let palette = median_cut_palette(&two_color_pixels(), 5);
+        assert_eq!(palette, vec![([200, 30, 40], 70), ([10, 90, 220], 30)]);
+    }
+
+    #[test]
+    fn kmeans_two_colors_returns_exactly_those() {
+        let palette = kmeans_palette(&two_color_pixels(), 5);
+        assert_eq!(palette, vec![([200, 30, 40], 70), ([10, 90, 220], 30)]);
+    }
+
+    #[test]
+    fn median_cut_respects_requested_count() {
+        let pixels: Vec<[u8; 3]> = (0..=255u8).map(|v| [v, v / 2, 255 - v]).collect();
+        assert_eq!(median_cut_palette(&pixels, 4).len(), 4);
+    }
+
+    #[test]
+    fn average_rgba_mixes_channels() {
+        let pixels = [Rgba([255, 0, 0, 255]), Rgba([0, 0, 255, 255])];
+        assert_eq!(average_rgba(pixels.iter(), false), Some([128, 0, 128, 255]));
+    }
+
+    #[test]
+    fn average_rgba_skips_transparent_when_requested() {
+        let pixels = [Rgba([255, 255, 255, 255]), Rgba([0, 0, 0, 0])];
+        assert_eq!(
+            average_rgba(pixels.iter(), true),
+            Some([255, 255, 255, 255])
+        );
+        assert_eq!(
+            average_rgba(pixels.iter(), false),
+            Some([128, 128, 128, 128])
+        );
+    }
+
+    #[test]
+    fn average_rgba_all_transparent_is_none() {
+        let pixels = [Rgba([10, 20, 30, 0])];
+        assert_eq!(average_rgba(pixels.iter(), true), None);
+    }
+
+    #[test]
+    fn histogram_bins_sum_to_pixel_count() {
+        let pixels = [
+            image::Rgb([0, 0, 0]),
+            image::Rgb([255, 255, 255]),
+            image::Rgb([128, 64, 32]),
+        ];
+        let hist = compute_histogram(pixels.iter(), 8, 1);
+        for channel in [&hist.red, &hist.green, &hist.blue, &hist.luminance] {
+            assert_eq!(channel.len(), 8);
+            assert_eq!(channel.iter().sum::<u64>(), 3);
+        }
+        assert_eq!(hist.red[0], 1);
+        assert_eq!(hist.red[4], 1);
+        assert_eq!(hist.red[7], 1);
+    }
+
+    #[test]
+    fn parse_color_input_accepts_hex_and_arrays() {
+        assert_eq!(parse_color_input("#FF8000").unwrap(), [255, 128, 0]);
+        assert_eq!(parse_color_input("[1, 2, 3]").unwrap(), [1, 2, 3]);
+        assert!(parse_color_input("#FFF").is_err());
+        assert!(parse_color_input("[1, 2]").is_err());
+        assert!(parse_color_input("[1, 2, 300]").is_err());
+    }
+
+    #[test]
+    fn srgb_to_lab_matches_reference_values() {
+        let white = srgb_to_lab([255, 255, 255]);
+        assert!((white[0] - 100.0).abs() < 0.01 && white[1].abs() < 0.01 && white[2].abs() < 0.01);
+        let red = srgb_to_lab([255, 0, 0]);
+        assert!((red[0] - 53.24).abs() < 0.05);
+        assert!((red[1] - 80.09).abs() < 0.05);
+        assert!((red[2] - 67.20).abs() < 0.05);
+    }
+
+    #[test]
+    fn ciede2000_matches_sharma_test_data() {
+        let cases = [
+            ([50.0, 2.6772, -79.7751], [50.0, 0.0, -82.7485], 2.0425),
+            ([50.0, -1.0, 2.0], [50.0, 0.0, 0.0], 2.3669),
+            ([50.0, 2.5, 0.0], [73.0, 25.0, -18.0], 27.1492),
+            (
+                [2.0776, 0.0795, -1.1350],
+                [0.9033, -0.0636, -0.5514],
+                0.9082,
+            ),
+        ];
+        for (lab1, lab2, expected) in cases {
+            assert!((ciede2000(lab1, lab2) - expected).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn color_distance_flags_similar_colors() {
+        let near = color_distance("#808080", "#818181", "ciede2000", None).unwrap();
+        assert!(near.perceptually_similar);
+        let far = color_distance("#FF0000", "#0000FF", "ciede2000", None).unwrap();
+        assert!(!far.perceptually_similar);
+        assert!(color_distance("#000000", "#FFFFFF", "manhattan", None).is_err());
+    }
+
+    #[test]
+    fn luminance_uses_bt601_weights() {
+        assert_eq!(luminance_bt601(255, 255, 255), 255);
+        assert_eq!(luminance_bt601(255, 0, 0), 76);
+        assert_eq!(luminance_bt601(0, 255, 0), 150);
+        assert_eq!(luminance_bt601(0, 0, 255), 29);
+    }
+}
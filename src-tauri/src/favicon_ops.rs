@@ -1,4 +1,5 @@
-use image::{DynamicImage, ImageFormat};
+use color_quant::NeuQuant;
+use image::{DynamicImage, ImageFormat, RgbaImage};
 use serde::{Deserialize, Serialize};
 use std::io::{Cursor, Write};
 use std::path::PathBuf;
@@ -6,7 +7,8 @@ use zip::write::SimpleFileOptions;
 use zip::ZipWriter;
 
 use crate::progress::emit_progress_simple;
-use crate::utils::{ensure_output_dir, file_stem as get_file_stem};
+use crate::svg_ops::{parse_svg, render_svg_square};
+use crate::utils::{ensure_output_dir, file_stem as get_file_stem, get_extension};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct FaviconResult {
@@ -15,38 +17,222 @@ pub struct FaviconResult {
     pub errors: Vec<String>,
 }
 
-/// Sizes to generate: (filename, width, height)
-const FAVICON_SIZES: &[(&str, u32, u32)] = &[
-    ("favicon-16x16.png", 16, 16),
-    ("favicon-32x32.png", 32, 32),
-    ("favicon-48x48.png", 48, 48),
-    ("apple-touch-icon.png", 180, 180),
-    ("android-chrome-192x192.png", 192, 192),
-    ("android-chrome-512x512.png", 512, 512),
+/// Square sizes to generate: (filename, edge length)
+const FAVICON_SIZES: &[(&str, u32)] = &[
+    ("favicon-16x16.png", 16),
+    ("favicon-32x32.png", 32),
+    ("favicon-48x48.png", 48),
+    ("apple-touch-icon.png", 180),
+    ("android-chrome-192x192.png", 192),
+    ("android-chrome-512x512.png", 512),
 ];
 
-/// ICO sizes to embed
+/// ICO sizes to embed in the favicon pack
 const ICO_SIZES: &[u32] = &[16, 32, 48];
 
-fn resize_to_png_bytes(img: &DynamicImage, w: u32, h: u32) -> Result<Vec<u8>, String> {
-    let resized = img.resize_exact(w, h, image::imageops::FilterType::Lanczos3);
+/// ICO sizes for a standalone icon (`image_to_ico`), including the 256px desktop size.
+pub const DEFAULT_ICO_FILE_SIZES: &[u32] = &[16, 32, 48, 256];
+
+/// Open Graph preview image dimensions (Facebook/LinkedIn/Twitter large card).
+const OG_IMAGE_SIZE: (u32, u32) = (1200, 630);
+
+/// Default OG logo width as a fraction of the 1200px canvas.
+pub const DEFAULT_OG_LOGO_SCALE: f32 = 0.6;
+
+/// Default OG background: white.
+pub const DEFAULT_OG_BACKGROUND: [u8; 3] = [255, 255, 255];
+
+/// Drop shadow under the OG logo, only drawn on non-white backgrounds.
+const OG_SHADOW_OPACITY: f32 = 0.15;
+const OG_SHADOW_OFFSET: i64 = 8;
+const OG_SHADOW_BLUR_SIGMA: f32 = 6.0;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IcoResult {
+    pub output_path: String,
+    pub sizes_included: Vec<u32>,
+    pub errors: Vec<String>,
+}
+
+/// Favicon input: a raster image that gets downscaled, or an SVG rendered
+/// separately at every output size so strokes stay crisp.
+enum FaviconSource {
+    Raster(DynamicImage),
+    Svg {
+        tree: resvg::usvg::Tree,
+        /// Render once at this size and downscale instead of rendering per size.
+        override_size: Option<u32>,
+    },
+}
+
+impl FaviconSource {
+    fn open(path: &str, svg_override_size: Option<u32>) -> Result<Self, String> {
+        if get_extension(path) == "svg" {
+            let data = std::fs::read(path).map_err(|e| format!("Cannot read '{}': {}", path, e))?;
+            return Ok(Self::Svg {
+                tree: parse_svg(&data)?,
+                override_size: svg_override_size,
+            });
+        }
+        image::open(path)
+            .map(Self::Raster)
+            .map_err(|e| format!("Cannot open '{}': {}", path, e))
+    }
+
+    /// Produce a `size × size` frame.
+    fn render(&self, size: u32) -> Result<DynamicImage, String> {
+        let filter = image::imageops::FilterType::Lanczos3;
+        match self {
+            Self::Raster(img) => Ok(img.resize_exact(size, size, filter)),
+            Self::Svg {
+                tree,
+                override_size: None,
+            } => render_svg_square(tree, size).map(DynamicImage::ImageRgba8),
+            Self::Svg {
+                tree,
+                override_size: Some(viewport),
+            } => {
+                let rendered = render_svg_square(tree, *viewport)?;
+                Ok(DynamicImage::ImageRgba8(rendered).resize_exact(size, size, filter))
+            }
+        }
+    }
+
+    /// Produce a logo that fits within `max_width × max_height`, keeping the
+    /// raster aspect ratio (SVGs are rendered square).
+    fn render_fit(&self, max_width: u32, max_height: u32) -> Result<DynamicImage, String> {
+        match self {
+            Self::Raster(img) => {
+                Ok(img.resize(max_width, max_height, image::imageops::FilterType::Lanczos3))
+            }
+            Self::Svg { .. } => self.render(max_width.min(max_height)),
+        }
+    }
+}
+
+fn render_png_bytes(source: &FaviconSource, size: u32) -> Result<Vec<u8>, String> {
+    let frame = source.render(size)?;
     let mut buf = Cursor::new(Vec::new());
-    resized
+    frame
         .write_to(&mut buf, ImageFormat::Png)
-        .map_err(|e| format!("Failed to encode {}x{} PNG: {}", w, h, e))?;
+        .map_err(|e| format!("Failed to encode {}x{} PNG: {}", size, size, e))?;
     Ok(buf.into_inner())
 }
 
+/// Default ICO color depth: PNG-compressed 32-bit frames.
+pub const DEFAULT_ICO_COLOR_DEPTH: u8 = 32;
+
+/// NeuQuant sampling factor (1 = best quality, 30 = fastest); icons are tiny.
+const ICO_QUANT_SAMPLE_FACTOR: i32 = 1;
+
+/// Bytes per row of a DIB, padded to a 4-byte boundary.
+fn dib_row_stride(width: u32, bits_per_pixel: u32) -> usize {
+    ((width * bits_per_pixel).div_ceil(32) * 4) as usize
+}
+
+/// Encode a BMP-style ICO frame: BITMAPINFOHEADER, optional palette, bottom-up
+/// XOR pixels (24-bit BGR or 8-bit indexed) and a 1-bit AND transparency mask.
+fn encode_dib_frame(rgba: &RgbaImage, color_depth: u8) -> Vec<u8> {
+    let (w, h) = rgba.dimensions();
+    let bpp = color_depth as u32;
+    let xor_stride = dib_row_stride(w, bpp);
+    let and_stride = dib_row_stride(w, 1);
+
+    let quantizer =
+        (color_depth == 8).then(|| NeuQuant::new(ICO_QUANT_SAMPLE_FACTOR, 256, rgba.as_raw()));
+    let palette_len = if quantizer.is_some() { 256 } else { 0 };
+    let image_size = (xor_stride + and_stride) * h as usize;
+
+    let mut data = Vec::with_capacity(40 + palette_len * 4 + image_size);
+    // BITMAPINFOHEADER — height counts both the XOR and AND bitmaps
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&(w as i32).to_le_bytes());
+    data.extend_from_slice(&((h * 2) as i32).to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes()); // planes
+    data.extend_from_slice(&(bpp as u16).to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes()); // BI_RGB
+    data.extend_from_slice(&(image_size as u32).to_le_bytes());
+    data.extend_from_slice(&0i32.to_le_bytes()); // x pixels per meter
+    data.extend_from_slice(&0i32.to_le_bytes()); // y pixels per meter
+    data.extend_from_slice(&(palette_len as u32).to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes()); // important colors
+
+    // Palette as RGBQUAD (B, G, R, reserved)
+    if let Some(nq) = &quantizer {
+        let map = nq.color_map_rgba();
+        for i in 0..palette_len {
+            let c = map.get(i * 4..i * 4 + 4).unwrap_or(&[0, 0, 0, 0]);
+            data.extend_from_slice(&[c[2], c[1], c[0], 0]);
+        }
+    }
+
+    // XOR bitmap, bottom-up
+    for y in (0..h).rev() {
+        let row_start = data.len();
+        for x in 0..w {
+            let p = rgba.get_pixel(x, y).0;
+            match &quantizer {
+                Some(nq) => data.push(nq.index_of(&p) as u8),
+                None => data.extend_from_slice(&[p[2], p[1], p[0]]),
+            }
+        }
+        data.resize(row_start + xor_stride, 0);
+    }
+
+    // AND mask, bottom-up: a set bit marks a transparent pixel
+    for y in (0..h).rev() {
+        let mut row = vec![0u8; and_stride];
+        for x in 0..w {
+            if rgba.get_pixel(x, y).0[3] < 128 {
+                row[(x / 8) as usize] |= 0x80 >> (x % 8);
+            }
+        }
+        data.extend_from_slice(&row);
+    }
+
+    data
+}
+
+/// Encode one ICO frame at the given size and color depth (8, 24 or 32).
+fn encode_ico_frame(source: &FaviconSource, size: u32, color_depth: u8) -> Result<Vec<u8>, String> {
+    match color_depth {
+        32 => render_png_bytes(source, size),
+        8 | 24 => {
+            let frame = source.render(size)?.to_rgba8();
+            Ok(encode_dib_frame(&frame, color_depth))
+        }
+        other => Err(format!(
+            "Unsupported ICO color depth: {} (expected 8, 24 or 32)",
+            other
+        )),
+    }
+}
+
+/// Sort and dedupe requested ICO sizes, rejecting any outside the format's 1–256 range.
+fn normalize_ico_sizes(sizes: &[u32]) -> Result<Vec<u32>, String> {
+    if let Some(bad) = sizes.iter().find(|&&s| s == 0 || s > 256) {
+        return Err(format!("ICO size {} is out of range (1-256)", bad));
+    }
+    let mut sizes = sizes.to_vec();
+    sizes.sort_unstable();
+    sizes.dedup();
+    if sizes.is_empty() {
+        return Err("No ICO sizes given".to_string());
+    }
+    Ok(sizes)
+}
+
 /// Build a minimal ICO file containing multiple sizes.
-/// ICO format: header (6 bytes) + entries (16 bytes each) + image data (PNG payloads)
-fn build_ico(img: &DynamicImage) -> Result<Vec<u8>, String> {
-    let mut png_payloads: Vec<(u32, Vec<u8>)> = Vec::new();
-    for &size in ICO_SIZES {
-        let png_data = resize_to_png_bytes(img, size, size)?;
-        png_payloads.push((size, png_data));
+/// ICO format: header (6 bytes) + entries (16 bytes each) + image data.
+/// 32-bit frames are PNG payloads; 8- and 24-bit frames are BMP DIBs for older readers.
+fn build_ico(source: &FaviconSource, sizes: &[u32], color_depth: u8) -> Result<Vec<u8>, String> {
+    let mut frames: Vec<(u32, Vec<u8>)> = Vec::new();
+    for &size in sizes {
+        let frame_data = encode_ico_frame(source, size, color_depth)?;
+        frames.push((size, frame_data));
     }
 
-    let num_images = png_payloads.len() as u16;
+    let num_images = frames.len() as u16;
     let mut ico = Vec::new();
 
     // ICO header: reserved(2) + type=1(2) + count(2)
@@ -55,31 +241,81 @@ fn build_ico(img: &DynamicImage) -> Result<Vec<u8>, String> {
     ico.extend_from_slice(&num_images.to_le_bytes());
 
     // Calculate data offset: header(6) + entries(16 * count)
-    let header_size = 6 + 16 * png_payloads.len();
+    let header_size = 6 + 16 * frames.len();
     let mut data_offset = header_size;
 
     // Write directory entries
-    for (size, png_data) in &png_payloads {
+    for (size, png_data) in &frames {
         let dim = if *size >= 256 { 0u8 } else { *size as u8 };
         ico.push(dim); // width
         ico.push(dim); // height
         ico.push(0); // color palette count
         ico.push(0); // reserved
         ico.extend_from_slice(&1u16.to_le_bytes()); // color planes
-        ico.extend_from_slice(&32u16.to_le_bytes()); // bits per pixel
+        ico.extend_from_slice(&(color_depth as u16).to_le_bytes()); // bits per pixel
         ico.extend_from_slice(&(png_data.len() as u32).to_le_bytes()); // data size
         ico.extend_from_slice(&(data_offset as u32).to_le_bytes()); // data offset
         data_offset += png_data.len();
     }
 
-    // Write PNG payloads
-    for (_, png_data) in &png_payloads {
+    // Write frame payloads
+    for (_, png_data) in &frames {
         ico.extend_from_slice(png_data);
     }
 
     Ok(ico)
 }
 
+/// Compose the 1200×630 OG image: the logo scaled to `logo_scale` of the canvas
+/// width (capped at the canvas height), centered on a solid background.
+fn render_og_image(
+    source: &FaviconSource,
+    background: [u8; 3],
+    logo_scale: f32,
+) -> Result<RgbaImage, String> {
+    let (width, height) = OG_IMAGE_SIZE;
+    let logo_width = ((width as f32 * logo_scale).round() as u32).clamp(1, width);
+    let logo = source.render_fit(logo_width, height)?.to_rgba8();
+
+    let [r, g, b] = background;
+    let mut canvas = RgbaImage::from_pixel(width, height, image::Rgba([r, g, b, 255]));
+    let x = (width as i64 - logo.width() as i64) / 2;
+    let y = (height as i64 - logo.height() as i64) / 2;
+
+    if background != DEFAULT_OG_BACKGROUND {
+        // Pad by ~3σ so the blur can fade out past the logo's edges
+        let pad = (OG_SHADOW_BLUR_SIGMA * 3.0).ceil() as u32;
+        let mut shadow = RgbaImage::new(logo.width() + 2 * pad, logo.height() + 2 * pad);
+        for (lx, ly, src) in logo.enumerate_pixels() {
+            let alpha = (src[3] as f32 * OG_SHADOW_OPACITY).round() as u8;
+            shadow.put_pixel(lx + pad, ly + pad, image::Rgba([0, 0, 0, alpha]));
+        }
+        let shadow = image::imageops::blur(&shadow, OG_SHADOW_BLUR_SIGMA);
+        image::imageops::overlay(
+            &mut canvas,
+            &shadow,
+            x + OG_SHADOW_OFFSET - pad as i64,
+            y + OG_SHADOW_OFFSET - pad as i64,
+        );
+    }
+
+    image::imageops::overlay(&mut canvas, &logo, x, y);
+    Ok(canvas)
+}
+
+fn render_og_png_bytes(
+    source: &FaviconSource,
+    background: [u8; 3],
+    logo_scale: f32,
+) -> Result<Vec<u8>, String> {
+    let canvas = render_og_image(source, background, logo_scale)?;
+    let mut buf = Cursor::new(Vec::new());
+    canvas
+        .write_to(&mut buf, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode OG image: {}", e))?;
+    Ok(buf.into_inner())
+}
+
 fn generate_webmanifest() -> String {
     serde_json::json!({
         "name": "",
@@ -103,8 +339,15 @@ fn generate_webmanifest() -> String {
     .to_string()
 }
 
+/// Generate the favicon set (PNG sizes, favicon.ico, OG image, webmanifest) as a ZIP.
+/// SVG inputs are rasterized at each size; `svg_override_size` renders them once
+/// at that viewport size and downscales instead.
 pub fn generate_favicons(
     image_path: &str,
+    ico_color_depth: u8,
+    svg_override_size: Option<u32>,
+    og_background: [u8; 3],
+    og_logo_scale: f32,
     output_dir: &str,
     app_handle: &tauri::AppHandle,
 ) -> FaviconResult {
@@ -120,12 +363,10 @@ pub fn generate_favicons(
         return result;
     }
 
-    let img = match image::open(image_path) {
-        Ok(i) => i,
+    let source = match FaviconSource::open(image_path, svg_override_size) {
+        Ok(s) => s,
         Err(e) => {
-            result
-                .errors
-                .push(format!("Cannot open '{}': {}", image_path, e));
+            result.errors.push(e);
             return result;
         }
     };
@@ -145,13 +386,13 @@ pub fn generate_favicons(
     let mut zip = ZipWriter::new(zip_file);
     let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
 
-    // Total steps: PNG sizes + favicon.ico + site.webmanifest
-    let total_steps = FAVICON_SIZES.len() + 2;
+    // Total steps: PNG sizes + favicon.ico + og-image.png + site.webmanifest
+    let total_steps = FAVICON_SIZES.len() + 3;
     let mut step = 0;
 
     // Generate PNG sizes
-    for (filename, w, h) in FAVICON_SIZES {
-        match resize_to_png_bytes(&img, *w, *h) {
+    for (filename, size) in FAVICON_SIZES {
+        match render_png_bytes(&source, *size) {
             Ok(png_data) => {
                 if let Err(e) = zip.start_file(*filename, options) {
                     result.errors.push(format!("{}: {}", filename, e));
@@ -172,7 +413,7 @@ pub fn generate_favicons(
     }
 
     // Generate favicon.ico
-    match build_ico(&img) {
+    match build_ico(&source, ICO_SIZES, ico_color_depth) {
         Ok(ico_data) => {
             if let Err(e) = zip.start_file("favicon.ico", options) {
                 result.errors.push(format!("favicon.ico: {}", e));
@@ -189,6 +430,24 @@ pub fn generate_favicons(
     step += 1;
     emit_progress_simple(app_handle, step, total_steps, "favicon.ico");
 
+    // Generate og-image.png
+    match render_og_png_bytes(&source, og_background, og_logo_scale) {
+        Ok(png_data) => {
+            if let Err(e) = zip.start_file("og-image.png", options) {
+                result.errors.push(format!("og-image.png: {}", e));
+            } else if let Err(e) = zip.write_all(&png_data) {
+                result.errors.push(format!("og-image.png: {}", e));
+            } else {
+                result.generated_files.push("og-image.png".to_string());
+            }
+        }
+        Err(e) => {
+            result.errors.push(format!("og-image.png: {}", e));
+        }
+    }
+    step += 1;
+    emit_progress_simple(app_handle, step, total_steps, "og-image.png");
+
     // Generate site.webmanifest
     let manifest = generate_webmanifest();
     if let Err(e) = zip.start_file("site.webmanifest", options) {
@@ -209,6 +468,49 @@ pub fn generate_favicons(
     result
 }
 
+/// Convert one image (raster or SVG) straight to a multi-size `{stem}.ico`.
+pub fn image_to_ico(image_path: &str, sizes: &[u32], output_dir: &str) -> IcoResult {
+    let mut result = IcoResult {
+        output_path: String::new(),
+        sizes_included: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let sizes = match normalize_ico_sizes(sizes) {
+        Ok(s) => s,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let ico_data = match FaviconSource::open(image_path, None)
+        .and_then(|source| build_ico(&source, &sizes, DEFAULT_ICO_COLOR_DEPTH))
+    {
+        Ok(data) => data,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
+    let output_path = out_dir.join(format!("{}.ico", get_file_stem(image_path)));
+    match std::fs::write(&output_path, ico_data) {
+        Ok(()) => {
+            result.output_path = output_path.to_string_lossy().to_string();
+            result.sizes_included = sizes;
+        }
+        Err(e) => result.errors.push(format!("Cannot write ICO: {}", e)),
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -230,10 +532,105 @@ mod tests {
         assert!(FAVICON_SIZES.len() >= 4);
         assert!(FAVICON_SIZES
             .iter()
-            .any(|(name, _, _)| *name == "apple-touch-icon.png"));
+            .any(|(name, _)| *name == "apple-touch-icon.png"));
         assert!(FAVICON_SIZES
             .iter()
-            .any(|(name, _, _)| *name == "favicon-16x16.png"));
+            .any(|(name, _)| *name == "favicon-16x16.png"));
+    }
+
+    #[test]
+    fn dib_frame_has_expected_layout() {
+        let img = RgbaImage::from_pixel(16, 16, image::Rgba([10, 20, 30, 255]));
+        let frame = encode_dib_frame(&img, 24);
+        // header + 16 rows of 48 bytes + 16 rows of 4-byte mask
+        assert_eq!(frame.len(), 40 + 16 * 48 + 16 * 4);
+        assert_eq!(&frame[40..43], &[30, 20, 10]);
+
+        let frame = encode_dib_frame(&img, 8);
+        assert_eq!(frame.len(), 40 + 256 * 4 + 16 * 16 + 16 * 4);
+    }
+
+    #[test]
+    fn dib_mask_marks_transparent_pixels() {
+        let mut img = RgbaImage::from_pixel(16, 1, image::Rgba([0, 0, 0, 255]));
+        img.put_pixel(0, 0, image::Rgba([0, 0, 0, 0]));
+        let frame = encode_dib_frame(&img, 24);
+        let mask_start = 40 + dib_row_stride(16, 24);
+        assert_eq!(frame[mask_start], 0x80);
+    }
+
+    #[test]
+    fn og_image_centers_logo_on_background() {
+        let logo = RgbaImage::from_pixel(100, 50, image::Rgba([255, 0, 0, 255]));
+        let source = FaviconSource::Raster(DynamicImage::ImageRgba8(logo));
+        let canvas = render_og_image(&source, [255, 255, 255], 0.5).unwrap();
+        assert_eq!(canvas.dimensions(), OG_IMAGE_SIZE);
+        assert_eq!(canvas.get_pixel(0, 0), &image::Rgba([255, 255, 255, 255]));
+        assert_eq!(canvas.get_pixel(600, 315), &image::Rgba([255, 0, 0, 255]));
+        // 600x300 logo centered: x 300..900, y 165..465
+        assert_eq!(
+            canvas.get_pixel(299, 315),
+            &image::Rgba([255, 255, 255, 255])
+        );
+        assert_eq!(
+            canvas.get_pixel(600, 470),
+            &image::Rgba([255, 255, 255, 255])
+        );
+    }
+
+    #[test]
+    fn og_image_shadow_only_on_colored_background() {
+        let logo = RgbaImage::from_pixel(100, 50, image::Rgba([255, 0, 0, 255]));
+        let source = FaviconSource::Raster(DynamicImage::ImageRgba8(logo));
+        let bg = [0, 0, 200];
+        let canvas = render_og_image(&source, bg, 0.5).unwrap();
+        assert_eq!(canvas.get_pixel(0, 0), &image::Rgba([0, 0, 200, 255]));
+        // Just below the logo's bottom edge the shadow darkens the background,
+        // and the blur fades out beyond the shadow's own edge.
+        let below = canvas.get_pixel(600, 468);
+        assert!(below[2] < 200);
+        let faded = canvas.get_pixel(600, 476);
+        assert!(faded[2] < 200 && faded[2] > below[2]);
+    }
+
+    #[test]
+    fn build_ico_rejects_unknown_depth() {
+        let source = FaviconSource::Raster(DynamicImage::new_rgba8(4, 4));
+        assert!(build_ico(&source, ICO_SIZES, 16).is_err());
+        let ico = build_ico(&source, ICO_SIZES, 24).unwrap();
+        // First directory entry's bits-per-pixel field
+        assert_eq!(u16::from_le_bytes([ico[12], ico[13]]), 24);
+    }
+
+    #[test]
+    fn svg_source_renders_each_size_exactly() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="64" height="64">
+            <circle cx="32" cy="32" r="30" fill="#000"/>
+        </svg>"##;
+        for override_size in [None, Some(128)] {
+            let source = FaviconSource::Svg {
+                tree: parse_svg(svg).unwrap(),
+                override_size,
+            };
+            let frame = source.render(16).unwrap();
+            assert_eq!((frame.width(), frame.height()), (16, 16));
+        }
+    }
+
+    #[test]
+    fn ico_sizes_are_normalized() {
+        assert_eq!(
+            normalize_ico_sizes(&[256, 16, 32, 16]).unwrap(),
+            vec![16, 32, 256]
+        );
+        assert!(normalize_ico_sizes(&[16, 512]).is_err());
+        assert!(normalize_ico_sizes(&[]).is_err());
+
+        let source = FaviconSource::Raster(DynamicImage::new_rgba8(8, 8));
+        let ico = build_ico(&source, DEFAULT_ICO_FILE_SIZES, 32).unwrap();
+        assert_eq!(u16::from_le_bytes([ico[4], ico[5]]), 4);
+        // 256px frames are stored with a 0 dimension byte
+        assert_eq!(ico[6 + 3 * 16], 0);
     }
 
     #[test]
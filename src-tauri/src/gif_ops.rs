@@ -1,17 +1,24 @@
-use gif::{Encoder, Frame, Repeat};
-use image::GenericImageView;
+use gif::{ColorOutput, DecodeOptions, Encoder, Frame, Repeat};
+use image::{GenericImageView, Rgb, RgbImage};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::fs::File;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::progress::emit_progress_simple;
-use crate::utils::ensure_output_dir;
+use crate::utils::{ensure_output_dir, file_size, file_stem};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AnimationResult {
     pub output_path: String,
     pub frame_count: usize,
     pub format: String,
+    /// Average frame delay (centiseconds) before and after a timing change, when applicable.
+    pub original_avg_delay_cs: Option<f32>,
+    pub new_avg_delay_cs: Option<f32>,
     pub errors: Vec<String>,
 }
 
@@ -21,6 +28,8 @@ pub fn create_gif(
     image_paths: &[String],
     delay_ms: u16,
     loop_count: u16,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
     output_dir: &str,
     app_handle: &tauri::AppHandle,
 ) -> AnimationResult {
@@ -28,6 +37,8 @@ pub fn create_gif(
         output_path: String::new(),
         frame_count: 0,
         format: "gif".to_string(),
+        original_avg_delay_cs: None,
+        new_avg_delay_cs: None,
         errors: Vec::new(),
     };
 
@@ -53,7 +64,7 @@ pub fn create_gif(
         }
     };
 
-    let (width, height) = first_img.dimensions();
+    let (width, height) = cap_dimensions(first_img.dimensions(), max_width, max_height);
 
     if width > u16::MAX as u32 || height > u16::MAX as u32 {
         result.errors.push(format!(
@@ -69,39 +80,15 @@ pub fn create_gif(
     let gif_height = height as u16;
 
     let output_path = out_dir.join("animation.gif");
-    let file = match File::create(&output_path) {
-        Ok(f) => f,
-        Err(e) => {
-            result
-                .errors
-                .push(format!("Cannot create output file: {}", e));
-            return result;
-        }
-    };
-
-    let mut encoder = match Encoder::new(file, gif_width, gif_height, &[]) {
+    let mut encoder = match open_gif_encoder(&output_path, gif_width, gif_height, loop_count) {
         Ok(enc) => enc,
         Err(e) => {
-            result
-                .errors
-                .push(format!("Cannot create GIF encoder: {}", e));
+            result.errors.push(e);
             return result;
         }
     };
 
-    // Set repeat behavior
-    let repeat = if loop_count == 0 {
-        Repeat::Infinite
-    } else {
-        Repeat::Finite(loop_count)
-    };
-    if let Err(e) = encoder.set_repeat(repeat) {
-        result.errors.push(format!("Cannot set loop: {}", e));
-        return result;
-    }
-
-    // GIF delay is in centiseconds (1/100th of a second)
-    let delay_cs = (delay_ms / 10).max(1);
+    let delay_cs = gif_delay_cs(delay_ms);
 
     for (i, path) in image_paths.iter().enumerate() {
         let img = match image::open(path) {
@@ -112,7 +99,7 @@ pub fn create_gif(
             }
         };
 
-        // Resize to match first frame dimensions
+        // Resize to match first frame dimensions (after the max cap)
         let resized = img.resize_exact(
             gif_width as u32,
             gif_height as u32,
@@ -139,3 +126,533 @@ pub fn create_gif(
     result.output_path = output_path.to_string_lossy().to_string();
     result
 }
+
+/// GIF delay is in centiseconds (1/100th of a second); never below one tick.
+pub(crate) fn gif_delay_cs(delay_ms: u16) -> u16 {
+    (delay_ms / 10).max(1)
+}
+
+/// Create `output_path` with a GIF encoder for a `width × height` canvas.
+/// A `loop_count` of 0 loops forever.
+pub(crate) fn open_gif_encoder(
+    output_path: &Path,
+    width: u16,
+    height: u16,
+    loop_count: u16,
+) -> Result<Encoder<File>, String> {
+    let file =
+        File::create(output_path).map_err(|e| format!("Cannot create output file: {}", e))?;
+    let mut encoder = Encoder::new(file, width, height, &[])
+        .map_err(|e| format!("Cannot create GIF encoder: {}", e))?;
+    let repeat = if loop_count == 0 {
+        Repeat::Infinite
+    } else {
+        Repeat::Finite(loop_count)
+    };
+    encoder
+        .set_repeat(repeat)
+        .map_err(|e| format!("Cannot set loop: {}", e))?;
+    Ok(encoder)
+}
+
+/// Scale `(width, height)` down proportionally so it fits the optional maximums.
+/// Dimensions that already fit are returned unchanged.
+fn cap_dimensions(
+    (width, height): (u32, u32),
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> (u32, u32) {
+    let scale_w = max_width.map_or(1.0, |m| m as f64 / width.max(1) as f64);
+    let scale_h = max_height.map_or(1.0, |m| m as f64 / height.max(1) as f64);
+    let scale = scale_w.min(scale_h);
+    if scale >= 1.0 {
+        return (width, height);
+    }
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+// --- GIF Speed ---
+
+/// Scale a frame delay (centiseconds) for a playback speed factor, clamped to the GIF range.
+fn scale_delay(delay_cs: u16, speed_factor: f32) -> u16 {
+    (delay_cs as f32 / speed_factor).round().clamp(1.0, 65535.0) as u16
+}
+
+fn average_delay(frames: &[Frame<'static>]) -> f32 {
+    if frames.is_empty() {
+        return 0.0;
+    }
+    frames.iter().map(|f| f.delay as f32).sum::<f32>() / frames.len() as f32
+}
+
+/// Change GIF playback speed by scaling every frame delay by `1 / speed_factor`.
+/// Frames are copied as indexed data, so pixels, palettes, and disposal methods are untouched.
+pub fn gif_speed(
+    gif_path: &str,
+    speed_factor: f32,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> AnimationResult {
+    let mut result = AnimationResult {
+        output_path: String::new(),
+        frame_count: 0,
+        format: "gif".to_string(),
+        original_avg_delay_cs: None,
+        new_avg_delay_cs: None,
+        errors: Vec::new(),
+    };
+
+    if !speed_factor.is_finite() || speed_factor <= 0.0 {
+        result
+            .errors
+            .push("Speed factor must be greater than zero".to_string());
+        return result;
+    }
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let file = match File::open(gif_path) {
+        Ok(f) => f,
+        Err(e) => {
+            result.errors.push(format!("Cannot open GIF: {}", e));
+            return result;
+        }
+    };
+
+    let mut options = DecodeOptions::new();
+    options.set_color_output(ColorOutput::Indexed);
+    let mut decoder = match options.read_info(file) {
+        Ok(d) => d,
+        Err(e) => {
+            result.errors.push(format!("Cannot decode GIF: {}", e));
+            return result;
+        }
+    };
+
+    let mut frames: Vec<Frame<'static>> = Vec::new();
+    loop {
+        match decoder.read_next_frame() {
+            Ok(Some(frame)) => frames.push(frame.clone()),
+            Ok(None) => break,
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Frame {}: {}", frames.len() + 1, e));
+                break;
+            }
+        }
+    }
+
+    if frames.is_empty() {
+        result.errors.push("GIF contains no frames".to_string());
+        return result;
+    }
+
+    let (width, height) = (decoder.width(), decoder.height());
+    let global_palette = decoder.global_palette().unwrap_or(&[]).to_vec();
+    let repeat = decoder.repeat();
+
+    result.original_avg_delay_cs = Some(average_delay(&frames));
+    for frame in frames.iter_mut() {
+        frame.delay = scale_delay(frame.delay, speed_factor);
+    }
+    result.new_avg_delay_cs = Some(average_delay(&frames));
+
+    let output_path = out_dir.join(format!("{}-speed.gif", file_stem(gif_path)));
+    let out_file = match File::create(&output_path) {
+        Ok(f) => f,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot create output file: {}", e));
+            return result;
+        }
+    };
+
+    let mut encoder = match Encoder::new(out_file, width, height, &global_palette) {
+        Ok(enc) => enc,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot create GIF encoder: {}", e));
+            return result;
+        }
+    };
+    if let Err(e) = encoder.set_repeat(repeat) {
+        result.errors.push(format!("Cannot set loop: {}", e));
+        return result;
+    }
+
+    let total = frames.len();
+    for (i, frame) in frames.iter().enumerate() {
+        if let Err(e) = encoder.write_frame(frame) {
+            result
+                .errors
+                .push(format!("Frame {}: write error — {}", i + 1, e));
+            continue;
+        }
+        result.frame_count += 1;
+        emit_progress_simple(app_handle, i + 1, total, gif_path);
+    }
+
+    result.output_path = output_path.to_string_lossy().to_string();
+    result
+}
+
+// --- GIF Info ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GifInfo {
+    pub width: u16,
+    pub height: u16,
+    pub frame_count: usize,
+    /// 0 means loop forever.
+    pub loop_count: u16,
+    pub frame_delays_cs: Vec<u16>,
+    pub file_size_bytes: u64,
+}
+
+/// Read GIF dimensions, loop count, and per-frame timing.
+/// LZW image data is skipped rather than decompressed, so this stays fast on large files.
+pub fn gif_info(gif_path: &str) -> Result<GifInfo, String> {
+    let file = File::open(gif_path).map_err(|e| format!("Cannot open GIF: {}", e))?;
+
+    let mut options = DecodeOptions::new();
+    options.skip_frame_decoding(true);
+    let mut decoder = options
+        .read_info(BufReader::new(file))
+        .map_err(|e| format!("Cannot read GIF: {}", e))?;
+
+    let mut frame_delays_cs = Vec::new();
+    while let Some(frame) = decoder
+        .next_frame_info()
+        .map_err(|e| format!("Frame {}: {}", frame_delays_cs.len() + 1, e))?
+    {
+        frame_delays_cs.push(frame.delay);
+    }
+
+    // The NETSCAPE loop extension is only known once the blocks have been walked
+    let loop_count = match decoder.repeat() {
+        Repeat::Infinite => 0,
+        Repeat::Finite(n) => n,
+    };
+
+    Ok(GifInfo {
+        width: decoder.width(),
+        height: decoder.height(),
+        frame_count: frame_delays_cs.len(),
+        loop_count,
+        frame_delays_cs,
+        file_size_bytes: file_size(gif_path),
+    })
+}
+
+// --- Slideshow (MP4) ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SlideshowResult {
+    pub output_path: String,
+    pub frame_count: usize,
+    pub duration_ms: u64,
+    pub file_size_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// Output frame rate; each slide is repeated to fill its hold time.
+const SLIDESHOW_FPS: u32 = 30;
+
+/// Map a 1–100 quality setting onto x264's CRF scale (51 = worst, 0 = lossless).
+fn quality_to_crf(quality: u32) -> u32 {
+    51 - quality.clamp(1, 100) * 51 / 100
+}
+
+/// H.264 with yuv420p needs even frame dimensions.
+fn even_dimensions(width: u32, height: u32) -> (u32, u32) {
+    ((width & !1).max(2), (height & !1).max(2))
+}
+
+/// Fit an image inside `width`x`height`, letterboxed on black.
+fn letterbox_frame(img: &image::DynamicImage, width: u32, height: u32) -> RgbImage {
+    let fitted = img
+        .resize(width, height, image::imageops::FilterType::Lanczos3)
+        .to_rgb8();
+    let mut canvas = RgbImage::from_pixel(width, height, Rgb([0, 0, 0]));
+    let x = (width - fitted.width()) / 2;
+    let y = (height - fitted.height()) / 2;
+    image::imageops::overlay(&mut canvas, &fitted, x as i64, y as i64);
+    canvas
+}
+
+/// Build an ffmpeg concat-demuxer script holding each frame for `frame_duration_ms`.
+/// The last file is listed twice because the demuxer ignores the final duration otherwise.
+fn concat_script(frame_names: &[String], frame_duration_ms: u32) -> String {
+    let seconds = frame_duration_ms as f64 / 1000.0;
+    let mut script = String::from("ffconcat version 1.0\n");
+    for name in frame_names {
+        script.push_str(&format!("file '{}'\nduration {:.3}\n", name, seconds));
+    }
+    if let Some(last) = frame_names.last() {
+        script.push_str(&format!("file '{}'\n", last));
+    }
+    script
+}
+
+fn ensure_ffmpeg() -> Result<(), String> {
+    match Command::new("ffmpeg").arg("-version").output() {
+        Ok(out) if out.status.success() => Ok(()),
+        Ok(_) => Err("ffmpeg is installed but failed to run".to_string()),
+        Err(e) if e.kind() == ErrorKind::NotFound => Err(
+            "ffmpeg was not found. Install ffmpeg and make sure it is on your PATH to create videos"
+                .to_string(),
+        ),
+        Err(e) => Err(format!("Cannot run ffmpeg: {}", e)),
+    }
+}
+
+/// Encode a batch of images as an H.264 MP4 slideshow using the ffmpeg CLI.
+/// Frames are letterboxed to `resolution` (default: first image's size) and
+/// each is held for `frame_duration_ms`.
+pub fn create_slideshow(
+    input_paths: &[String],
+    frame_duration_ms: u32,
+    output_path: &str,
+    resolution: Option<(u32, u32)>,
+    quality: u32,
+    app_handle: &tauri::AppHandle,
+) -> SlideshowResult {
+    let mut result = SlideshowResult {
+        output_path: String::new(),
+        frame_count: 0,
+        duration_ms: 0,
+        file_size_bytes: 0,
+        errors: Vec::new(),
+    };
+
+    if input_paths.is_empty() {
+        result.errors.push("No images provided".to_string());
+        return result;
+    }
+    if let Err(e) = ensure_ffmpeg() {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut output = PathBuf::from(output_path);
+    if output.extension().is_none() {
+        output.set_extension("mp4");
+    }
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Err(e) = ensure_output_dir(parent) {
+            result.errors.push(e);
+            return result;
+        }
+    }
+
+    let (width, height) = match resolution {
+        Some((w, h)) => even_dimensions(w, h),
+        None => match image::open(&input_paths[0]) {
+            Ok(img) => even_dimensions(img.width(), img.height()),
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Cannot open first image: {}", e));
+                return result;
+            }
+        },
+    };
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let work_dir = std::env::temp_dir().join(format!(
+        "rustine-slideshow-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+    if let Err(e) = fs::create_dir_all(&work_dir) {
+        result
+            .errors
+            .push(format!("Cannot create temporary directory: {}", e));
+        return result;
+    }
+
+    let encoded = encode_slideshow(
+        input_paths,
+        frame_duration_ms,
+        &output,
+        width,
+        height,
+        quality,
+        &work_dir,
+        app_handle,
+        &mut result,
+    );
+    let _ = fs::remove_dir_all(&work_dir);
+
+    match encoded {
+        Ok(frame_count) => {
+            result.frame_count = frame_count;
+            result.duration_ms = frame_count as u64 * frame_duration_ms as u64;
+            result.output_path = output.to_string_lossy().to_string();
+            result.file_size_bytes = file_size(&result.output_path);
+        }
+        Err(e) => result.errors.push(e),
+    }
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn encode_slideshow(
+    input_paths: &[String],
+    frame_duration_ms: u32,
+    output: &Path,
+    width: u32,
+    height: u32,
+    quality: u32,
+    work_dir: &Path,
+    app_handle: &tauri::AppHandle,
+    result: &mut SlideshowResult,
+) -> Result<usize, String> {
+    let total = input_paths.len();
+    let written: Vec<Result<String, String>> = input_paths
+        .par_iter()
+        .enumerate()
+        .map(|(i, path)| {
+            let img = image::open(path).map_err(|e| format!("Frame {}: {}", i + 1, e))?;
+            let name = format!("frame_{:05}.png", i + 1);
+            letterbox_frame(&img, width, height)
+                .save(work_dir.join(&name))
+                .map_err(|e| format!("Frame {}: write error — {}", i + 1, e))?;
+            emit_progress_simple(app_handle, i + 1, total, path);
+            Ok(name)
+        })
+        .collect();
+
+    let mut frame_names = Vec::new();
+    for entry in written {
+        match entry {
+            Ok(name) => frame_names.push(name),
+            Err(e) => result.errors.push(e),
+        }
+    }
+    if frame_names.is_empty() {
+        return Err("No frames could be decoded".to_string());
+    }
+
+    let script_path = work_dir.join("frames.ffconcat");
+    fs::write(&script_path, concat_script(&frame_names, frame_duration_ms))
+        .map_err(|e| format!("Cannot write frame list: {}", e))?;
+
+    let crf = quality_to_crf(quality).to_string();
+    let fps = SLIDESHOW_FPS.to_string();
+    let out = Command::new("ffmpeg")
+        .args([
+            "-y",
+            "-loglevel",
+            "error",
+            "-f",
+            "concat",
+            "-safe",
+            "0",
+            "-i",
+        ])
+        .arg(&script_path)
+        .args([
+            "-c:v",
+            "libx264",
+            "-crf",
+            &crf,
+            "-preset",
+            "medium",
+            "-pix_fmt",
+            "yuv420p",
+            "-r",
+            &fps,
+            "-movflags",
+            "+faststart",
+        ])
+        .arg(output)
+        .output()
+        .map_err(|e| format!("Cannot run ffmpeg: {}", e))?;
+    if !out.status.success() {
+        return Err(format!(
+            "ffmpeg encoding failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+
+    Ok(frame_names.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cap_dimensions_scales_proportionally() {
+        assert_eq!(
+            cap_dimensions((1600, 1200), Some(800), Some(600)),
+            (800, 600)
+        );
+        assert_eq!(cap_dimensions((1600, 900), Some(800), None), (800, 450));
+        assert_eq!(
+            cap_dimensions((1000, 2000), Some(800), Some(600)),
+            (300, 600)
+        );
+        assert_eq!(cap_dimensions((640, 480), Some(800), Some(600)), (640, 480));
+        assert_eq!(cap_dimensions((640, 480), None, None), (640, 480));
+    }
+
+    #[test]
+    fn scale_delay_speeds_up_and_slows_down() {
+        assert_eq!(scale_delay(10, 2.0), 5);
+        assert_eq!(scale_delay(10, 0.1), 100);
+    }
+
+    #[test]
+    fn scale_delay_clamps_to_gif_range() {
+        assert_eq!(scale_delay(1, 10.0), 1);
+        assert_eq!(scale_delay(0, 1.0), 1);
+        assert_eq!(scale_delay(65535, 0.1), 65535);
+    }
+
+    #[test]
+    fn quality_maps_to_crf_range() {
+        assert_eq!(quality_to_crf(100), 0);
+        assert_eq!(quality_to_crf(1), 51);
+        assert_eq!(quality_to_crf(0), 51);
+        assert_eq!(quality_to_crf(80), 11);
+    }
+
+    #[test]
+    fn even_dimensions_rounds_down() {
+        assert_eq!(even_dimensions(1921, 1081), (1920, 1080));
+        assert_eq!(even_dimensions(1, 1), (2, 2));
+    }
+
+    #[test]
+    fn concat_script_repeats_last_frame() {
+        let names = vec!["a.png".to_string(), "b.png".to_string()];
+        assert_eq!(
+            concat_script(&names, 1500),
+            "ffconcat version 1.0\nfile 'a.png'\nduration 1.500\nfile 'b.png'\nduration 1.500\nfile 'b.png'\n"
+        );
+    }
+
+    #[test]
+    fn letterbox_frame_centers_image() {
+        let img = image::DynamicImage::ImageRgb8(RgbImage::from_pixel(4, 2, Rgb([255, 255, 255])));
+        let frame = letterbox_frame(&img, 4, 4);
+        assert_eq!(frame.dimensions(), (4, 4));
+        assert_eq!(frame.get_pixel(0, 0), &Rgb([0, 0, 0]));
+        assert_eq!(frame.get_pixel(0, 1), &Rgb([255, 255, 255]));
+    }
+}
@@ -1,16 +1,22 @@
 use ab_glyph::{FontArc, PxScale};
 use image::{DynamicImage, ImageFormat, ImageReader, Rgba};
-use imageproc::drawing::draw_text_mut;
+use imageproc::drawing::{draw_text_mut, text_size};
+use imageproc::geometric_transformations::{rotate_about_center, Interpolation};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, LazyLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread;
+use std::time::Duration;
 use webp::Encoder;
 
-use crate::progress::emit_progress;
-use crate::utils::{ensure_output_dir, file_size, file_stem, get_extension};
+use crate::cancel::CancelSignal;
+use crate::progress::{emit_progress, emit_progress_steps, emit_seam_progress};
+use crate::utils::{ensure_output_dir, file_size, file_stem, get_extension, write_atomic};
 
 /// Pixel margin from image edges for watermark placement.
 const WATERMARK_MARGIN_PX: i32 = 20;
@@ -36,6 +42,9 @@ pub struct BatchProgress {
     pub completed: usize,
     pub total: usize,
     pub results: Vec<ProcessingResult>,
+    /// Batch-level remarks, e.g. parameters that were overridden.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notes: Vec<String>,
 }
 
 impl BatchProgress {
@@ -58,39 +67,133 @@ impl BatchProgress {
                     output_height: 0,
                 })
                 .collect(),
+            notes: Vec::new(),
         }
     }
 }
 
+// --- Batch report ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchCsvResult {
+    pub output_path: String,
+    pub rows: usize,
+}
+
+#[derive(Serialize)]
+struct BatchCsvRow<'a> {
+    input_path: &'a str,
+    output_path: &'a str,
+    success: bool,
+    error: &'a str,
+    input_size_bytes: u64,
+    output_size_bytes: u64,
+    /// Empty for failed files and unknown input sizes.
+    size_reduction_pct: Option<String>,
+    input_width: u32,
+    input_height: u32,
+    output_width: u32,
+    output_height: u32,
+}
+
+/// Render batch results as CSV, one row per file with a header line.
+fn batch_results_csv(progress: &BatchProgress) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for r in &progress.results {
+        let reduction = (r.success && r.input_size > 0).then(|| {
+            let pct = (1.0 - r.output_size as f64 / r.input_size as f64) * 100.0;
+            format!("{:.2}", pct)
+        });
+        writer
+            .serialize(BatchCsvRow {
+                input_path: &r.input_path,
+                output_path: &r.output_path,
+                success: r.success,
+                error: r.error.as_deref().unwrap_or(""),
+                input_size_bytes: r.input_size,
+                output_size_bytes: r.output_size,
+                size_reduction_pct: reduction,
+                input_width: r.input_width,
+                input_height: r.input_height,
+                output_width: r.output_width,
+                output_height: r.output_height,
+            })
+            .map_err(|e| format!("Cannot write CSV row: {}", e))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| format!("Cannot finish CSV: {}", e))
+}
+
+/// Write a finished batch's results to `output_path` as a CSV report (atomically).
+/// Pure post-processing: no image is opened.
+pub fn batch_results_to_csv(
+    progress: &BatchProgress,
+    output_path: &str,
+) -> Result<BatchCsvResult, String> {
+    let csv = batch_results_csv(progress)?;
+    let output = Path::new(output_path);
+    if let Some(parent) = output.parent() {
+        ensure_output_dir(parent)?;
+    }
+    write_atomic(output, &csv)?;
+    Ok(BatchCsvResult {
+        output_path: output_path.to_string(),
+        rows: progress.results.len(),
+    })
+}
+
+/// Open and decode an image, sniffing the real format from its content so that
+/// misnamed files (e.g. a JPEG saved as `.png`) still decode.
 fn load_image(path: &str) -> Result<DynamicImage, String> {
     ImageReader::open(path)
+        .and_then(|r| r.with_guessed_format())
         .map_err(|e| format!("Cannot open file '{}': {}", path, e))?
         .decode()
         .map_err(|e| format!("Cannot decode image '{}': {}", path, e))
 }
 
+/// Read image dimensions from the header only, without decoding pixels.
+fn read_dimensions(path: &str) -> Result<(u32, u32), String> {
+    ImageReader::open(path)
+        .and_then(|r| r.with_guessed_format())
+        .map_err(|e| format!("Cannot open file '{}': {}", path, e))?
+        .into_dimensions()
+        .map_err(|e| format!("Cannot read image '{}': {}", path, e))
+}
+
+/// Encode images as WebP. With `lossless`, pixels (including alpha) are kept exactly,
+/// `quality` is ignored, and outputs use a `-lossless.webp` suffix.
 pub fn compress_to_webp(
     input_paths: Vec<String>,
     quality: f32,
+    lossless: bool,
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
     batch_process(
+        "compress_to_webp",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        move |input_path, out_dir| {
             let img = load_image(input_path)?;
             let rgba = img.to_rgba8();
             let (w, h) = rgba.dimensions();
 
             let encoder = Encoder::from_rgba(&rgba, w, h);
-            let webp_data = encoder.encode(quality);
+            let (webp_data, suffix) = if lossless {
+                (encoder.encode_lossless(), "lossless")
+            } else {
+                (encoder.encode(quality), "compressed")
+            };
 
             let stem = file_stem(input_path);
-            let output_path = out_dir.join(format!("{}-compressed.webp", stem));
+            let output_path = out_dir.join(format!("{}-{}.webp", stem, suffix));
             fs::write(&output_path, &*webp_data)
                 .map_err(|e| format!("Cannot write WebP file: {}", e))?;
 
@@ -105,15 +208,18 @@ pub fn compress_to_jpeg(
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
     let quality = quality.clamp(1, 100);
 
     batch_process(
+        "compress_to_jpeg",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        move |input_path, out_dir| {
             let img = load_image(input_path)?;
             let rgb = img.to_rgb8();
 
@@ -132,75 +238,588 @@ pub fn compress_to_jpeg(
     )
 }
 
+/// Codec settings for `convert_images`. Only the fields for the chosen output format
+/// are read; the defaults reproduce the historical output.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConvertOptions {
+    pub jpeg_quality: Option<u8>,
+    /// zlib-style level, 0 (fastest) to 9 (smallest).
+    pub png_compression: Option<u32>,
+    pub webp_quality: Option<f32>,
+    pub webp_lossless: Option<bool>,
+    /// "none", "lzw", "deflate" or "packbits".
+    pub tiff_compression: Option<String>,
+}
+
+const DEFAULT_CONVERT_JPEG_QUALITY: u8 = 75;
+const DEFAULT_CONVERT_PNG_COMPRESSION: u32 = 6;
+const DEFAULT_CONVERT_WEBP_QUALITY: f32 = 100.0;
+const DEFAULT_CONVERT_TIFF_COMPRESSION: &str = "none";
+
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            jpeg_quality: Some(DEFAULT_CONVERT_JPEG_QUALITY),
+            png_compression: Some(DEFAULT_CONVERT_PNG_COMPRESSION),
+            webp_quality: Some(DEFAULT_CONVERT_WEBP_QUALITY),
+            webp_lossless: Some(false),
+            tiff_compression: Some(DEFAULT_CONVERT_TIFF_COMPRESSION.to_string()),
+        }
+    }
+}
+
+fn png_compression_type(level: u32) -> image::codecs::png::CompressionType {
+    use image::codecs::png::CompressionType;
+    match level {
+        0..=3 => CompressionType::Fast,
+        4..=6 => CompressionType::Default,
+        _ => CompressionType::Best,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TiffCodec {
+    None,
+    Lzw,
+    Deflate,
+    Packbits,
+}
+
+impl TiffCodec {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "none" => Ok(Self::None),
+            "lzw" => Ok(Self::Lzw),
+            "deflate" => Ok(Self::Deflate),
+            "packbits" => Ok(Self::Packbits),
+            other => Err(format!("Unknown TIFF compression: {}", other)),
+        }
+    }
+
+    fn compression(self) -> tiff::encoder::Compression {
+        use tiff::encoder::{Compression, DeflateLevel};
+        match self {
+            Self::None => Compression::Uncompressed,
+            Self::Lzw => Compression::Lzw,
+            Self::Deflate => Compression::Deflate(DeflateLevel::Balanced),
+            Self::Packbits => Compression::Packbits,
+        }
+    }
+}
+
+/// `ConvertOptions` resolved against the defaults and clamped to valid ranges.
+#[derive(Debug, Clone, Copy)]
+struct ConvertSettings {
+    jpeg_quality: u8,
+    png_compression: image::codecs::png::CompressionType,
+    webp_quality: f32,
+    webp_lossless: bool,
+    tiff_codec: TiffCodec,
+}
+
+impl ConvertSettings {
+    fn resolve(options: &ConvertOptions) -> Result<Self, String> {
+        Ok(Self {
+            jpeg_quality: options
+                .jpeg_quality
+                .unwrap_or(DEFAULT_CONVERT_JPEG_QUALITY)
+                .clamp(1, 100),
+            png_compression: png_compression_type(
+                options
+                    .png_compression
+                    .unwrap_or(DEFAULT_CONVERT_PNG_COMPRESSION),
+            ),
+            webp_quality: options
+                .webp_quality
+                .unwrap_or(DEFAULT_CONVERT_WEBP_QUALITY)
+                .clamp(0.0, 100.0),
+            webp_lossless: options.webp_lossless.unwrap_or(false),
+            tiff_codec: TiffCodec::parse(
+                options
+                    .tiff_compression
+                    .as_deref()
+                    .unwrap_or(DEFAULT_CONVERT_TIFF_COMPRESSION),
+            )?,
+        })
+    }
+}
+
+/// Encode `data` as a TIFF of colortype `C` with the given compression.
+fn encode_tiff<C>(
+    path: &Path,
+    codec: TiffCodec,
+    width: u32,
+    height: u32,
+    data: &[C::Inner],
+) -> Result<(), String>
+where
+    C: tiff::encoder::colortype::ColorType,
+    [C::Inner]: tiff::encoder::TiffValue,
+{
+    let writer = create_output_file(path)?;
+    tiff::encoder::TiffEncoder::new(writer)
+        .map(|encoder| encoder.with_compression(codec.compression()))
+        .and_then(|mut encoder| encoder.write_image::<C>(width, height, data))
+        .map_err(|e| format!("Cannot save TIFF: {}", e))
+}
+
+/// Write a compressed TIFF, keeping the source's channels and bit depth where
+/// the encoder supports them (gray+alpha is widened to RGBA).
+fn write_compressed_tiff(img: &DynamicImage, path: &Path, codec: TiffCodec) -> Result<(), String> {
+    use image::ColorType;
+    use tiff::encoder::colortype;
+    let (w, h) = (img.width(), img.height());
+    match img.color() {
+        ColorType::L8 => {
+            encode_tiff::<colortype::Gray8>(path, codec, w, h, img.to_luma8().as_raw())
+        }
+        ColorType::L16 => {
+            encode_tiff::<colortype::Gray16>(path, codec, w, h, img.to_luma16().as_raw())
+        }
+        ColorType::Rgb8 => {
+            encode_tiff::<colortype::RGB8>(path, codec, w, h, img.to_rgb8().as_raw())
+        }
+        ColorType::Rgb16 => {
+            encode_tiff::<colortype::RGB16>(path, codec, w, h, img.to_rgb16().as_raw())
+        }
+        ColorType::La8 | ColorType::Rgba8 => {
+            encode_tiff::<colortype::RGBA8>(path, codec, w, h, img.to_rgba8().as_raw())
+        }
+        _ => encode_tiff::<colortype::RGBA16>(path, codec, w, h, img.to_rgba16().as_raw()),
+    }
+}
+
+fn create_output_file(path: &Path) -> Result<std::io::BufWriter<fs::File>, String> {
+    fs::File::create(path)
+        .map(std::io::BufWriter::new)
+        .map_err(|e| format!("Cannot create output file: {}", e))
+}
+
+/// Encode one image as `{stem}-converted.{format}`, returning the output path
+/// and the written dimensions.
+fn write_converted(
+    img: &DynamicImage,
+    stem: &str,
+    out_dir: &Path,
+    target_format: &str,
+    settings: &ConvertSettings,
+) -> Result<(String, (u32, u32)), String> {
+    let mut dims = (img.width(), img.height());
+    let output_path_str = match target_format {
+        "webp" => {
+            let rgba = img.to_rgba8();
+            let (w, h) = rgba.dimensions();
+            let encoder = Encoder::from_rgba(&rgba, w, h);
+            let webp_data = if settings.webp_lossless {
+                encoder.encode_lossless()
+            } else {
+                encoder.encode(settings.webp_quality)
+            };
+            let output_path = out_dir.join(format!("{}-converted.webp", stem));
+            fs::write(&output_path, &*webp_data)
+                .map_err(|e| format!("Cannot write WebP: {}", e))?;
+            output_path.to_string_lossy().to_string()
+        }
+        "png" => {
+            let output_path = out_dir.join(format!("{}-converted.png", stem));
+            let mut writer = create_output_file(&output_path)?;
+            let encoder = image::codecs::png::PngEncoder::new_with_quality(
+                &mut writer,
+                settings.png_compression,
+                image::codecs::png::FilterType::Adaptive,
+            );
+            img.write_with_encoder(encoder)
+                .map_err(|e| format!("Cannot save PNG: {}", e))?;
+            output_path.to_string_lossy().to_string()
+        }
+        "jpg" | "jpeg" => {
+            let output_path = out_dir.join(format!("{}-converted.jpg", stem));
+            let mut writer = create_output_file(&output_path)?;
+            let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(
+                &mut writer,
+                settings.jpeg_quality,
+            );
+            img.to_rgb8()
+                .write_with_encoder(encoder)
+                .map_err(|e| format!("Cannot save JPEG: {}", e))?;
+            output_path.to_string_lossy().to_string()
+        }
+        "bmp" => {
+            let output_path = out_dir.join(format!("{}-converted.bmp", stem));
+            img.save_with_format(&output_path, ImageFormat::Bmp)
+                .map_err(|e| format!("Cannot save BMP: {}", e))?;
+            output_path.to_string_lossy().to_string()
+        }
+        "ico" => {
+            let resized = img.resize(256, 256, image::imageops::FilterType::Lanczos3);
+            dims = (resized.width(), resized.height());
+            let output_path = out_dir.join(format!("{}-converted.ico", stem));
+            resized
+                .save_with_format(&output_path, ImageFormat::Ico)
+                .map_err(|e| format!("Cannot save ICO: {}", e))?;
+            output_path.to_string_lossy().to_string()
+        }
+        "tiff" | "tif" => {
+            let output_path = out_dir.join(format!("{}-converted.tiff", stem));
+            // Uncompressed output keeps the historical encoder path unchanged
+            if settings.tiff_codec == TiffCodec::None {
+                img.save_with_format(&output_path, ImageFormat::Tiff)
+                    .map_err(|e| format!("Cannot save TIFF: {}", e))?;
+            } else {
+                write_compressed_tiff(img, &output_path, settings.tiff_codec)?;
+            }
+            output_path.to_string_lossy().to_string()
+        }
+        _ => return Err(format!("Unsupported output format: {}", target_format)),
+    };
+    Ok((output_path_str, dims))
+}
+
+/// Input dimensions plus the per-format outcome (output path and dimensions).
+type ConvertedFile = ((u32, u32), Vec<Result<(String, (u32, u32)), String>>);
+
+/// Lowercase the requested formats, fold aliases that share an output file
+/// (`jpeg` → `jpg`, `tif` → `tiff`) and drop repeats, keeping the first occurrence.
+fn normalize_formats(output_formats: &[String]) -> Vec<String> {
+    let mut formats: Vec<String> = Vec::with_capacity(output_formats.len());
+    for format in output_formats {
+        let format = match format.trim().to_lowercase().as_str() {
+            "jpeg" => "jpg".to_string(),
+            "tif" => "tiff".to_string(),
+            other => other.to_string(),
+        };
+        if !format.is_empty() && !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    formats
+}
+
+/// Convert every input into each of `output_formats`. Each file is decoded once
+/// and encoded in every format; `results` holds one entry per input and format.
 pub fn convert_images(
     input_paths: Vec<String>,
-    output_format: String,
+    output_formats: Vec<String>,
+    convert_options: ConvertOptions,
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
-    let target_format = output_format.to_lowercase();
+    let settings = match ConvertSettings::resolve(&convert_options) {
+        Ok(s) => s,
+        Err(e) => return BatchProgress::all_failed(&input_paths, e),
+    };
+    let formats = normalize_formats(&output_formats);
+    if formats.is_empty() {
+        return BatchProgress::all_failed(&input_paths, "No output format given".to_string());
+    }
 
-    batch_process(
+    let format_count = formats.len();
+    let per_format: Arc<Mutex<HashMap<String, ConvertedFile>>> = Arc::default();
+    let outcomes = Arc::clone(&per_format);
+    let pass = batch_process_steps(
+        "convert_images",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        format_count,
+        move |input_path, out_dir| {
             let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
             let stem = file_stem(input_path);
+            let converted: Vec<Result<(String, (u32, u32)), String>> = formats
+                .iter()
+                .map(|format| write_converted(&img, &stem, out_dir, format, &settings))
+                .collect();
 
-            let output_path_str = match target_format.as_str() {
-                "webp" => {
-                    let rgba = img.to_rgba8();
-                    let (w, h) = rgba.dimensions();
-                    let encoder = Encoder::from_rgba(&rgba, w, h);
-                    let webp_data = encoder.encode(100.0);
-                    let output_path = out_dir.join(format!("{}-converted.webp", stem));
-                    fs::write(&output_path, &*webp_data)
-                        .map_err(|e| format!("Cannot write WebP: {}", e))?;
-                    output_path.to_string_lossy().to_string()
-                }
-                "png" => {
-                    let output_path = out_dir.join(format!("{}-converted.png", stem));
-                    img.save_with_format(&output_path, ImageFormat::Png)
-                        .map_err(|e| format!("Cannot save PNG: {}", e))?;
-                    output_path.to_string_lossy().to_string()
-                }
-                "jpg" | "jpeg" => {
-                    let output_path = out_dir.join(format!("{}-converted.jpg", stem));
-                    img.save_with_format(&output_path, ImageFormat::Jpeg)
-                        .map_err(|e| format!("Cannot save JPEG: {}", e))?;
-                    output_path.to_string_lossy().to_string()
-                }
-                "bmp" => {
-                    let output_path = out_dir.join(format!("{}-converted.bmp", stem));
-                    img.save_with_format(&output_path, ImageFormat::Bmp)
-                        .map_err(|e| format!("Cannot save BMP: {}", e))?;
-                    output_path.to_string_lossy().to_string()
-                }
-                "ico" => {
-                    let resized = img.resize(256, 256, image::imageops::FilterType::Lanczos3);
-                    let output_path = out_dir.join(format!("{}-converted.ico", stem));
-                    resized
-                        .save_with_format(&output_path, ImageFormat::Ico)
-                        .map_err(|e| format!("Cannot save ICO: {}", e))?;
-                    output_path.to_string_lossy().to_string()
-                }
-                "tiff" | "tif" => {
-                    let output_path = out_dir.join(format!("{}-converted.tiff", stem));
-                    img.save_with_format(&output_path, ImageFormat::Tiff)
-                        .map_err(|e| format!("Cannot save TIFF: {}", e))?;
-                    output_path.to_string_lossy().to_string()
-                }
-                _ => return Err(format!("Unsupported output format: {}", target_format)),
-            };
-
-            Ok((output_path_str, None))
+            let first_written = converted.iter().find_map(|c| c.as_ref().ok()).cloned();
+            let errors: Vec<String> = converted
+                .iter()
+                .filter_map(|c| c.as_ref().err().cloned())
+                .collect();
+            if let Ok(mut map) = outcomes.lock() {
+                map.insert(input_path.to_string(), ((w, h), converted));
+            }
+            match first_written {
+                Some((path, (ow, oh))) => Ok((path, Some((w, h, ow, oh)))),
+                None => Err(errors.join("; ")),
+            }
         },
+    );
+
+    // A timed-out worker may still record its outcomes, so only trust entries
+    // for inputs the batch reported as successful.
+    let per_format = match per_format.lock() {
+        Ok(map) => map.clone(),
+        Err(_) => HashMap::new(),
+    };
+    let results: Vec<ProcessingResult> = pass
+        .results
+        .into_iter()
+        .flat_map(|result| match per_format.get(&result.input_path) {
+            Some(((w, h), converted)) if result.success => converted
+                .iter()
+                .map(|outcome| match outcome {
+                    Ok((path, (ow, oh))) => build_result(
+                        &result.input_path,
+                        Ok(path.clone()),
+                        Some((*w, *h, *ow, *oh)),
+                    ),
+                    Err(e) => {
+                        build_result(&result.input_path, Err(e.clone()), Some((*w, *h, 0, 0)))
+                    }
+                })
+                .collect::<Vec<_>>(),
+            _ => vec![result; format_count],
+        })
+        .collect();
+    BatchProgress {
+        completed: results.iter().filter(|r| r.success).count(),
+        total: input_paths.len() * format_count,
+        results,
+        notes: pass.notes,
+    }
+}
+
+// --- Validation ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ValidationResult {
+    pub path: String,
+    pub valid: bool,
+    pub actual_format: Option<String>,
+    pub width: u32,
+    pub height: u32,
+    pub error: Option<String>,
+}
+
+/// Fully decode each file (not just its header) to detect corrupt or truncated images.
+/// Read-only, so every file is checked in parallel and failures never stop the batch.
+pub fn validate_images(input_paths: &[String]) -> Vec<ValidationResult> {
+    input_paths
+        .par_iter()
+        .map(|path| {
+            let actual_format = ImageReader::open(path)
+                .and_then(|r| r.with_guessed_format())
+                .ok()
+                .and_then(|r| r.format())
+                .map(|f| format!("{:?}", f).to_lowercase());
+
+            match load_image(path) {
+                Ok(img) => ValidationResult {
+                    path: path.clone(),
+                    valid: true,
+                    actual_format,
+                    width: img.width(),
+                    height: img.height(),
+                    error: None,
+                },
+                Err(e) => ValidationResult {
+                    path: path.clone(),
+                    valid: false,
+                    actual_format,
+                    width: 0,
+                    height: 0,
+                    error: Some(e),
+                },
+            }
+        })
+        .collect()
+}
+
+// --- Deduplication ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeduplicateResult {
+    /// Sets of near-identical files (two or more members each), in input order.
+    pub groups: Vec<Vec<String>>,
+    /// Files to keep: one per group plus every unique file.
+    pub kept: Vec<String>,
+    pub duplicates: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+pub const DEFAULT_DEDUP_THRESHOLD: u32 = 4;
+const PHASH_SAMPLE: usize = 32;
+const PHASH_BLOCK: usize = 8;
+
+/// Perceptual hash: 32×32 grayscale → 2D DCT-II → low-frequency 8×8 block,
+/// one bit per coefficient above the block median (DC term excluded from the median).
+fn perceptual_hash(img: &DynamicImage) -> u64 {
+    let n = PHASH_SAMPLE;
+    let gray = img
+        .resize_exact(n as u32, n as u32, image::imageops::FilterType::Triangle)
+        .to_luma8();
+    let pixels: Vec<f64> = gray.as_raw().iter().map(|&p| p as f64).collect();
+
+    // Only the first PHASH_BLOCK frequencies are needed along each axis
+    let cos_table: Vec<f64> = (0..PHASH_BLOCK)
+        .flat_map(|u| {
+            (0..n).map(move |x| {
+                (std::f64::consts::PI * (2 * x + 1) as f64 * u as f64 / (2 * n) as f64).cos()
+            })
+        })
+        .collect();
+    let rows: Vec<f64> = (0..n)
+        .flat_map(|y| {
+            let row = &pixels[y * n..(y + 1) * n];
+            let cos_table = &cos_table;
+            (0..PHASH_BLOCK).map(move |u| {
+                row.iter()
+                    .zip(&cos_table[u * n..(u + 1) * n])
+                    .map(|(p, c)| p * c)
+                    .sum::<f64>()
+            })
+        })
+        .collect();
+    let mut coeffs = [0f64; PHASH_BLOCK * PHASH_BLOCK];
+    for v in 0..PHASH_BLOCK {
+        for u in 0..PHASH_BLOCK {
+            coeffs[v * PHASH_BLOCK + u] = (0..n)
+                .map(|y| rows[y * PHASH_BLOCK + u] * cos_table[v * n + y])
+                .sum();
+        }
+    }
+
+    let mut ac: Vec<f64> = coeffs[1..].to_vec();
+    ac.sort_by(|a, b| a.total_cmp(b));
+    let median = ac[ac.len() / 2];
+    coeffs.iter().enumerate().fold(
+        0u64,
+        |hash, (i, &c)| if c > median { hash | (1 << i) } else { hash },
     )
 }
 
+struct UnionFind {
+    parent: Vec<usize>,
+    rank: Vec<u8>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return;
+        }
+        match self.rank[ra].cmp(&self.rank[rb]) {
+            std::cmp::Ordering::Less => self.parent[ra] = rb,
+            std::cmp::Ordering::Greater => self.parent[rb] = ra,
+            std::cmp::Ordering::Equal => {
+                self.parent[rb] = ra;
+                self.rank[ra] += 1;
+            }
+        }
+    }
+}
+
+/// Cluster hashes whose Hamming distance is within `threshold`; each group lists
+/// indices in ascending order and groups are ordered by their first member.
+fn group_by_hash(hashes: &[u64], threshold: u32) -> Vec<Vec<usize>> {
+    let mut uf = UnionFind::new(hashes.len());
+    for i in 0..hashes.len() {
+        for j in i + 1..hashes.len() {
+            if (hashes[i] ^ hashes[j]).count_ones() <= threshold {
+                uf.union(i, j);
+            }
+        }
+    }
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for i in 0..hashes.len() {
+        let root = uf.find(i);
+        let slot = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[slot].push(i);
+    }
+    groups
+}
+
+/// Find near-duplicate images by perceptual hash. Advisory only: nothing is deleted.
+/// `keep` picks the survivor of each group: "first", "last", "largest" or "smallest" (file size).
+pub fn deduplicate_images(
+    input_paths: &[String],
+    threshold: u32,
+    keep: &str,
+    app_handle: &tauri::AppHandle,
+) -> Result<DeduplicateResult, String> {
+    if !matches!(keep, "first" | "last" | "largest" | "smallest") {
+        return Err(format!("Unknown keep strategy: {}", keep));
+    }
+
+    let processed = AtomicUsize::new(0);
+    let total = input_paths.len();
+    let hashed: Vec<Result<u64, String>> = input_paths
+        .par_iter()
+        .map(|path| {
+            let hash = load_image(path).map(|img| perceptual_hash(&img));
+            emit_progress(app_handle, &processed, total, path);
+            hash
+        })
+        .collect();
+
+    let mut errors = Vec::new();
+    let mut paths: Vec<&String> = Vec::new();
+    let mut hashes: Vec<u64> = Vec::new();
+    for (path, hash) in input_paths.iter().zip(hashed) {
+        match hash {
+            Ok(h) => {
+                paths.push(path);
+                hashes.push(h);
+            }
+            Err(e) => errors.push(e),
+        }
+    }
+
+    let mut result = DeduplicateResult {
+        groups: Vec::new(),
+        kept: Vec::new(),
+        duplicates: Vec::new(),
+        errors,
+    };
+    for group in group_by_hash(&hashes, threshold) {
+        let keeper = match keep {
+            "last" => group.last().copied(),
+            "largest" => group.iter().copied().max_by_key(|&i| file_size(paths[i])),
+            "smallest" => group.iter().copied().min_by_key(|&i| file_size(paths[i])),
+            _ => group.first().copied(),
+        };
+        for &i in &group {
+            if Some(i) == keeper {
+                result.kept.push(paths[i].clone());
+            } else {
+                result.duplicates.push(paths[i].clone());
+            }
+        }
+        if group.len() > 1 {
+            result
+                .groups
+                .push(group.iter().map(|&i| paths[i].clone()).collect());
+        }
+    }
+    Ok(result)
+}
+
 // --- Shared helpers for new features ---
 
 fn save_in_original_format(
@@ -217,6 +836,11 @@ fn save_in_original_format(
             let webp_data = encoder.encode(90.0);
             fs::write(output_path, &*webp_data).map_err(|e| format!("Cannot write WebP: {}", e))
         }
+        "jpg" | "jpeg" if img.color().has_alpha() => {
+            DynamicImage::ImageRgb8(flatten_alpha(img, [255, 255, 255]))
+                .save_with_format(output_path, ImageFormat::Jpeg)
+                .map_err(|e| format!("Cannot save JPEG: {}", e))
+        }
         "jpg" | "jpeg" => img
             .save_with_format(output_path, ImageFormat::Jpeg)
             .map_err(|e| format!("Cannot save JPEG: {}", e)),
@@ -275,41 +899,180 @@ fn build_result(
 
 // emit_progress is imported from crate::progress
 
+/// Whether a batch may create a missing output directory.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CreateDirPolicy {
+    /// Create the directory (and parents) when missing.
+    #[default]
+    Auto,
+    /// Fail when the directory does not exist, so typos are not silently created.
+    RequireExists,
+}
+
+impl CreateDirPolicy {
+    pub fn parse(policy: Option<&str>) -> Result<Self, String> {
+        match policy {
+            None | Some("auto") => Ok(Self::Auto),
+            Some("require_exists") => Ok(Self::RequireExists),
+            Some(other) => Err(format!(
+                "Unknown directory policy: {} (expected \"auto\" or \"require_exists\")",
+                other
+            )),
+        }
+    }
+
+    /// Check (and with `Auto`, create) the output directory.
+    pub fn prepare(self, dir: &Path) -> Result<(), String> {
+        match self {
+            Self::Auto => ensure_output_dir(dir),
+            Self::RequireExists if dir.is_dir() => Ok(()),
+            Self::RequireExists => Err(format!(
+                "Output directory does not exist: {}",
+                dir.display()
+            )),
+        }
+    }
+}
+
+/// Per-batch execution settings shared by every batch command.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BatchOptions {
+    pub per_file_timeout_ms: Option<u64>,
+    pub create_dir_policy: CreateDirPolicy,
+}
+
+/// Per-file result of a batch closure: output path plus optional (in_w, in_h, out_w, out_h).
+type FileOutcome = Result<(String, Option<(u32, u32, u32, u32)>), String>;
+
+/// Run `process_fn` on a dedicated thread and stop waiting after `timeout_ms`.
+/// A timed-out thread cannot be killed, so it is detached and left to finish on its own.
+fn run_with_timeout<F>(
+    process_fn: Arc<F>,
+    input_path: &str,
+    out_dir: &Path,
+    timeout_ms: u64,
+) -> FileOutcome
+where
+    F: Fn(&str, &Path) -> FileOutcome + Send + Sync + 'static,
+{
+    let (tx, rx) = mpsc::channel();
+    let input = input_path.to_string();
+    let dir = out_dir.to_path_buf();
+    let handle = thread::Builder::new()
+        .name("batch-file".to_string())
+        .spawn(move || {
+            let _ = tx.send(process_fn(&input, &dir));
+        })
+        .map_err(|e| format!("Cannot start worker thread: {}", e))?;
+
+    match rx.recv_timeout(Duration::from_millis(timeout_ms)) {
+        Ok(outcome) => {
+            let _ = handle.join();
+            outcome
+        }
+        Err(RecvTimeoutError::Timeout) => {
+            tracing::warn!(input_path, timeout_ms, "File processing timed out");
+            Err(format!("Timed out after {}ms", timeout_ms))
+        }
+        // The sender was dropped without a result: the worker panicked
+        Err(RecvTimeoutError::Disconnected) => {
+            let _ = handle.join();
+            Err("Processing failed unexpectedly".to_string())
+        }
+    }
+}
+
 /// Generic batch processor — handles output dir creation, parallel iteration,
 /// progress events, and result aggregation. Each caller only provides its
 /// per-file processing closure.
 ///
 /// The closure receives `(input_path, output_dir)` and returns
 /// `Ok((output_path, optional_dims))` or `Err(message)`.
-fn batch_process<F>(
+///
+/// With `options.per_file_timeout_ms`, each file runs on its own thread; a file that
+/// exceeds the limit is reported as timed out and its thread is left detached.
+fn batch_process<F, C>(
+    operation: &str,
+    input_paths: &[String],
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+    cancel: &C,
+    options: BatchOptions,
+    process_fn: F,
+) -> BatchProgress
+where
+    F: Fn(&str, &Path) -> FileOutcome + Send + Sync + 'static,
+    C: CancelSignal + Sync,
+{
+    batch_process_steps(
+        operation,
+        input_paths,
+        output_dir,
+        app_handle,
+        cancel,
+        options,
+        1,
+        process_fn,
+    )
+}
+
+/// `batch_process` for closures that write `steps_per_file` outputs per input:
+/// progress events count outputs, so the UI total is `inputs × steps_per_file`.
+#[allow(clippy::too_many_arguments)]
+fn batch_process_steps<F, C>(
+    operation: &str,
     input_paths: &[String],
     output_dir: &str,
     app_handle: &tauri::AppHandle,
-    cancel: &Arc<AtomicBool>,
+    cancel: &C,
+    options: BatchOptions,
+    steps_per_file: usize,
     process_fn: F,
 ) -> BatchProgress
 where
-    F: Fn(&str, &Path) -> Result<(String, Option<(u32, u32, u32, u32)>), String> + Sync,
+    F: Fn(&str, &Path) -> FileOutcome + Send + Sync + 'static,
+    C: CancelSignal + Sync,
 {
     let total = input_paths.len();
+    let progress_total = total * steps_per_file;
     let out_dir = PathBuf::from(output_dir);
+    let started = std::time::Instant::now();
 
-    if let Err(e) = ensure_output_dir(&out_dir) {
+    if let Err(e) = options.create_dir_policy.prepare(&out_dir) {
+        tracing::error!(operation, "Cannot prepare output directory: {}", e);
         return BatchProgress::all_failed(input_paths, e);
     }
 
     let processed = AtomicUsize::new(0);
+    let process_fn = Arc::new(process_fn);
 
     let results: Vec<ProcessingResult> = input_paths
         .par_iter()
         .map(|input_path| {
-            if cancel.load(Ordering::Relaxed) {
-                emit_progress(app_handle, &processed, total, input_path);
+            if cancel.is_cancelled() {
+                emit_progress_steps(
+                    app_handle,
+                    &processed,
+                    steps_per_file,
+                    progress_total,
+                    input_path,
+                );
                 return build_result(input_path, Err("Cancelled".to_string()), None);
             }
 
-            let result = process_fn(input_path, &out_dir);
-            emit_progress(app_handle, &processed, total, input_path);
+            let result = match options.per_file_timeout_ms {
+                Some(timeout_ms) => {
+                    run_with_timeout(Arc::clone(&process_fn), input_path, &out_dir, timeout_ms)
+                }
+                None => process_fn(input_path, &out_dir),
+            };
+            emit_progress_steps(
+                app_handle,
+                &processed,
+                steps_per_file,
+                progress_total,
+                input_path,
+            );
 
             let (path_result, dims) = match result {
                 Ok((path, dims)) => (Ok(path), dims),
@@ -320,45 +1083,384 @@ where
         .collect();
 
     let completed = results.iter().filter(|r| r.success).count();
+    tracing::info!(
+        operation,
+        file_count = total,
+        completed,
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "Batch finished"
+    );
     BatchProgress {
         completed,
         total,
         results,
+        notes: Vec::new(),
     }
 }
 
 // --- Resize ---
 
-#[allow(clippy::too_many_arguments)]
-pub fn resize_images(
-    input_paths: Vec<String>,
-    mode: String,
-    width: u32,
-    height: u32,
-    percentage: u32,
-    output_dir: String,
-    app_handle: tauri::AppHandle,
-    cancel: Arc<AtomicBool>,
-) -> BatchProgress {
-    batch_process(
-        &input_paths,
-        &output_dir,
-        &app_handle,
-        &cancel,
-        |input_path, out_dir| {
-            let img = load_image(input_path)?;
-            let (orig_w, orig_h) = (img.width(), img.height());
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResizePreset {
+    pub name: &'static str,
+    pub mode: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
 
-            let (new_w, new_h) = match mode.as_str() {
-                "exact" => (width, height),
-                "width" => {
-                    let ratio = width as f64 / orig_w as f64;
+/// Named resize profiles; a preset replaces the caller's mode and dimensions.
+pub static RESIZE_PRESETS: [ResizePreset; 5] = [
+    ResizePreset {
+        name: "icon",
+        mode: "exact",
+        width: 256,
+        height: 256,
+    },
+    ResizePreset {
+        name: "thumbnail",
+        mode: "fit_down",
+        width: 150,
+        height: 150,
+    },
+    ResizePreset {
+        name: "hd",
+        mode: "fit_down",
+        width: 1920,
+        height: 1080,
+    },
+    ResizePreset {
+        name: "4k",
+        mode: "fit_down",
+        width: 3840,
+        height: 2160,
+    },
+    ResizePreset {
+        name: "web",
+        mode: "width",
+        width: 1200,
+        height: 0,
+    },
+];
+
+fn find_resize_preset(name: &str) -> Result<&'static ResizePreset, String> {
+    RESIZE_PRESETS
+        .iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| {
+            let valid: Vec<&str> = RESIZE_PRESETS.iter().map(|p| p.name).collect();
+            format!(
+                "Unknown resize preset '{}' (valid presets: {})",
+                name,
+                valid.join(", ")
+            )
+        })
+}
+
+/// Scale (w, h) to fit within (max_w, max_h) preserving aspect ratio; never returns a zero side.
+fn fit_within(w: u32, h: u32, max_w: u32, max_h: u32) -> (u32, u32) {
+    let scale = (max_w as f64 / w as f64).min(max_h as f64 / h as f64);
+    (
+        ((w as f64 * scale).round() as u32).max(1),
+        ((h as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// Map a resampling filter name to its `FilterType`; defaults to Lanczos3.
+fn parse_filter_type(name: Option<&str>) -> Result<image::imageops::FilterType, String> {
+    use image::imageops::FilterType;
+    match name.map(|n| n.to_lowercase()).as_deref() {
+        None | Some("lanczos3") => Ok(FilterType::Lanczos3),
+        Some("nearest") => Ok(FilterType::Nearest),
+        Some("triangle") => Ok(FilterType::Triangle),
+        Some("catmullrom") => Ok(FilterType::CatmullRom),
+        Some("gaussian") => Ok(FilterType::Gaussian),
+        Some(other) => Err(format!("Unknown resize filter: {}", other)),
+    }
+}
+
+/// Dimensions scaled by `sqrt(target / current)` so the area is about `megapixels` million.
+fn megapixel_dimensions(width: u32, height: u32, megapixels: f64) -> (u32, u32) {
+    let scale = (megapixels * 1_000_000.0 / (width as f64 * height as f64)).sqrt();
+    (
+        (width as f64 * scale).round() as u32,
+        (height as f64 * scale).round() as u32,
+    )
+}
+
+/// Default canvas fill: opaque white for formats without alpha, transparent otherwise.
+fn default_canvas_fill(ext: &str) -> [u8; 4] {
+    match ext {
+        "jpg" | "jpeg" | "bmp" => [255, 255, 255, 255],
+        _ => [0, 0, 0, 0],
+    }
+}
+
+// --- Seam carving ---
+
+/// Seams removed between two `"seam-carve-progress"` events.
+const SEAM_PROGRESS_INTERVAL: usize = 10;
+
+/// Gradient magnitude of the luminance at every pixel (row-major, `w × h`).
+fn seam_energy_map(pixels: &[Rgba<u8>], w: usize, h: usize) -> Vec<f32> {
+    let lum: Vec<f32> = pixels.iter().map(|p| luminance(p) as f32).collect();
+    (0..w * h)
+        .map(|i| {
+            let (x, y) = (i % w, i / w);
+            let left = lum[y * w + x.saturating_sub(1)];
+            let right = lum[y * w + (x + 1).min(w - 1)];
+            let up = lum[y.saturating_sub(1) * w + x];
+            let down = lum[(y + 1).min(h - 1) * w + x];
+            (right - left).abs() + (down - up).abs()
+        })
+        .collect()
+}
+
+/// Column index per row of the minimum-energy top-to-bottom 8-connected seam.
+fn find_vertical_seam(energy: &[f32], w: usize, h: usize) -> Vec<usize> {
+    let mut cost = energy.to_vec();
+    for y in 1..h {
+        for x in 0..w {
+            let prev = (y - 1) * w;
+            let best = cost[prev + x.saturating_sub(1)..=prev + (x + 1).min(w - 1)]
+                .iter()
+                .copied()
+                .fold(f32::INFINITY, f32::min);
+            cost[y * w + x] += best;
+        }
+    }
+
+    let cheapest = |row: usize, cols: std::ops::RangeInclusive<usize>, fallback: usize| {
+        cols.min_by(|&a, &b| cost[row * w + a].total_cmp(&cost[row * w + b]))
+            .unwrap_or(fallback)
+    };
+    let mut seam = vec![0; h];
+    let mut x = cheapest(h - 1, 0..=w - 1, 0);
+    seam[h - 1] = x;
+    for y in (0..h - 1).rev() {
+        x = cheapest(y, x.saturating_sub(1)..=(x + 1).min(w - 1), x);
+        seam[y] = x;
+    }
+    seam
+}
+
+/// Remove seams until `img` is `target_w` wide. `on_seam(removed, total)` is called every
+/// `SEAM_PROGRESS_INTERVAL` seams and once at the end.
+fn seam_carve_width(
+    img: &image::RgbaImage,
+    target_w: u32,
+    cancel: &AtomicBool,
+    mut on_seam: impl FnMut(usize, usize),
+) -> Result<image::RgbaImage, String> {
+    let (mut w, h) = (img.width() as usize, img.height() as usize);
+    let total = w.saturating_sub(target_w as usize);
+    let mut pixels: Vec<Rgba<u8>> = img.pixels().copied().collect();
+
+    for removed in 1..=total {
+        if cancel.load(std::sync::atomic::Ordering::Relaxed) {
+            return Err("Cancelled".to_string());
+        }
+        let energy = seam_energy_map(&pixels, w, h);
+        let seam = find_vertical_seam(&energy, w, h);
+        pixels = pixels
+            .chunks(w)
+            .zip(&seam)
+            .flat_map(|(row, &skip)| {
+                row.iter()
+                    .enumerate()
+                    .filter(move |(x, _)| *x != skip)
+                    .map(|(_, p)| *p)
+            })
+            .collect();
+        w -= 1;
+        if removed % SEAM_PROGRESS_INTERVAL == 0 || removed == total {
+            on_seam(removed, total);
+        }
+    }
+
+    let raw: Vec<u8> = pixels.into_iter().flat_map(|p| p.0).collect();
+    image::RgbaImage::from_raw(w as u32, h as u32, raw)
+        .ok_or_else(|| "Seam carving produced an invalid image buffer".to_string())
+}
+
+/// Content-aware shrink along one axis. A `width`/`height` of 0 or equal to the
+/// original leaves that axis alone; exactly one axis must shrink.
+fn seam_carve(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    cancel: &AtomicBool,
+    on_seam: impl FnMut(usize, usize),
+) -> Result<DynamicImage, String> {
+    let (orig_w, orig_h) = (img.width(), img.height());
+    let carve_w = width != 0 && width != orig_w;
+    let carve_h = height != 0 && height != orig_h;
+    match (carve_w, carve_h) {
+        (true, true) => {
+            Err("Seam carving resizes one dimension at a time: set width or height to 0".into())
+        }
+        (false, false) => Err("Seam carving needs a target width or height".into()),
+        (true, false) if width > orig_w => Err(format!(
+            "Seam carving target width must be smaller than the original ({} px)",
+            orig_w
+        )),
+        (false, true) if height > orig_h => Err(format!(
+            "Seam carving target height must be smaller than the original ({} px)",
+            orig_h
+        )),
+        (true, false) => Ok(DynamicImage::ImageRgba8(seam_carve_width(
+            &img.to_rgba8(),
+            width,
+            cancel,
+            on_seam,
+        )?)),
+        (false, true) => {
+            let turned = image::imageops::rotate90(&img.to_rgba8());
+            let carved = seam_carve_width(&turned, height, cancel, on_seam)?;
+            Ok(DynamicImage::ImageRgba8(image::imageops::rotate270(
+                &carved,
+            )))
+        }
+    }
+}
+
+/// Resize images. Modes: "exact", "width", "height", "percentage",
+/// "fit_down" (only shrinks images larger than `width × height`, copies the rest byte-for-byte),
+/// "canvas" (fits the image, then centers it on an exact `width × height` canvas
+/// filled with `fill_color`; upscaling only when `allow_upscale` is set),
+/// "megapixels" (scales down to about `megapixels` million pixels, copying smaller
+/// images like "fit_down"),
+/// and "seam_carve" (content-aware shrink of either width or height, with
+/// `"seam-carve-progress"` events every few seams).
+/// A `preset` from `RESIZE_PRESETS` overrides `mode`, `width`, and `height`.
+#[allow(clippy::too_many_arguments)]
+pub fn resize_images(
+    input_paths: Vec<String>,
+    mode: String,
+    width: u32,
+    height: u32,
+    percentage: u32,
+    megapixels: Option<f64>,
+    fill_color: Option<[u8; 4]>,
+    allow_upscale: bool,
+    filter: Option<String>,
+    preset: Option<String>,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let filter_type = match parse_filter_type(filter.as_deref()) {
+        Ok(f) => f,
+        Err(e) => return BatchProgress::all_failed(&input_paths, e),
+    };
+
+    let (mode, width, height, preset_note) = match preset.as_deref() {
+        None => (mode, width, height, None),
+        Some(name) => match find_resize_preset(name) {
+            Ok(p) => (
+                p.mode.to_string(),
+                p.width,
+                p.height,
+                Some(format!(
+                    "Preset '{}' applied; mode, width and height parameters were ignored",
+                    p.name
+                )),
+            ),
+            Err(e) => return BatchProgress::all_failed(&input_paths, e),
+        },
+    };
+
+    let target_mp = megapixels.unwrap_or(0.0);
+    if mode == "megapixels" && !(target_mp.is_finite() && target_mp > 0.0) {
+        return BatchProgress::all_failed(
+            &input_paths,
+            "Megapixels mode needs a positive megapixel target".to_string(),
+        );
+    }
+
+    let seam_handle = app_handle.clone();
+    let seam_cancel = cancel.clone();
+    let mut progress = batch_process(
+        "resize_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-resized.{}", stem, ext));
+
+            if mode == "seam_carve" {
+                let img = load_image(input_path)?;
+                let (orig_w, orig_h) = (img.width(), img.height());
+                let carved = seam_carve(&img, width, height, &seam_cancel, |done, total| {
+                    emit_seam_progress(&seam_handle, input_path, done, total)
+                })?;
+                let (new_w, new_h) = (carved.width(), carved.height());
+                save_in_original_format(&carved, input_path, &output_path)?;
+                return Ok((
+                    output_path.to_string_lossy().to_string(),
+                    Some((orig_w, orig_h, new_w, new_h)),
+                ));
+            }
+
+            if mode == "megapixels" {
+                // Already within the budget: copy untouched like "fit_down"
+                let (orig_w, orig_h) = read_dimensions(input_path)?;
+                if orig_w as f64 * orig_h as f64 <= target_mp * 1_000_000.0 {
+                    fs::copy(input_path, &output_path)
+                        .map_err(|e| format!("Cannot copy file: {}", e))?;
+                    return Ok((
+                        output_path.to_string_lossy().to_string(),
+                        Some((orig_w, orig_h, orig_w, orig_h)),
+                    ));
+                }
+            }
+
+            if mode == "fit_down" {
+                if width == 0 || height == 0 {
+                    return Err("Target dimensions cannot be zero".to_string());
+                }
+                // Already small enough: copy untouched to avoid a lossy re-encode
+                let (orig_w, orig_h) = read_dimensions(input_path)?;
+                if orig_w <= width && orig_h <= height {
+                    fs::copy(input_path, &output_path)
+                        .map_err(|e| format!("Cannot copy file: {}", e))?;
+                    return Ok((
+                        output_path.to_string_lossy().to_string(),
+                        Some((orig_w, orig_h, orig_w, orig_h)),
+                    ));
+                }
+            }
+
+            let img = load_image(input_path)?;
+            let (orig_w, orig_h) = (img.width(), img.height());
+
+            let (new_w, new_h) = match mode.as_str() {
+                "exact" => (width, height),
+                "fit_down" => fit_within(orig_w, orig_h, width, height),
+                "canvas" => {
+                    if width == 0 || height == 0 {
+                        return Err("Target dimensions cannot be zero".to_string());
+                    }
+                    let (fit_w, fit_h) = fit_within(orig_w, orig_h, width, height);
+                    if !allow_upscale && (fit_w > orig_w || fit_h > orig_h) {
+                        (orig_w, orig_h)
+                    } else {
+                        (fit_w, fit_h)
+                    }
+                }
+                "width" => {
+                    let ratio = width as f64 / orig_w as f64;
                     (width, (orig_h as f64 * ratio).round() as u32)
                 }
                 "height" => {
                     let ratio = height as f64 / orig_h as f64;
                     ((orig_w as f64 * ratio).round() as u32, height)
                 }
+                "megapixels" => megapixel_dimensions(orig_w, orig_h, target_mp),
                 "percentage" => {
                     let scale = percentage as f64 / 100.0;
                     (
@@ -373,11 +1475,24 @@ pub fn resize_images(
                 return Err("Target dimensions cannot be zero".to_string());
             }
 
-            let resized = img.resize_exact(new_w, new_h, image::imageops::FilterType::Lanczos3);
+            let resized = img.resize_exact(new_w, new_h, filter_type);
 
-            let ext = get_extension(input_path);
-            let stem = file_stem(input_path);
-            let output_path = out_dir.join(format!("{}-resized.{}", stem, ext));
+            if mode == "canvas" {
+                let fill = fill_color.unwrap_or_else(|| default_canvas_fill(&ext));
+                let mut canvas = image::RgbaImage::from_pixel(width, height, Rgba(fill));
+                image::imageops::overlay(
+                    &mut canvas,
+                    &resized.to_rgba8(),
+                    (width as i64 - new_w as i64) / 2,
+                    (height as i64 - new_h as i64) / 2,
+                );
+                let canvas_img = DynamicImage::ImageRgba8(canvas);
+                save_in_original_format(&canvas_img, input_path, &output_path)?;
+                return Ok((
+                    output_path.to_string_lossy().to_string(),
+                    Some((orig_w, orig_h, width, height)),
+                ));
+            }
 
             save_in_original_format(&resized, input_path, &output_path)?;
             Ok((
@@ -385,7 +1500,9 @@ pub fn resize_images(
                 Some((orig_w, orig_h, new_w, new_h)),
             ))
         },
-    )
+    );
+    progress.notes.extend(preset_note);
+    progress
 }
 
 // --- EXIF Strip ---
@@ -395,13 +1512,16 @@ pub fn strip_metadata(
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
     batch_process(
+        "strip_metadata",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        move |input_path, out_dir| {
             let img = load_image(input_path)?;
             let (w, h) = (img.width(), img.height());
 
@@ -451,6 +1571,182 @@ static SYSTEM_FONT: LazyLock<Result<Vec<u8>, String>> = LazyLock::new(|| {
     Err("No system font found. Install Arial, DejaVu Sans, or Liberation Sans.".to_string())
 });
 
+/// DejaVu Sans compiled into the binary — last resort on systems with no usable fonts
+/// (minimal containers, headless servers). License: resources/fonts/DejaVuSans-LICENSE.txt
+static BUNDLED_FONT: &[u8] = include_bytes!("../resources/fonts/DejaVuSans.ttf");
+
+/// Parse the cached system font into a ready-to-draw `FontArc`,
+/// falling back to the bundled font when no system font is available.
+pub(crate) fn load_system_font() -> Result<FontArc, String> {
+    if let Ok(font_data) = SYSTEM_FONT.as_ref() {
+        if let Ok(font) = FontArc::try_from_vec(font_data.clone()) {
+            return Ok(font);
+        }
+    }
+    FontArc::try_from_slice(BUNDLED_FONT).map_err(|_| "Failed to load font".to_string())
+}
+
+/// Load the watermark font: the user-supplied file when given, otherwise the system/bundled font.
+/// An unreadable or invalid custom font falls back rather than failing the whole batch.
+fn load_watermark_font(font_path: Option<&str>) -> Result<FontArc, String> {
+    if let Some(path) = font_path {
+        match fs::read(path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| FontArc::try_from_vec(data).map_err(|e| e.to_string()))
+        {
+            Ok(font) => return Ok(font),
+            Err(e) => tracing::warn!("Cannot load font '{}': {}", path, e),
+        }
+    }
+    load_system_font()
+}
+
+/// Measured layout of a (possibly multi-line) watermark text block.
+struct TextBlock<'a> {
+    lines: Vec<&'a str>,
+    line_widths: Vec<i32>,
+    line_height: i32,
+    width: i32,
+    height: i32,
+}
+
+/// Split `text` on newlines and measure each line with the actual font metrics.
+fn measure_text_block<'a>(
+    text: &'a str,
+    font: &FontArc,
+    scale: PxScale,
+    font_size: f32,
+    line_spacing: f32,
+) -> TextBlock<'a> {
+    let lines: Vec<&str> = text.split('\n').map(|l| l.trim_end_matches('\r')).collect();
+    let line_widths: Vec<i32> = lines
+        .iter()
+        .map(|line| text_size(scale, font, line).0 as i32)
+        .collect();
+    let line_height = (font_size * line_spacing).round().max(1.0) as i32;
+    let width = line_widths.iter().copied().max().unwrap_or(0);
+    let height = line_height * (lines.len() as i32 - 1) + font_size.ceil() as i32;
+
+    TextBlock {
+        lines,
+        line_widths,
+        line_height,
+        width,
+        height,
+    }
+}
+
+/// Draw a text block with its top-left corner at (x, y).
+/// `align` positions each line inside the block: 0.0 = left, 0.5 = center, 1.0 = right.
+#[allow(clippy::too_many_arguments)]
+fn draw_text_block(
+    canvas: &mut image::RgbaImage,
+    color: Rgba<u8>,
+    x: i32,
+    y: i32,
+    align: f32,
+    scale: PxScale,
+    font: &FontArc,
+    block: &TextBlock,
+) {
+    for (i, (line, line_w)) in block.lines.iter().zip(&block.line_widths).enumerate() {
+        let line_x = x + ((block.width - line_w) as f32 * align) as i32;
+        let line_y = y + block.line_height * i as i32;
+        draw_text_mut(canvas, color, line_x, line_y, scale, font, line);
+    }
+}
+
+/// Render a text block into a transparent sprite tinted with `color`, rotated
+/// counter-clockwise by `rotation_degrees`. Right angles are rotated exactly;
+/// any other angle uses bilinear interpolation on an expanded canvas so no corner is clipped.
+fn render_text_sprite(
+    block: &TextBlock,
+    align: f32,
+    scale: PxScale,
+    font: &FontArc,
+    color: Rgba<u8>,
+    rotation_degrees: f32,
+) -> image::RgbaImage {
+    let (w, h) = (block.width.max(1) as u32, block.height.max(1) as u32);
+
+    // Draw opaque white first so the alpha channel holds pure glyph coverage
+    let mut mask = image::RgbaImage::new(w, h);
+    draw_text_block(
+        &mut mask,
+        Rgba([255, 255, 255, 255]),
+        0,
+        0,
+        align,
+        scale,
+        font,
+        block,
+    );
+
+    let degrees = rotation_degrees.rem_euclid(360.0);
+    let near = |target: f32| (degrees - target).abs() < 0.01;
+    let mut sprite = if near(0.0) || near(360.0) {
+        mask
+    } else if near(90.0) {
+        image::imageops::rotate270(&mask)
+    } else if near(180.0) {
+        image::imageops::rotate180(&mask)
+    } else if near(270.0) {
+        image::imageops::rotate90(&mask)
+    } else {
+        // rotate_padded turns clockwise, so negate for counter-clockwise
+        let theta = -degrees.to_radians();
+        let (out_w, out_h) = rotated_bounds(w, h, theta);
+        rotate_padded(&mask, theta, Rgba([0, 0, 0, 0]), out_w, out_h)
+    };
+
+    for pixel in sprite.pixels_mut() {
+        let alpha = (pixel[3] as u16 * color[3] as u16 / 255) as u8;
+        *pixel = Rgba([color[0], color[1], color[2], alpha]);
+    }
+    sprite
+}
+
+/// Size of the box that fully contains a `w × h` image rotated by `theta` radians.
+fn rotated_bounds(w: u32, h: u32, theta: f32) -> (u32, u32) {
+    let (sin, cos) = (theta.sin().abs(), theta.cos().abs());
+    (
+        ((w as f32 * cos + h as f32 * sin).ceil() as u32).max(1),
+        ((w as f32 * sin + h as f32 * cos).ceil() as u32).max(1),
+    )
+}
+
+/// Rotate `img` clockwise by `theta` radians about its center with bilinear
+/// sampling, returning the centered `out_w × out_h` window. The image is first
+/// padded onto a square `fill` canvas so no corner is clipped mid-rotation.
+fn rotate_padded(
+    img: &image::RgbaImage,
+    theta: f32,
+    fill: Rgba<u8>,
+    out_w: u32,
+    out_h: u32,
+) -> image::RgbaImage {
+    let (w, h) = img.dimensions();
+    let side = out_w.max(out_h).max(w).max(h) + 2;
+    let mut padded = image::RgbaImage::from_pixel(side, side, fill);
+    image::imageops::replace(
+        &mut padded,
+        img,
+        ((side - w) / 2) as i64,
+        ((side - h) / 2) as i64,
+    );
+    let turned = rotate_about_center(&padded, theta, Interpolation::Bilinear, fill);
+    image::imageops::crop_imm(
+        &turned,
+        (side - out_w) / 2,
+        (side - out_h) / 2,
+        out_w,
+        out_h,
+    )
+    .to_image()
+}
+
+/// Draw text on images. `text` may span several lines (`\n`); `rotation_degrees`
+/// turns the whole block counter-clockwise, including every tile in tiled mode.
 #[allow(clippy::too_many_arguments)]
 pub fn add_watermark(
     input_paths: Vec<String>,
@@ -459,80 +1755,78 @@ pub fn add_watermark(
     opacity: f32,
     font_size: f32,
     color: String,
+    font_path: Option<String>,
+    line_spacing: f32,
+    rotation_degrees: f32,
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
-    let font_data = match SYSTEM_FONT.as_ref() {
-        Ok(d) => d.clone(),
-        Err(e) => return BatchProgress::all_failed(&input_paths, e.clone()),
-    };
-    let font = match FontArc::try_from_vec(font_data) {
+    let font = match load_watermark_font(font_path.as_deref()) {
         Ok(f) => f,
-        Err(_) => {
-            return BatchProgress::all_failed(&input_paths, "Failed to load font".to_string())
-        }
+        Err(e) => return BatchProgress::all_failed(&input_paths, e),
     };
 
     let opacity_byte = (opacity.clamp(0.0, 1.0) * 255.0) as u8;
     let (cr, cg, cb) = crate::utils::parse_hex_color(&color, (255, 255, 255));
     let color = Rgba([cr, cg, cb, opacity_byte]);
     let scale = PxScale::from(font_size);
+    let block = measure_text_block(&text, &font, scale, font_size, line_spacing);
+
+    // Line alignment follows the anchor side so the block hugs its corner
+    let align = match position.as_str() {
+        "top-left" | "bottom-left" | "tiled" => 0.0,
+        "top-right" | "bottom-right" => 1.0,
+        _ => 0.5,
+    };
+    // The sprite is identical for every image — render it once
+    let sprite = render_text_sprite(&block, align, scale, &font, color, rotation_degrees);
+    let (sprite_w, sprite_h) = (sprite.width() as i64, sprite.height() as i64);
 
     batch_process(
+        "add_watermark",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        move |input_path, out_dir| {
             let img = load_image(input_path)?;
             let (img_w, img_h) = (img.width(), img.height());
             let mut base = img.to_rgba8();
 
-            let text_width = (font_size * text.len() as f32 * 0.55) as i32;
-            let text_height = font_size as i32;
-            let margin = WATERMARK_MARGIN_PX;
+            let (img_w_i, img_h_i) = (img_w as i64, img_h as i64);
+            let margin = WATERMARK_MARGIN_PX as i64;
+            let right_x = img_w_i - sprite_w - margin;
+            let bottom_y = img_h_i - sprite_h - margin;
+            let center_x = (img_w_i - sprite_w) / 2;
+            let center_y = (img_h_i - sprite_h) / 2;
 
-            match position.as_str() {
-                "center" => {
-                    let x = (img_w as i32 - text_width) / 2;
-                    let y = (img_h as i32 - text_height) / 2;
-                    draw_text_mut(&mut base, color, x, y, scale, &font, &text);
-                }
-                "top-left" => {
-                    draw_text_mut(&mut base, color, margin, margin, scale, &font, &text);
-                }
-                "top-right" => {
-                    let x = img_w as i32 - text_width - margin;
-                    draw_text_mut(&mut base, color, x, margin, scale, &font, &text);
-                }
-                "bottom-left" => {
-                    let y = img_h as i32 - text_height - margin;
-                    draw_text_mut(&mut base, color, margin, y, scale, &font, &text);
-                }
-                "bottom-right" => {
-                    let x = img_w as i32 - text_width - margin;
-                    let y = img_h as i32 - text_height - margin;
-                    draw_text_mut(&mut base, color, x, y, scale, &font, &text);
-                }
-                "tiled" => {
-                    let step_x = text_width + WATERMARK_TILE_SPACING_PX;
-                    let step_y = text_height + WATERMARK_TILE_SPACING_PX;
-                    let mut y = margin;
-                    while y < img_h as i32 {
-                        let mut x = margin;
-                        while x < img_w as i32 {
-                            draw_text_mut(&mut base, color, x, y, scale, &font, &text);
-                            x += step_x;
-                        }
-                        y += step_y;
+            if position == "tiled" {
+                let step_x = sprite_w + WATERMARK_TILE_SPACING_PX as i64;
+                let step_y = sprite_h + WATERMARK_TILE_SPACING_PX as i64;
+                let mut y = margin;
+                while y < img_h_i {
+                    let mut x = margin;
+                    while x < img_w_i {
+                        image::imageops::overlay(&mut base, &sprite, x, y);
+                        x += step_x;
                     }
+                    y += step_y;
                 }
-                _ => {
-                    let x = (img_w as i32 - text_width) / 2;
-                    let y = (img_h as i32 - text_height) / 2;
-                    draw_text_mut(&mut base, color, x, y, scale, &font, &text);
-                }
+            } else {
+                let (x, y) = match position.as_str() {
+                    "top-left" => (margin, margin),
+                    "top-right" => (right_x, margin),
+                    "bottom-left" => (margin, bottom_y),
+                    "bottom-right" => (right_x, bottom_y),
+                    _ => (center_x, center_y),
+                };
+                // Keep the whole block inside the image (when it fits at all)
+                let x = x.clamp(0, (img_w_i - sprite_w).max(0));
+                let y = y.clamp(0, (img_h_i - sprite_h).max(0));
+                image::imageops::overlay(&mut base, &sprite, x, y);
             }
 
             let result_img = DynamicImage::ImageRgba8(base);
@@ -562,6 +1856,7 @@ pub fn add_image_watermark(
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
     // Load the watermark image once (shared across all target images)
     let watermark_img = match load_image(&watermark_path) {
@@ -585,11 +1880,13 @@ pub fn add_image_watermark(
     let opacity_clamped = opacity.clamp(0.0, 1.0);
 
     batch_process(
+        "add_image_watermark",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        move |input_path, out_dir| {
             let img = load_image(input_path)?;
             let (img_w, img_h) = (img.width(), img.height());
             let mut base = img.to_rgba8();
@@ -685,13 +1982,16 @@ pub fn optimize_lossless(
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
     batch_process(
+        "optimize_lossless",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
+        options,
+        move |input_path, out_dir| {
             let ext = get_extension(input_path);
             let stem = file_stem(input_path);
 
@@ -726,55 +2026,1688 @@ pub fn optimize_lossless(
     )
 }
 
-// --- Crop ---
+// --- Placeholder ---
 
-fn parse_ratio(ratio: &str) -> Option<(f64, f64)> {
-    let parts: Vec<&str> = ratio.split(':').collect();
-    if parts.len() == 2 {
-        if let (Ok(w), Ok(h)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
-            if w > 0.0 && h > 0.0 {
-                return Some((w, h));
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PlaceholderResult {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Largest side accepted for generated images (placeholders, gradients…).
+pub(crate) const MAX_GENERATED_DIMENSION: u32 = 16384;
+
+/// Create a solid-color PNG, optionally with centered text.
+/// `text: None` draws the default `"{width}×{height}"` label; `Some("")` draws nothing.
+pub fn generate_placeholder(
+    width: u32,
+    height: u32,
+    fill_color: [u8; 4],
+    text: Option<String>,
+    text_color: [u8; 4],
+    output_path: &str,
+) -> Result<PlaceholderResult, String> {
+    if width == 0
+        || height == 0
+        || width > MAX_GENERATED_DIMENSION
+        || height > MAX_GENERATED_DIMENSION
+    {
+        return Err(format!(
+            "Placeholder dimensions must be between 1 and {} px",
+            MAX_GENERATED_DIMENSION
+        ));
+    }
+
+    let mut canvas = image::RgbaImage::from_pixel(width, height, Rgba(fill_color));
+
+    let label = text.unwrap_or_else(|| format!("{}×{}", width, height));
+    if !label.trim().is_empty() {
+        let font = load_system_font()?;
+
+        // Start at a sixth of the short side, then shrink until the label fits in 90% of the width.
+        let mut font_size = (width.min(height) as f32 / 6.0).max(8.0);
+        let (mut text_w, mut text_h) = text_size(PxScale::from(font_size), &font, &label);
+        let max_text_w = (width as f32 * 0.9).max(1.0);
+        if text_w as f32 > max_text_w {
+            font_size = (font_size * max_text_w / text_w as f32).max(4.0);
+            (text_w, text_h) = text_size(PxScale::from(font_size), &font, &label);
+        }
+
+        let x = (width as i32 - text_w as i32) / 2;
+        let y = (height as i32 - text_h as i32) / 2;
+        draw_text_mut(
+            &mut canvas,
+            Rgba(text_color),
+            x,
+            y,
+            PxScale::from(font_size),
+            &font,
+            &label,
+        );
+    }
+
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    canvas
+        .save_with_format(output_path, ImageFormat::Png)
+        .map_err(|e| format!("Cannot save PNG: {}", e))?;
+
+    Ok(PlaceholderResult {
+        output_path: output_path.to_string(),
+        width,
+        height,
+    })
+}
+
+// --- Gradient ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GradientStop {
+    pub position: f32,
+    pub color: [u8; 4],
+}
+
+/// Interpolate the color at `t` (0.0–1.0) between sorted gradient stops, in sRGB space.
+/// Positions before the first / after the last stop take that stop's color.
+fn sample_gradient(stops: &[GradientStop], t: f32) -> [u8; 4] {
+    let (first, last) = match (stops.first(), stops.last()) {
+        (Some(f), Some(l)) => (f, l),
+        _ => return [0, 0, 0, 0],
+    };
+    if t <= first.position {
+        return first.color;
+    }
+    if t >= last.position {
+        return last.color;
+    }
+
+    for pair in stops.windows(2) {
+        let (a, b) = (&pair[0], &pair[1]);
+        if t >= a.position && t <= b.position {
+            let span = b.position - a.position;
+            let local = if span > f32::EPSILON {
+                (t - a.position) / span
+            } else {
+                0.0
+            };
+            let mut color = [0u8; 4];
+            for (i, channel) in color.iter_mut().enumerate() {
+                let mixed = a.color[i] as f32 + (b.color[i] as f32 - a.color[i] as f32) * local;
+                *channel = mixed.round().clamp(0.0, 255.0) as u8;
             }
+            return color;
         }
     }
-    None
+
+    last.color
+}
+
+/// Render a linear or radial gradient into a new RGBA buffer.
+/// Linear gradients run along `angle_degrees` (0° = left→right, 90° = top→bottom) and
+/// span the full projection of the image onto that axis; radial gradients go from
+/// the center to the corners.
+fn render_gradient(
+    width: u32,
+    height: u32,
+    gradient_type: &str,
+    angle_degrees: f32,
+    stops: &[GradientStop],
+) -> Result<image::RgbaImage, String> {
+    if stops.is_empty() {
+        return Err("At least one gradient stop is required".to_string());
+    }
+    let mut sorted_stops = stops.to_vec();
+    sorted_stops.sort_by(|a, b| a.position.total_cmp(&b.position));
+
+    let (cx, cy) = (width as f32 / 2.0, height as f32 / 2.0);
+    let angle = angle_degrees.to_radians();
+    let (dir_x, dir_y) = (angle.cos(), angle.sin());
+    let half_extent = ((width as f32 * dir_x).abs() + (height as f32 * dir_y).abs()) / 2.0;
+    let max_radius = (cx * cx + cy * cy).sqrt();
+
+    let position_of: Box<dyn Fn(f32, f32) -> f32> = match gradient_type {
+        "linear" => Box::new(move |dx, dy| {
+            if half_extent <= f32::EPSILON {
+                return 0.0;
+            }
+            ((dx * dir_x + dy * dir_y) / half_extent + 1.0) / 2.0
+        }),
+        "radial" => Box::new(move |dx, dy| {
+            if max_radius <= f32::EPSILON {
+                return 0.0;
+            }
+            (dx * dx + dy * dy).sqrt() / max_radius
+        }),
+        other => return Err(format!("Unknown gradient type: {}", other)),
+    };
+
+    let mut canvas = image::RgbaImage::new(width, height);
+    for (x, y, pixel) in canvas.enumerate_pixels_mut() {
+        // Sample at pixel centers so the gradient is symmetric
+        let t = position_of(x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+        *pixel = Rgba(sample_gradient(&sorted_stops, t));
+    }
+
+    Ok(canvas)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GradientResult {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Generate a linear or radial gradient PNG.
+pub fn generate_gradient(
+    width: u32,
+    height: u32,
+    gradient_type: &str,
+    angle_degrees: f32,
+    stops: &[GradientStop],
+    output_path: &str,
+) -> Result<GradientResult, String> {
+    if width == 0
+        || height == 0
+        || width > MAX_GENERATED_DIMENSION
+        || height > MAX_GENERATED_DIMENSION
+    {
+        return Err(format!(
+            "Gradient dimensions must be between 1 and {} px",
+            MAX_GENERATED_DIMENSION
+        ));
+    }
+
+    let canvas = render_gradient(
+        width,
+        height,
+        &gradient_type.to_lowercase(),
+        angle_degrees,
+        stops,
+    )?;
+
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    canvas
+        .save_with_format(output_path, ImageFormat::Png)
+        .map_err(|e| format!("Cannot save PNG: {}", e))?;
+
+    Ok(GradientResult {
+        output_path: output_path.to_string(),
+        width,
+        height,
+    })
+}
+
+/// Place `img` unchanged (alpha included) in the middle of a gradient canvas
+/// `border` pixels larger on every side.
+fn add_gradient_border(
+    img: &image::RgbaImage,
+    border: u32,
+    stops: &[GradientStop],
+    angle_degrees: f32,
+) -> Result<image::RgbaImage, String> {
+    let (w, h) = (img.width() + border * 2, img.height() + border * 2);
+    if w > MAX_GENERATED_DIMENSION || h > MAX_GENERATED_DIMENSION {
+        return Err(format!(
+            "Bordered image would be {}x{}, exceeding {} px",
+            w, h, MAX_GENERATED_DIMENSION
+        ));
+    }
+    let mut canvas = render_gradient(w, h, "linear", angle_degrees, stops)?;
+    image::imageops::replace(&mut canvas, img, border as i64, border as i64);
+    Ok(canvas)
 }
 
+/// Surround each image with a linear gradient border, saved as `{stem}-gradient-border.png`.
 #[allow(clippy::too_many_arguments)]
-pub fn crop_images(
+pub fn image_border_gradient(
     input_paths: Vec<String>,
-    ratio: String,
-    anchor: String,
-    target_width: u32,
-    target_height: u32,
-    crop_x: Option<u32>,
-    crop_y: Option<u32>,
+    border_width: u32,
+    gradient_stops: Vec<GradientStop>,
+    gradient_angle: f32,
     output_dir: String,
     app_handle: tauri::AppHandle,
     cancel: Arc<AtomicBool>,
+    options: BatchOptions,
 ) -> BatchProgress {
+    if border_width == 0 {
+        return BatchProgress::all_failed(
+            &input_paths,
+            "Border width must be at least 1 px".to_string(),
+        );
+    }
+    if gradient_stops.is_empty() {
+        return BatchProgress::all_failed(
+            &input_paths,
+            "At least one gradient stop is required".to_string(),
+        );
+    }
+
     batch_process(
+        "image_border_gradient",
         &input_paths,
         &output_dir,
         &app_handle,
         &cancel,
-        |input_path, out_dir| {
-            let img = load_image(input_path)?;
-            let (orig_w, orig_h) = (img.width(), img.height());
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?.to_rgba8();
+            let (w, h) = img.dimensions();
+            let bordered =
+                add_gradient_border(&img, border_width, &gradient_stops, gradient_angle)?;
 
-            // When explicit crop_x/crop_y are provided, use them directly
-            // (free-form rectangle drawn by the user on the preview)
-            if let (Some(cx), Some(cy)) = (crop_x, crop_y) {
-                let cw = target_width.min(orig_w.saturating_sub(cx));
-                let ch = target_height.min(orig_h.saturating_sub(cy));
-                if cw == 0 || ch == 0 {
-                    return Err("Crop dimensions cannot be zero".to_string());
-                }
-                let cropped = img.crop_imm(cx.min(orig_w), cy.min(orig_h), cw, ch);
-                let ext = get_extension(input_path);
-                let stem = file_stem(input_path);
-                let output_path = out_dir.join(format!("{}-cropped.{}", stem, ext));
-                save_in_original_format(&cropped, input_path, &output_path)?;
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-gradient-border.png", stem));
+            bordered
+                .save_with_format(&output_path, ImageFormat::Png)
+                .map_err(|e| format!("Cannot save PNG: {}", e))?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, bordered.width(), bordered.height())),
+            ))
+        },
+    )
+}
+
+// --- Color Blindness Simulation ---
+
+/// Viénot, Brettel & Mollon (1999) dichromat simulation matrices, applied in linear RGB.
+/// Tritanopia uses the commonly paired Viénot-style approximation.
+fn color_blindness_matrix(blindness_type: &str) -> Option<[[f32; 3]; 3]> {
+    match blindness_type {
+        "protanopia" => Some([
+            [0.11238, 0.88762, 0.0],
+            [0.11238, 0.88762, 0.0],
+            [0.00401, -0.00401, 1.0],
+        ]),
+        "deuteranopia" => Some([
+            [0.29275, 0.70725, 0.0],
+            [0.29275, 0.70725, 0.0],
+            [-0.02234, 0.02234, 1.0],
+        ]),
+        "tritanopia" => Some([
+            [1.0, 0.14461, -0.14461],
+            [0.0, 0.85924, 0.14076],
+            [0.0, 0.85924, 0.14076],
+        ]),
+        _ => None,
+    }
+}
+
+fn srgb_to_linear(channel: u8) -> f32 {
+    let c = channel as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(linear: f32) -> u8 {
+    let c = linear.clamp(0.0, 1.0);
+    let encoded = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (encoded * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// Run one RGB triple through a simulation matrix (sRGB in, sRGB out).
+fn simulate_pixel(rgb: [u8; 3], matrix: &[[f32; 3]; 3]) -> [u8; 3] {
+    let linear = rgb.map(srgb_to_linear);
+    matrix.map(|row| linear_to_srgb(row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]))
+}
+
+pub fn simulate_color_blindness(
+    input_paths: Vec<String>,
+    blindness_type: String,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let blindness_type = blindness_type.to_lowercase();
+    let matrix = match color_blindness_matrix(&blindness_type) {
+        Some(m) => m,
+        None => {
+            return BatchProgress::all_failed(
+                &input_paths,
+                format!("Unknown color blindness type: {}", blindness_type),
+            )
+        }
+    };
+
+    batch_process(
+        "simulate_color_blindness",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mut rgba = img.to_rgba8();
+
+            for pixel in rgba.pixels_mut() {
+                let [r, g, b] = simulate_pixel([pixel[0], pixel[1], pixel[2]], &matrix);
+                pixel[0] = r;
+                pixel[1] = g;
+                pixel[2] = b;
+            }
+
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-{}.{}", stem, blindness_type, ext));
+
+            save_in_original_format(&DynamicImage::ImageRgba8(rgba), input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- White balance ---
+
+/// Color temperature range accepted by `white_balance_images`.
+pub const WHITE_BALANCE_KELVIN_RANGE: (f32, f32) = (2700.0, 10000.0);
+
+/// Reference white the correction maps the light source onto (daylight).
+const NEUTRAL_KELVIN: f32 = 6500.0;
+
+/// RGB of a black body at `kelvin`, using Tanner Helland's fit of the Planckian locus.
+fn kelvin_to_rgb(kelvin: f32) -> [f32; 3] {
+    let t = kelvin / 100.0;
+    let r = if t <= 66.0 {
+        255.0
+    } else {
+        329.698_73 * (t - 60.0).powf(-0.133_204_76)
+    };
+    let g = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let b = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+    [r, g, b].map(|c| c.clamp(1.0, 255.0))
+}
+
+/// Per-channel multipliers that neutralize light of the given temperature.
+/// Warm light (low kelvin) gets its blue boosted and red pulled back, cool light the
+/// opposite. Green is the reference and always stays at 1.0.
+fn white_balance_multipliers(kelvin: f32) -> [f32; 3] {
+    let light = kelvin_to_rgb(kelvin);
+    let neutral = kelvin_to_rgb(NEUTRAL_KELVIN);
+    let raw = [
+        neutral[0] / light[0],
+        neutral[1] / light[1],
+        neutral[2] / light[2],
+    ];
+    [raw[0] / raw[1], 1.0, raw[2] / raw[1]]
+}
+
+/// Correct the color cast of photos lit at `temperature_kelvin`.
+pub fn white_balance_images(
+    input_paths: Vec<String>,
+    temperature_kelvin: f32,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let [r_mul, _, b_mul] = white_balance_multipliers(temperature_kelvin);
+
+    batch_process(
+        "white_balance_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mut rgba = img.to_rgba8();
+
+            for pixel in rgba.pixels_mut() {
+                pixel[0] = (pixel[0] as f32 * r_mul).round().clamp(0.0, 255.0) as u8;
+                pixel[2] = (pixel[2] as f32 * b_mul).round().clamp(0.0, 255.0) as u8;
+            }
+
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-wb.{}", stem, ext));
+
+            save_in_original_format(&DynamicImage::ImageRgba8(rgba), input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- Line extraction ---
+
+/// Canny thresholds for a 0–1 strength: stronger means lower thresholds and more lines.
+fn canny_thresholds(strength: f32) -> (f32, f32) {
+    let strength = strength.clamp(0.0, 1.0);
+    let high = 200.0 - strength * 170.0;
+    (high * 0.4, high)
+}
+
+/// Turn photos into line drawings with a Canny edge detector. Edges come out white on
+/// black; `invert` gives black lines on white, like a sketch.
+pub fn line_extract_images(
+    input_paths: Vec<String>,
+    strength: f32,
+    invert: bool,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let (low, high) = canny_thresholds(strength);
+
+    batch_process(
+        "line_extract_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mut edges = imageproc::edges::canny(&img.to_luma8(), low, high);
+            if invert {
+                image::imageops::invert(&mut edges);
+            }
+
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-lines.png", stem));
+            edges
+                .save_with_format(&output_path, ImageFormat::Png)
+                .map_err(|e| format!("Cannot save PNG: {}", e))?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- Pixel sort ---
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SortChannel {
+    Luminance,
+    Red,
+    Hue,
+}
+
+impl SortChannel {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name.to_lowercase().as_str() {
+            "luminance" => Ok(Self::Luminance),
+            "red" => Ok(Self::Red),
+            "hue" => Ok(Self::Hue),
+            other => Err(format!("Unknown sort channel: {}", other)),
+        }
+    }
+
+    /// Channel value scaled to 0–255 so it compares against the thresholds.
+    fn value(self, px: &Rgba<u8>) -> u8 {
+        match self {
+            Self::Luminance => luminance(px),
+            Self::Red => px[0],
+            Self::Hue => hue_byte(px),
+        }
+    }
+}
+
+fn luminance(px: &Rgba<u8>) -> u8 {
+    (0.299 * px[0] as f32 + 0.587 * px[1] as f32 + 0.114 * px[2] as f32).round() as u8
+}
+
+fn hue_byte(px: &Rgba<u8>) -> u8 {
+    let (r, g, b) = (px[0] as f32, px[1] as f32, px[2] as f32);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    if delta == 0.0 {
+        return 0;
+    }
+    let sector = if max == r {
+        ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    (sector / 6.0 * 255.0).round() as u8
+}
+
+/// Sort, by luminance, every contiguous run of pixels whose channel value lies in `low..=high`.
+fn sort_pixel_runs(line: &mut [Rgba<u8>], channel: SortChannel, low: u8, high: u8) {
+    let mut start = 0;
+    while start < line.len() {
+        if !(low..=high).contains(&channel.value(&line[start])) {
+            start += 1;
+            continue;
+        }
+        let mut end = start + 1;
+        while end < line.len() && (low..=high).contains(&channel.value(&line[end])) {
+            end += 1;
+        }
+        line[start..end].sort_by_key(luminance);
+        start = end;
+    }
+}
+
+/// Glitch effect: sort pixel runs along rows (`horizontal`) or columns (`vertical`).
+#[allow(clippy::too_many_arguments)]
+pub fn pixel_sort_images(
+    input_paths: Vec<String>,
+    direction: String,
+    threshold_low: u8,
+    threshold_high: u8,
+    channel: String,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let vertical = match direction.to_lowercase().as_str() {
+        "horizontal" => false,
+        "vertical" => true,
+        other => {
+            return BatchProgress::all_failed(
+                &input_paths,
+                format!("Unknown sort direction: {}", other),
+            )
+        }
+    };
+    let channel = match SortChannel::parse(&channel) {
+        Ok(c) => c,
+        Err(e) => return BatchProgress::all_failed(&input_paths, e),
+    };
+    let (low, high) = if threshold_low <= threshold_high {
+        (threshold_low, threshold_high)
+    } else {
+        (threshold_high, threshold_low)
+    };
+
+    batch_process(
+        "pixel_sort_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mut rgba = img.to_rgba8();
+
+            if vertical {
+                let mut column = Vec::with_capacity(h as usize);
+                for x in 0..w {
+                    column.clear();
+                    column.extend((0..h).map(|y| *rgba.get_pixel(x, y)));
+                    sort_pixel_runs(&mut column, channel, low, high);
+                    for (y, px) in column.iter().enumerate() {
+                        rgba.put_pixel(x, y as u32, *px);
+                    }
+                }
+            } else {
+                let mut row = Vec::with_capacity(w as usize);
+                for y in 0..h {
+                    row.clear();
+                    row.extend((0..w).map(|x| *rgba.get_pixel(x, y)));
+                    sort_pixel_runs(&mut row, channel, low, high);
+                    for (x, px) in row.iter().enumerate() {
+                        rgba.put_pixel(x as u32, y, *px);
+                    }
+                }
+            }
+
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-glitch.{}", stem, ext));
+
+            save_in_original_format(&DynamicImage::ImageRgba8(rgba), input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- 3D LUT ---
+
+/// Largest `LUT_3D_SIZE` accepted; real-world grading LUTs use 17, 33 or 65.
+const MAX_LUT_SIZE: usize = 256;
+
+/// A parsed `.cube` 3D lookup table. Entries are stored red-fastest, as in the file.
+#[derive(Debug)]
+struct CubeLut {
+    size: usize,
+    domain_min: [f32; 3],
+    domain_max: [f32; 3],
+    table: Vec<[f32; 3]>,
+}
+
+fn parse_cube_triplet(parts: &[&str], line_no: usize) -> Result<[f32; 3], String> {
+    if parts.len() != 3 {
+        return Err(format!("Line {}: expected 3 values", line_no));
+    }
+    let mut values = [0.0f32; 3];
+    for (value, part) in values.iter_mut().zip(parts) {
+        *value = part
+            .parse()
+            .map_err(|_| format!("Line {}: invalid number '{}'", line_no, part))?;
+    }
+    Ok(values)
+}
+
+impl CubeLut {
+    fn parse(text: &str) -> Result<Self, String> {
+        let mut size = None;
+        let mut domain_min = [0.0; 3];
+        let mut domain_max = [1.0; 3];
+        let mut table = Vec::new();
+
+        for (i, raw) in text.lines().enumerate() {
+            let line_no = i + 1;
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts[0] {
+                "TITLE" => {}
+                "LUT_1D_SIZE" => return Err("1D LUTs are not supported".to_string()),
+                "LUT_3D_SIZE" => {
+                    let n: usize = parts
+                        .get(1)
+                        .and_then(|v| v.parse().ok())
+                        .ok_or_else(|| format!("Line {}: invalid LUT_3D_SIZE", line_no))?;
+                    if !(2..=MAX_LUT_SIZE).contains(&n) {
+                        return Err(format!(
+                            "LUT size {} is out of range (2-{})",
+                            n, MAX_LUT_SIZE
+                        ));
+                    }
+                    size = Some(n);
+                }
+                "DOMAIN_MIN" => domain_min = parse_cube_triplet(&parts[1..], line_no)?,
+                "DOMAIN_MAX" => domain_max = parse_cube_triplet(&parts[1..], line_no)?,
+                _ => table.push(parse_cube_triplet(&parts, line_no)?),
+            }
+        }
+
+        let size = size.ok_or_else(|| "Missing LUT_3D_SIZE".to_string())?;
+        if table.len() != size * size * size {
+            return Err(format!(
+                "Expected {} LUT entries, found {}",
+                size * size * size,
+                table.len()
+            ));
+        }
+        if (0..3).any(|c| domain_max[c] <= domain_min[c]) {
+            return Err("DOMAIN_MAX must be greater than DOMAIN_MIN".to_string());
+        }
+        Ok(Self {
+            size,
+            domain_min,
+            domain_max,
+            table,
+        })
+    }
+
+    fn entry(&self, r: usize, g: usize, b: usize) -> [f32; 3] {
+        self.table[r + g * self.size + b * self.size * self.size]
+    }
+
+    /// Map an RGB triple (in the LUT domain) with trilinear interpolation.
+    fn sample(&self, rgb: [f32; 3]) -> [f32; 3] {
+        let max_index = (self.size - 1) as f32;
+        let pos = [0, 1, 2].map(|c| {
+            let t = (rgb[c] - self.domain_min[c]) / (self.domain_max[c] - self.domain_min[c]);
+            t.clamp(0.0, 1.0) * max_index
+        });
+        let base = pos.map(|p| (p.floor() as usize).min(self.size - 2));
+        let frac = [0, 1, 2].map(|c| pos[c] - base[c] as f32);
+
+        let mut out = [0.0f32; 3];
+        for corner in 0..8 {
+            let (dr, dg, db) = (corner & 1, (corner >> 1) & 1, (corner >> 2) & 1);
+            let weight = (if dr == 1 { frac[0] } else { 1.0 - frac[0] })
+                * (if dg == 1 { frac[1] } else { 1.0 - frac[1] })
+                * (if db == 1 { frac[2] } else { 1.0 - frac[2] });
+            if weight == 0.0 {
+                continue;
+            }
+            let value = self.entry(base[0] + dr, base[1] + dg, base[2] + db);
+            for (acc, v) in out.iter_mut().zip(value) {
+                *acc += v * weight;
+            }
+        }
+        out
+    }
+}
+
+/// Color-grade images through a `.cube` 3D LUT. Alpha is preserved.
+pub fn apply_lut_images(
+    input_paths: Vec<String>,
+    lut_path: String,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let lut = match fs::read_to_string(&lut_path)
+        .map_err(|e| format!("Cannot read LUT file: {}", e))
+        .and_then(|text| CubeLut::parse(&text))
+    {
+        Ok(lut) => Arc::new(lut),
+        Err(e) => return BatchProgress::all_failed(&input_paths, e),
+    };
+
+    batch_process(
+        "apply_lut_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mut rgba = img.to_rgba8();
+
+            let span = [0, 1, 2].map(|c| lut.domain_max[c] - lut.domain_min[c]);
+            for pixel in rgba.pixels_mut() {
+                let rgb = [0, 1, 2].map(|c| lut.domain_min[c] + pixel[c] as f32 / 255.0 * span[c]);
+                let graded = lut.sample(rgb);
+                for (channel, value) in pixel.0.iter_mut().zip(graded) {
+                    *channel = (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+                }
+            }
+
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-graded.{}", stem, ext));
+
+            save_in_original_format(&DynamicImage::ImageRgba8(rgba), input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- Vignette ---
+
+/// Brightness multiplier at `dist` (0 at the center, 1 at the corners).
+fn vignette_factor(dist: f32, strength: f32, radius: f32) -> f32 {
+    if radius >= 1.0 {
+        return 1.0;
+    }
+    let falloff = ((dist - radius) / (1.0 - radius)).max(0.0);
+    (1.0 - strength * falloff).clamp(0.0, 1.0)
+}
+
+/// Darken edges outside `radius` (a fraction of the half-diagonal, so corners sit at 1.0).
+pub fn vignette_images(
+    input_paths: Vec<String>,
+    strength: f32,
+    radius: f32,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    batch_process(
+        "vignette_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mut rgba = img.to_rgba8();
+
+            let (cx, cy) = (w as f32 / 2.0, h as f32 / 2.0);
+            let half_diagonal = (cx * cx + cy * cy).sqrt().max(f32::EPSILON);
+            for (x, y, pixel) in rgba.enumerate_pixels_mut() {
+                let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+                let dist = (dx * dx + dy * dy).sqrt() / half_diagonal;
+                let factor = vignette_factor(dist, strength, radius);
+                for channel in pixel.0.iter_mut().take(3) {
+                    *channel = (*channel as f32 * factor).round() as u8;
+                }
+            }
+
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-vignette.{}", stem, ext));
+
+            save_in_original_format(&DynamicImage::ImageRgba8(rgba), input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- Rotate ---
+
+/// Rotate clockwise by `angle` degrees. Right angles are exact pixel moves; any
+/// other angle is interpolated bilinearly with `background` filling the uncovered
+/// corners. Without `expand_canvas` the result is centered on the original size,
+/// cropping or padding as needed.
+fn rotate_image(
+    img: &DynamicImage,
+    angle: f32,
+    background: Rgba<u8>,
+    expand_canvas: bool,
+) -> image::RgbaImage {
+    let (w, h) = (img.width(), img.height());
+    let degrees = angle.rem_euclid(360.0);
+    let near = |target: f32| (degrees - target).abs() < 0.01;
+
+    let rotated = if near(0.0) || near(360.0) {
+        img.to_rgba8()
+    } else if near(90.0) {
+        image::imageops::rotate90(&img.to_rgba8())
+    } else if near(180.0) {
+        image::imageops::rotate180(&img.to_rgba8())
+    } else if near(270.0) {
+        image::imageops::rotate270(&img.to_rgba8())
+    } else {
+        let theta = degrees.to_radians();
+        let (out_w, out_h) = if expand_canvas {
+            rotated_bounds(w, h, theta)
+        } else {
+            (w, h)
+        };
+        return rotate_padded(&img.to_rgba8(), theta, background, out_w, out_h);
+    };
+
+    if expand_canvas || rotated.dimensions() == (w, h) {
+        return rotated;
+    }
+    let mut canvas = image::RgbaImage::from_pixel(w, h, background);
+    image::imageops::replace(
+        &mut canvas,
+        &rotated,
+        (w as i64 - rotated.width() as i64) / 2,
+        (h as i64 - rotated.height() as i64) / 2,
+    );
+    canvas
+}
+
+/// Rotate images clockwise by `angle` degrees, filling uncovered areas with
+/// `background_color`. `expand_canvas` grows the output to fit the whole image.
+#[allow(clippy::too_many_arguments)]
+pub fn rotate_images(
+    input_paths: Vec<String>,
+    angle: f32,
+    background_color: [u8; 4],
+    expand_canvas: bool,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let background = Rgba(background_color);
+    batch_process(
+        "rotate_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let rotated = rotate_image(&img, angle, background, expand_canvas);
+            let (out_w, out_h) = rotated.dimensions();
+
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-rotated.{}", stem, ext));
+
+            save_in_original_format(&DynamicImage::ImageRgba8(rotated), input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, out_w, out_h)),
+            ))
+        },
+    )
+}
+
+// --- Selective metadata strip ---
+
+/// Remove only the EXIF tags named in `strip_tags` (labels from `read_metadata`).
+/// JPEGs are copied byte for byte apart from the edited EXIF segment; other
+/// formats fall back to a full re-encode, which drops all metadata.
+pub fn strip_metadata_selective(
+    input_paths: Vec<String>,
+    strip_tags: Vec<String>,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let tags = match crate::metadata_ops::exif_tags_for_labels(&strip_tags) {
+        Ok(tags) if !tags.is_empty() => tags,
+        Ok(_) => {
+            return BatchProgress::all_failed(&input_paths, "No EXIF tags selected".to_string())
+        }
+        Err(e) => return BatchProgress::all_failed(&input_paths, e),
+    };
+
+    batch_process(
+        "strip_metadata_selective",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let ext = get_extension(input_path);
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-stripped.{}", stem, ext));
+
+            if matches!(ext.as_str(), "jpg" | "jpeg") {
+                let (w, h) = read_dimensions(input_path)?;
+                let mut bytes =
+                    fs::read(input_path).map_err(|e| format!("Cannot read file: {}", e))?;
+                crate::metadata_ops::strip_jpeg_exif_tags(&mut bytes, &tags)?;
+                fs::write(&output_path, &bytes).map_err(|e| format!("Cannot write file: {}", e))?;
+                return Ok((
+                    output_path.to_string_lossy().to_string(),
+                    Some((w, h, w, h)),
+                ));
+            }
+
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            save_in_original_format(&img, input_path, &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- Flatten transparency ---
+
+/// Composite `img` over an opaque `background`: `fg * a + bg * (1 - a)` per channel.
+pub(crate) fn flatten_alpha(img: &DynamicImage, background: [u8; 3]) -> image::RgbImage {
+    let rgba = img.to_rgba8();
+    image::RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let alpha = a as u32;
+        let blend =
+            |fg: u8, bg: u8| ((fg as u32 * alpha + bg as u32 * (255 - alpha) + 127) / 255) as u8;
+        image::Rgb([
+            blend(r, background[0]),
+            blend(g, background[1]),
+            blend(b, background[2]),
+        ])
+    })
+}
+
+/// Remove alpha by blending onto `background_color`. PNG sources are written as
+/// JPEG since nothing transparent is left; other formats keep their extension.
+pub fn flatten_transparency(
+    input_paths: Vec<String>,
+    background_color: [u8; 3],
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    batch_process(
+        "flatten_transparency",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let flat = DynamicImage::ImageRgb8(flatten_alpha(&img, background_color));
+
+            let ext = match get_extension(input_path).as_str() {
+                "png" => "jpg".to_string(),
+                other => other.to_string(),
+            };
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-flat.{}", stem, ext));
+
+            save_in_original_format(&flat, &output_path.to_string_lossy(), &output_path)?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+// --- Alpha masks ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MaskToAlphaResult {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Alpha channel as grayscale: 0 (transparent) is black, 255 (opaque) is white.
+fn alpha_mask(img: &DynamicImage) -> Result<image::GrayImage, String> {
+    if !img.color().has_alpha() {
+        return Err("Image has no alpha channel".to_string());
+    }
+    let rgba = img.to_rgba8();
+    Ok(image::GrayImage::from_fn(
+        rgba.width(),
+        rgba.height(),
+        |x, y| image::Luma([rgba.get_pixel(x, y)[3]]),
+    ))
+}
+
+/// Use `mask` luminance as the alpha of `img`; both must share dimensions.
+fn apply_alpha_mask(img: &DynamicImage, mask: &DynamicImage) -> Result<image::RgbaImage, String> {
+    if (img.width(), img.height()) != (mask.width(), mask.height()) {
+        return Err(format!(
+            "Mask is {}×{} but the image is {}×{}",
+            mask.width(),
+            mask.height(),
+            img.width(),
+            img.height()
+        ));
+    }
+    let mask = mask.to_luma8();
+    let mut rgba = img.to_rgba8();
+    for (pixel, alpha) in rgba.pixels_mut().zip(mask.pixels()) {
+        pixel[3] = alpha[0];
+    }
+    Ok(rgba)
+}
+
+/// Save each image's alpha channel as a grayscale `{stem}-mask.png`.
+/// Images without an alpha channel fail individually.
+pub fn alpha_to_mask(
+    input_paths: Vec<String>,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    batch_process(
+        "alpha_to_mask",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let mask = alpha_mask(&img)?;
+
+            let stem = file_stem(input_path);
+            let output_path = out_dir.join(format!("{}-mask.png", stem));
+            mask.save_with_format(&output_path, ImageFormat::Png)
+                .map_err(|e| format!("Cannot save PNG: {}", e))?;
+            Ok((
+                output_path.to_string_lossy().to_string(),
+                Some((w, h, w, h)),
+            ))
+        },
+    )
+}
+
+/// Combine an image with a grayscale mask (white = opaque) into `{stem}-alpha.png`.
+pub fn mask_to_alpha(
+    image_path: &str,
+    mask_path: &str,
+    output_dir: &str,
+) -> Result<MaskToAlphaResult, String> {
+    let img = load_image(image_path)?;
+    let mask = load_image(mask_path)?;
+    let rgba = apply_alpha_mask(&img, &mask)?;
+
+    let out_dir = Path::new(output_dir);
+    ensure_output_dir(out_dir)?;
+    let output_path = out_dir.join(format!("{}-alpha.png", file_stem(image_path)));
+    rgba.save_with_format(&output_path, ImageFormat::Png)
+        .map_err(|e| format!("Cannot save PNG: {}", e))?;
+
+    Ok(MaskToAlphaResult {
+        output_path: output_path.to_string_lossy().to_string(),
+        width: rgba.width(),
+        height: rgba.height(),
+    })
+}
+
+// --- Channel split ---
+
+/// Split into grayscale `red`, `green`, `blue` (and `alpha` when present) planes.
+fn split_channels(img: &DynamicImage) -> Vec<(&'static str, image::GrayImage)> {
+    let has_alpha = img.color().has_alpha();
+    let rgba = img.to_rgba8();
+    let (w, h) = rgba.dimensions();
+    let plane_count = if has_alpha { 4 } else { 3 };
+    let mut planes: Vec<Vec<u8>> = (0..plane_count)
+        .map(|_| Vec::with_capacity(w as usize * h as usize))
+        .collect();
+    for pixel in rgba.pixels() {
+        for (plane, &value) in planes.iter_mut().zip(pixel.0.iter()) {
+            plane.push(value);
+        }
+    }
+    ["red", "green", "blue", "alpha"]
+        .into_iter()
+        .zip(planes)
+        .filter_map(|(name, plane)| image::GrayImage::from_raw(w, h, plane).map(|g| (name, g)))
+        .collect()
+}
+
+/// Save each color channel as a grayscale `{stem}-{channel}.png`. Successful
+/// inputs get one result per channel file; `completed` still counts inputs.
+pub fn image_channels_split(
+    input_paths: Vec<String>,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    let channel_outputs: Arc<Mutex<HashMap<String, Vec<String>>>> = Arc::default();
+    let outputs = Arc::clone(&channel_outputs);
+    let mut progress = batch_process(
+        "image_channels_split",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let img = load_image(input_path)?;
+            let (w, h) = (img.width(), img.height());
+            let stem = file_stem(input_path);
+
+            let mut paths = Vec::new();
+            for (name, plane) in split_channels(&img) {
+                let output_path = out_dir.join(format!("{}-{}.png", stem, name));
+                plane
+                    .save_with_format(&output_path, ImageFormat::Png)
+                    .map_err(|e| format!("Cannot save {} channel: {}", name, e))?;
+                paths.push(output_path.to_string_lossy().to_string());
+            }
+
+            let first = paths.first().cloned().unwrap_or_default();
+            if let Ok(mut map) = outputs.lock() {
+                map.insert(input_path.to_string(), paths);
+            }
+            Ok((first, Some((w, h, w, h))))
+        },
+    );
+
+    let Ok(channel_outputs) = channel_outputs.lock() else {
+        return progress;
+    };
+    progress.results = progress
+        .results
+        .into_iter()
+        .flat_map(|result| match channel_outputs.get(&result.input_path) {
+            Some(paths) if result.success => paths
+                .iter()
+                .map(|path| ProcessingResult {
+                    output_path: path.clone(),
+                    output_size: file_size(path),
+                    ..result.clone()
+                })
+                .collect(),
+            _ => vec![result],
+        })
+        .collect();
+    progress
+}
+
+// --- Mosaic ---
+
+/// Allowed mosaic tile edge, in pixels.
+pub const MOSAIC_TILE_SIZE_RANGE: std::ops::RangeInclusive<u32> = 2..=256;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MosaicResult {
+    pub output_path: String,
+    pub tiles_used: usize,
+    pub unique_tiles: usize,
+}
+
+fn average_rgb<'a>(pixels: impl Iterator<Item = &'a image::Rgb<u8>>) -> [f32; 3] {
+    let mut sum = [0u64; 3];
+    let mut count = 0u64;
+    for px in pixels {
+        for (total, channel) in sum.iter_mut().zip(px.0) {
+            *total += channel as u64;
+        }
+        count += 1;
+    }
+    sum.map(|total| total as f32 / count.max(1) as f32)
+}
+
+fn closest_tile(color: [f32; 3], tile_colors: &[[f32; 3]]) -> usize {
+    let distance = |c: &[f32; 3]| {
+        c.iter()
+            .zip(color)
+            .map(|(a, b)| (a - b) * (a - b))
+            .sum::<f32>()
+    };
+    tile_colors
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| distance(a).total_cmp(&distance(b)))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Load every decodable image in `tile_dir`, cropped and resized to `tile_size`.
+/// Files that fail to decode are skipped.
+fn load_mosaic_tiles(tile_dir: &str, tile_size: u32) -> Result<Vec<image::RgbImage>, String> {
+    let entries =
+        fs::read_dir(tile_dir).map_err(|e| format!("Cannot read folder '{}': {}", tile_dir, e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && ImageFormat::from_path(path).is_ok())
+        .collect();
+    paths.sort();
+
+    let tiles: Vec<image::RgbImage> = paths
+        .par_iter()
+        .filter_map(|path| load_image(&path.to_string_lossy()).ok())
+        .map(|img| {
+            img.resize_to_fill(tile_size, tile_size, image::imageops::FilterType::Triangle)
+                .to_rgb8()
+        })
+        .collect();
+
+    if tiles.is_empty() {
+        return Err(format!("No readable images found in '{}'", tile_dir));
+    }
+    Ok(tiles)
+}
+
+/// Rebuild `target_path` from `tile_size` blocks, each replaced by the tile whose
+/// average color is nearest in RGB space. Edge blocks are clipped.
+pub fn create_mosaic(
+    target_path: &str,
+    tile_dir: &str,
+    tile_size: u32,
+    output_path: &str,
+) -> Result<MosaicResult, String> {
+    if !MOSAIC_TILE_SIZE_RANGE.contains(&tile_size) {
+        return Err(format!(
+            "Tile size must be between {} and {} px",
+            MOSAIC_TILE_SIZE_RANGE.start(),
+            MOSAIC_TILE_SIZE_RANGE.end()
+        ));
+    }
+    let format = ImageFormat::from_path(output_path)
+        .map_err(|_| format!("Unsupported output format for '{}'", output_path))?;
+
+    let target = load_image(target_path)?.to_rgb8();
+    let tiles = load_mosaic_tiles(tile_dir, tile_size)?;
+    let tile_colors: Vec<[f32; 3]> = tiles.iter().map(|t| average_rgb(t.pixels())).collect();
+
+    let (width, height) = target.dimensions();
+    let mut canvas = image::RgbImage::new(width, height);
+    let mut used = HashSet::new();
+    let mut tiles_used = 0;
+    for y in (0..height).step_by(tile_size as usize) {
+        for x in (0..width).step_by(tile_size as usize) {
+            let block_w = tile_size.min(width - x);
+            let block_h = tile_size.min(height - y);
+            let block = image::imageops::crop_imm(&target, x, y, block_w, block_h);
+            let color = average_rgb(block.to_image().pixels());
+
+            let index = closest_tile(color, &tile_colors);
+            image::imageops::replace(&mut canvas, &tiles[index], x as i64, y as i64);
+            used.insert(index);
+            tiles_used += 1;
+        }
+    }
+
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    DynamicImage::ImageRgb8(canvas)
+        .save_with_format(output_path, format)
+        .map_err(|e| format!("Cannot save mosaic: {}", e))?;
+
+    Ok(MosaicResult {
+        output_path: output_path.to_string(),
+        tiles_used,
+        unique_tiles: used.len(),
+    })
+}
+
+// --- Collage ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollageResult {
+    pub output_path: String,
+    pub rows: u32,
+    pub cols: u32,
+    pub total_width: u32,
+    pub total_height: u32,
+}
+
+/// Length of `count` cells of `cell` pixels separated by `gap`, if it fits the
+/// generated-image limit.
+fn collage_extent(count: u32, cell: u32, gap: u32) -> Option<u32> {
+    let total = count as u64 * cell as u64 + count.saturating_sub(1) as u64 * gap as u64;
+    u32::try_from(total)
+        .ok()
+        .filter(|&t| t > 0 && t <= MAX_GENERATED_DIMENSION)
+}
+
+/// Top-left corner of the cell at `index`, filling rows left to right.
+fn collage_cell_origin(index: u32, cols: u32, cell_w: u32, cell_h: u32, gap: u32) -> (i64, i64) {
+    let (row, col) = ((index / cols) as i64, (index % cols) as i64);
+    (col * (cell_w + gap) as i64, row * (cell_h + gap) as i64)
+}
+
+/// Lay out `rows × cols` images on a grid, each center-cropped to the cell size.
+/// The background shows through the gaps; it keeps its alpha in PNG and WebP output.
+#[allow(clippy::too_many_arguments)]
+pub fn create_collage(
+    input_paths: &[String],
+    rows: u32,
+    cols: u32,
+    cell_width: u32,
+    cell_height: u32,
+    gap: u32,
+    background_color: [u8; 4],
+    output_path: &str,
+) -> Result<CollageResult, String> {
+    if rows == 0 || cols == 0 {
+        return Err("Collage needs at least one row and one column".to_string());
+    }
+    if cell_width == 0 || cell_height == 0 {
+        return Err("Cell dimensions cannot be zero".to_string());
+    }
+    if input_paths.len() as u64 != rows as u64 * cols as u64 {
+        return Err(format!(
+            "A {}×{} collage needs {} images, got {}",
+            rows,
+            cols,
+            rows as u64 * cols as u64,
+            input_paths.len()
+        ));
+    }
+    let (Some(total_width), Some(total_height)) = (
+        collage_extent(cols, cell_width, gap),
+        collage_extent(rows, cell_height, gap),
+    ) else {
+        return Err(format!(
+            "Collage dimensions must be between 1 and {} px",
+            MAX_GENERATED_DIMENSION
+        ));
+    };
+
+    let cells: Vec<image::RgbaImage> = input_paths
+        .par_iter()
+        .map(|path| {
+            load_image(path).map(|img| {
+                img.resize_to_fill(
+                    cell_width,
+                    cell_height,
+                    image::imageops::FilterType::Lanczos3,
+                )
+                .to_rgba8()
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut canvas =
+        image::RgbaImage::from_pixel(total_width, total_height, Rgba(background_color));
+    for (index, cell) in (0u32..).zip(&cells) {
+        let (x, y) = collage_cell_origin(index, cols, cell_width, cell_height, gap);
+        image::imageops::overlay(&mut canvas, cell, x, y);
+    }
+
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    let collage = match get_extension(output_path).as_str() {
+        "jpg" | "jpeg" | "bmp" => {
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(canvas).to_rgb8())
+        }
+        _ => DynamicImage::ImageRgba8(canvas),
+    };
+    save_in_original_format(&collage, output_path, Path::new(output_path))?;
+
+    Ok(CollageResult {
+        output_path: output_path.to_string(),
+        rows,
+        cols,
+        total_width,
+        total_height,
+    })
+}
+
+// --- Crop ---
+
+fn parse_ratio(ratio: &str) -> Option<(f64, f64)> {
+    let parts: Vec<&str> = ratio.split(':').collect();
+    if parts.len() == 2 {
+        if let (Ok(w), Ok(h)) = (parts[0].parse::<f64>(), parts[1].parse::<f64>()) {
+            if w > 0.0 && h > 0.0 {
+                return Some((w, h));
+            }
+        }
+    }
+    None
+}
+
+/// Rotate/flip an image so it displays upright according to its EXIF Orientation (1–8).
+fn apply_exif_orientation(img: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+/// Saliency is computed on a copy downscaled to this longest side.
+const SALIENCY_MAX_SIDE: u32 = 512;
+
+/// Top-left corner of the `crop_w × crop_h` window with the most detail, measured
+/// as the summed absolute Laplacian of the grayscale image. Ties (e.g. flat
+/// images) keep the centered window.
+fn smart_crop_origin(img: &DynamicImage, crop_w: u32, crop_h: u32) -> (u32, u32) {
+    let (w, h) = (img.width(), img.height());
+    let centered = (w.saturating_sub(crop_w) / 2, h.saturating_sub(crop_h) / 2);
+    if crop_w >= w && crop_h >= h {
+        return centered;
+    }
+
+    let scale = (SALIENCY_MAX_SIDE as f64 / w.max(h) as f64).min(1.0);
+    let sw = ((w as f64 * scale).round() as u32).max(1);
+    let sh = ((h as f64 * scale).round() as u32).max(1);
+    let cw = ((crop_w as f64 * scale).round() as u32).clamp(1, sw);
+    let ch = ((crop_h as f64 * scale).round() as u32).clamp(1, sh);
+    let gray = img
+        .resize_exact(sw, sh, image::imageops::FilterType::Triangle)
+        .to_luma8();
+
+    // Summed-area table of |Laplacian| so every window sum is O(1)
+    let stride = sw as usize + 1;
+    let mut integral = vec![0u64; stride * (sh as usize + 1)];
+    let at = |x: i64, y: i64| {
+        let x = x.clamp(0, sw as i64 - 1) as u32;
+        let y = y.clamp(0, sh as i64 - 1) as u32;
+        gray.get_pixel(x, y).0[0] as i64
+    };
+    for y in 0..sh as usize {
+        let mut row_sum = 0u64;
+        for x in 0..sw as usize {
+            let (xi, yi) = (x as i64, y as i64);
+            let laplacian =
+                4 * at(xi, yi) - at(xi - 1, yi) - at(xi + 1, yi) - at(xi, yi - 1) - at(xi, yi + 1);
+            row_sum += laplacian.unsigned_abs();
+            integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+        }
+    }
+    let window_sum = |x: usize, y: usize| {
+        let (x2, y2) = (x + cw as usize, y + ch as usize);
+        integral[y2 * stride + x2] + integral[y * stride + x]
+            - integral[y * stride + x2]
+            - integral[y2 * stride + x]
+    };
+
+    let mut best = ((sw - cw) as usize / 2, (sh - ch) as usize / 2);
+    let mut best_sum = window_sum(best.0, best.1);
+    for y in 0..=(sh - ch) as usize {
+        for x in 0..=(sw - cw) as usize {
+            let sum = window_sum(x, y);
+            if sum > best_sum {
+                best_sum = sum;
+                best = (x, y);
+            }
+        }
+    }
+
+    (
+        ((best.0 as f64 / scale).round() as u32).min(w.saturating_sub(crop_w)),
+        ((best.1 as f64 / scale).round() as u32).min(h.saturating_sub(crop_h)),
+    )
+}
+
+/// Origin and side of the largest square centered in a `width` x `height` image.
+fn centered_square(width: u32, height: u32) -> (u32, u32, u32) {
+    let side = width.min(height);
+    ((width - side) / 2, (height - side) / 2, side)
+}
+
+/// Clamp a crop rectangle to the image bounds; `None` when nothing is left.
+fn clamp_crop_rect(
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+    img_w: u32,
+    img_h: u32,
+) -> Option<(u32, u32, u32, u32)> {
+    let x = x.min(img_w);
+    let y = y.min(img_h);
+    let w = w.min(img_w - x);
+    let h = h.min(img_h - y);
+    (w > 0 && h > 0).then_some((x, y, w, h))
+}
+
+/// Crop images by ratio/anchor or by an explicit rectangle.
+///
+/// `mode` "ratio" (default) derives the crop from `ratio`/`anchor`, or uses
+/// `crop_x`/`crop_y` when both are set; "rect" takes `crop_x`, `crop_y`,
+/// `target_width`, `target_height` as the exact rectangle, clamped to the image.
+/// The "smart" anchor places the crop over the most detailed region.
+///
+/// With `auto_orient`, the EXIF rotation is applied before any crop math so
+/// anchors and coordinates refer to the image as the user sees it. The output is
+/// re-encoded without EXIF, so it carries the default (normal) orientation.
+#[allow(clippy::too_many_arguments)]
+pub fn crop_images(
+    input_paths: Vec<String>,
+    ratio: String,
+    anchor: String,
+    target_width: u32,
+    target_height: u32,
+    crop_x: Option<u32>,
+    crop_y: Option<u32>,
+    auto_orient: bool,
+    mode: String,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    if !matches!(mode.as_str(), "ratio" | "rect" | "square") {
+        return BatchProgress::all_failed(&input_paths, format!("Unknown crop mode: {}", mode));
+    }
+
+    batch_process(
+        "crop_images",
+        &input_paths,
+        &output_dir,
+        &app_handle,
+        &cancel,
+        options,
+        move |input_path, out_dir| {
+            let mut img = load_image(input_path)?;
+            if auto_orient {
+                if let Some(orientation) = crate::metadata_ops::read_exif_orientation(input_path) {
+                    img = apply_exif_orientation(img, orientation);
+                }
+            }
+            let (orig_w, orig_h) = (img.width(), img.height());
+
+            // Square mode: centered square on the shorter side, other parameters ignored
+            let rect = if mode == "square" {
+                let (x, y, side) = centered_square(orig_w, orig_h);
+                Some((x, y, side, side))
+            } else {
+                None
+            };
+            // Rect mode, or explicit crop_x/crop_y (free-form rectangle drawn by
+            // the user on the preview): use the rectangle directly
+            let rect = rect.or(match (mode.as_str(), crop_x, crop_y) {
+                ("rect", x, y) => {
+                    Some((x.unwrap_or(0), y.unwrap_or(0), target_width, target_height))
+                }
+                (_, Some(x), Some(y)) => Some((x, y, target_width, target_height)),
+                _ => None,
+            });
+            if let Some((rx, ry, rw, rh)) = rect {
+                let (cx, cy, cw, ch) = clamp_crop_rect(rx, ry, rw, rh, orig_w, orig_h)
+                    .ok_or_else(|| "Crop dimensions cannot be zero".to_string())?;
+                let cropped = img.crop_imm(cx, cy, cw, ch);
+                let ext = get_extension(input_path);
+                let stem = file_stem(input_path);
+                let output_path = out_dir.join(format!("{}-cropped.{}", stem, ext));
+                save_in_original_format(&cropped, input_path, &output_path)?;
                 return Ok((
                     output_path.to_string_lossy().to_string(),
                     Some((orig_w, orig_h, cw, ch)),
@@ -803,6 +3736,16 @@ pub fn crop_images(
                 "top-right" => (orig_w.saturating_sub(crop_w), 0),
                 "bottom-left" => (0, orig_h.saturating_sub(crop_h)),
                 "bottom-right" => (orig_w.saturating_sub(crop_w), orig_h.saturating_sub(crop_h)),
+                "smart" => {
+                    let (sx, sy) = smart_crop_origin(&img, crop_w, crop_h);
+                    tracing::debug!(
+                        input_path,
+                        center_x = sx + crop_w / 2,
+                        center_y = sy + crop_h / 2,
+                        "Smart crop center"
+                    );
+                    (sx, sy)
+                }
                 _ => (
                     (orig_w.saturating_sub(crop_w)) / 2,
                     (orig_h.saturating_sub(crop_h)) / 2,
@@ -824,10 +3767,503 @@ pub fn crop_images(
     )
 }
 
+/// Crop each image to the largest region with the exact `ratio` (e.g. "16:9"),
+/// placed at `anchor`. Delegates to `crop_images`, whose ratio mode already
+/// derives the crop size from the image itself.
+pub fn crop_to_ratio(
+    input_paths: Vec<String>,
+    ratio: String,
+    anchor: String,
+    output_dir: String,
+    app_handle: tauri::AppHandle,
+    cancel: Arc<AtomicBool>,
+    options: BatchOptions,
+) -> BatchProgress {
+    if parse_ratio(&ratio).is_none() {
+        return BatchProgress::all_failed(&input_paths, format!("Invalid crop ratio: {}", ratio));
+    }
+    crop_images(
+        input_paths,
+        ratio,
+        anchor,
+        0,
+        0,
+        None,
+        None,
+        false,
+        "ratio".to_string(),
+        output_dir,
+        app_handle,
+        cancel,
+        options,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn gradient_border_keeps_image_alpha() {
+        let img = image::RgbaImage::from_pixel(4, 4, Rgba([10, 20, 30, 0]));
+        let bordered = add_gradient_border(&img, 2, &two_stop_gradient(), 0.0).unwrap();
+        assert_eq!(bordered.dimensions(), (8, 8));
+        assert_eq!(bordered.get_pixel(4, 4), &Rgba([10, 20, 30, 0]));
+        assert_eq!(bordered.get_pixel(0, 4).0[3], 255);
+        assert!(bordered.get_pixel(0, 4).0[0] < bordered.get_pixel(7, 4).0[0]);
+    }
+
+    #[test]
+    fn perceptual_hash_ignores_brightness_but_not_content() {
+        let pattern = |offset: f64| {
+            image::GrayImage::from_fn(64, 64, |x, y| {
+                let (x, y) = (x as f64, y as f64);
+                let v = 120.0 + 50.0 * (x * 0.21).sin() + 40.0 * (y * 0.13 + x * 0.07).cos();
+                image::Luma([(v + offset) as u8])
+            })
+        };
+        let base = pattern(0.0);
+        let mut inverted = base.clone();
+        image::imageops::invert(&mut inverted);
+
+        let a = perceptual_hash(&DynamicImage::ImageLuma8(base));
+        let b = perceptual_hash(&DynamicImage::ImageLuma8(pattern(10.0)));
+        let c = perceptual_hash(&DynamicImage::ImageLuma8(inverted));
+        assert!((a ^ b).count_ones() <= DEFAULT_DEDUP_THRESHOLD);
+        assert!((a ^ c).count_ones() > DEFAULT_DEDUP_THRESHOLD);
+    }
+
+    #[test]
+    fn group_by_hash_links_transitively() {
+        // 0–1 and 1–2 are within 2 bits; 3 is far from everything
+        let hashes = [0b0000u64, 0b0011, 0b1111, u64::MAX];
+        assert_eq!(group_by_hash(&hashes, 2), vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn convert_options_defaults_and_tiff_codecs() {
+        let defaults = ConvertOptions::default();
+        assert_eq!(defaults.jpeg_quality, Some(75));
+        assert_eq!(defaults.webp_quality, Some(100.0));
+        let partial: ConvertOptions = serde_json::from_str(r#"{"jpeg_quality": 90}"#).unwrap();
+        assert_eq!(partial.jpeg_quality, Some(90));
+        assert_eq!(partial.tiff_compression.as_deref(), Some("none"));
+
+        assert_eq!(TiffCodec::parse("LZW").unwrap(), TiffCodec::Lzw);
+        assert!(TiffCodec::parse("jpeg").is_err());
+        assert!(ConvertSettings::resolve(&ConvertOptions {
+            tiff_compression: Some("zip".to_string()),
+            ..ConvertOptions::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn compressed_tiff_keeps_channel_layout() {
+        let dir = std::env::temp_dir().join("rustine-tiff-colortype-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let cases = [
+            (
+                "rgb.tiff",
+                DynamicImage::ImageRgb8(image::RgbImage::new(3, 2)),
+            ),
+            (
+                "gray.tiff",
+                DynamicImage::ImageLuma8(image::GrayImage::new(3, 2)),
+            ),
+            (
+                "rgb16.tiff",
+                DynamicImage::ImageRgb16(image::ImageBuffer::new(3, 2)),
+            ),
+        ];
+        for (name, img) in cases {
+            let path = dir.join(name);
+            write_compressed_tiff(&img, &path, TiffCodec::Lzw).unwrap();
+            assert_eq!(image::open(&path).unwrap().color(), img.color(), "{}", name);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn output_formats_are_normalized_and_deduplicated() {
+        let formats = normalize_formats(&[
+            "WebP".to_string(),
+            "jpg".to_string(),
+            "webp".to_string(),
+            " ".to_string(),
+        ]);
+        assert_eq!(formats, vec!["webp", "jpg"]);
+    }
+
+    #[test]
+    fn output_format_aliases_share_one_entry() {
+        let formats = normalize_formats(&[
+            "jpg".to_string(),
+            "JPEG".to_string(),
+            "tif".to_string(),
+            "tiff".to_string(),
+        ]);
+        assert_eq!(formats, vec!["jpg", "tiff"]);
+    }
+
+    #[test]
+    fn pixel_sort_only_touches_runs_within_thresholds() {
+        let gray = |v: u8| Rgba([v, v, v, 255]);
+        let mut line = vec![
+            gray(250),
+            gray(120),
+            gray(60),
+            gray(90),
+            gray(10),
+            gray(200),
+            gray(100),
+        ];
+        sort_pixel_runs(&mut line, SortChannel::Luminance, 50, 210);
+        let values: Vec<u8> = line.iter().map(|p| p[0]).collect();
+        assert_eq!(values, vec![250, 60, 90, 120, 10, 100, 200]);
+    }
+
+    #[test]
+    fn hue_channel_spans_the_byte_range() {
+        assert_eq!(SortChannel::Hue.value(&Rgba([255, 0, 0, 255])), 0);
+        assert_eq!(SortChannel::Hue.value(&Rgba([0, 255, 0, 255])), 85);
+        assert_eq!(SortChannel::Hue.value(&Rgba([0, 0, 255, 255])), 170);
+        assert!(SortChannel::parse("green").is_err());
+    }
+
+    #[test]
+    fn centered_square_uses_shorter_side() {
+        assert_eq!(centered_square(400, 300), (50, 0, 300));
+        assert_eq!(centered_square(300, 401), (0, 50, 300));
+        assert_eq!(centered_square(64, 64), (0, 0, 64));
+    }
+
+    /// 2x2x2 identity LUT in `.cube` order (red fastest).
+    const IDENTITY_CUBE: &str = "TITLE \"identity\"\n# comment\nLUT_3D_SIZE 2\n\
+        0 0 0\n1 0 0\n0 1 0\n1 1 0\n0 0 1\n1 0 1\n0 1 1\n1 1 1\n";
+
+    #[test]
+    fn cube_lut_identity_interpolates_exactly() {
+        let lut = CubeLut::parse(IDENTITY_CUBE).unwrap();
+        assert_eq!(lut.size, 2);
+        let out = lut.sample([0.25, 0.5, 0.9]);
+        for (got, want) in out.iter().zip([0.25, 0.5, 0.9]) {
+            assert!((got - want).abs() < 1e-6);
+        }
+        assert_eq!(lut.sample([1.0, 0.0, 1.0]), [1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn cube_lut_rejects_malformed_files() {
+        assert!(CubeLut::parse("0 0 0\n").is_err());
+        assert!(CubeLut::parse("LUT_3D_SIZE 2\n0 0 0\n").is_err());
+        assert!(CubeLut::parse("LUT_1D_SIZE 16\n").is_err());
+        assert!(CubeLut::parse("LUT_3D_SIZE 2\n0 0 x\n").is_err());
+    }
+
+    #[test]
+    fn vignette_darkens_only_past_radius() {
+        assert_eq!(vignette_factor(0.3, 1.0, 0.5), 1.0);
+        assert!((vignette_factor(0.75, 1.0, 0.5) - 0.5).abs() < 1e-6);
+        assert_eq!(vignette_factor(1.0, 1.0, 0.5), 0.0);
+        assert!((vignette_factor(1.0, 0.4, 0.5) - 0.6).abs() < 1e-6);
+        assert_eq!(vignette_factor(1.0, 1.0, 1.0), 1.0);
+    }
+
+    #[test]
+    fn megapixel_target_scales_area() {
+        assert_eq!(megapixel_dimensions(4000, 3000, 2.0), (1633, 1225));
+        assert_eq!(megapixel_dimensions(4000, 3000, 5.0), (2582, 1936));
+    }
+
+    #[test]
+    fn vertical_seam_follows_low_energy_column() {
+        let (w, h) = (4, 3);
+        let mut energy = vec![9.0; w * h];
+        for (y, col) in [2, 1, 1].into_iter().enumerate() {
+            energy[y * w + col] = 0.0;
+        }
+        assert_eq!(find_vertical_seam(&energy, w, h), vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn seam_carving_shrinks_only_requested_axis() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(12, 8, |x, _| {
+            Rgba([(x * 20) as u8, 0, 0, 255])
+        }));
+        let cancel = AtomicBool::new(false);
+        let mut reports = Vec::new();
+        assert!(seam_carve(&img, 0, 0, &cancel, |_, _| {}).is_err());
+        let carved = seam_carve(&img, 1, 0, &cancel, |done, total| {
+            reports.push((done, total))
+        })
+        .unwrap();
+        assert_eq!((carved.width(), carved.height()), (1, 8));
+        assert_eq!(reports, vec![(10, 11), (11, 11)]);
+
+        let carved = seam_carve(&img, 12, 5, &cancel, |_, _| {}).unwrap();
+        assert_eq!((carved.width(), carved.height()), (12, 5));
+        assert!(seam_carve(&img, 6, 4, &cancel, |_, _| {}).is_err());
+        assert!(seam_carve(&img, 20, 0, &cancel, |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn batch_csv_reports_reduction_for_successes_only() {
+        let mut progress = BatchProgress::all_failed(&["/in/b.png".to_string()], "bad".into());
+        progress.results.insert(
+            0,
+            ProcessingResult {
+                input_path: "/in/a, b.jpg".to_string(),
+                output_path: "/out/a.jpg".to_string(),
+                success: true,
+                error: None,
+                input_size: 2000,
+                output_size: 500,
+                input_width: 40,
+                input_height: 30,
+                output_width: 20,
+                output_height: 15,
+            },
+        );
+        let csv = String::from_utf8(batch_results_csv(&progress).unwrap()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "input_path,output_path,success,error,input_size_bytes,output_size_bytes,\
+             size_reduction_pct,input_width,input_height,output_width,output_height"
+        );
+        assert_eq!(
+            lines[1],
+            "\"/in/a, b.jpg\",/out/a.jpg,true,,2000,500,75.00,40,30,20,15"
+        );
+        assert_eq!(lines[2], "/in/b.png,,false,bad,0,0,,0,0,0,0");
+    }
+
+    #[test]
+    fn flatten_alpha_blends_onto_background() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(3, 1, |x, _| match x {
+            0 => Rgba([200, 100, 0, 255]),
+            1 => Rgba([200, 100, 0, 0]),
+            _ => Rgba([200, 100, 0, 128]),
+        }));
+        let flat = flatten_alpha(&img, [0, 0, 255]);
+        assert_eq!(flat.get_pixel(0, 0).0, [200, 100, 0]);
+        assert_eq!(flat.get_pixel(1, 0).0, [0, 0, 255]);
+        assert_eq!(flat.get_pixel(2, 0).0, [100, 50, 127]);
+    }
+
+    #[test]
+    fn alpha_mask_round_trips() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(2, 1, |x, _| {
+            Rgba([10, 20, 30, if x == 0 { 0 } else { 255 }])
+        }));
+        let mask = alpha_mask(&img).unwrap();
+        assert_eq!(mask.as_raw(), &vec![0, 255]);
+
+        let opaque = DynamicImage::ImageRgb8(image::RgbImage::new(2, 1));
+        assert_eq!(
+            alpha_mask(&opaque).unwrap_err(),
+            "Image has no alpha channel"
+        );
+
+        let rgba = apply_alpha_mask(&opaque, &DynamicImage::ImageLuma8(mask)).unwrap();
+        assert_eq!(rgba.get_pixel(0, 0)[3], 0);
+        assert_eq!(rgba.get_pixel(1, 0)[3], 255);
+
+        let wrong_size = DynamicImage::ImageLuma8(image::GrayImage::new(3, 1));
+        assert!(apply_alpha_mask(&opaque, &wrong_size).is_err());
+    }
+
+    #[test]
+    fn split_channels_extracts_each_plane() {
+        let rgb =
+            DynamicImage::ImageRgb8(image::RgbImage::from_pixel(2, 2, image::Rgb([10, 20, 30])));
+        let planes = split_channels(&rgb);
+        let names: Vec<&str> = planes.iter().map(|(n, _)| *n).collect();
+        assert_eq!(names, ["red", "green", "blue"]);
+        assert_eq!(planes[1].1.get_pixel(1, 1)[0], 20);
+
+        let rgba = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(2, 1, Rgba([1, 2, 3, 4])));
+        let planes = split_channels(&rgba);
+        assert_eq!(planes.len(), 4);
+        assert_eq!(planes[3].0, "alpha");
+        assert_eq!(planes[3].1.as_raw(), &vec![4, 4]);
+    }
+
+    #[test]
+    fn rotate_image_right_angles_are_exact() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_fn(4, 2, |x, y| {
+            Rgba([x as u8, y as u8, 0, 255])
+        }));
+        let bg = Rgba([9, 9, 9, 255]);
+
+        let turned = rotate_image(&img, 90.0, bg, true);
+        assert_eq!(turned.dimensions(), (2, 4));
+        // Clockwise: the bottom-left source pixel lands top-left.
+        assert_eq!(turned.get_pixel(0, 0), &Rgba([0, 1, 0, 255]));
+
+        let cropped = rotate_image(&img, -270.0, bg, false);
+        assert_eq!(cropped.dimensions(), (4, 2));
+        assert_eq!(cropped.get_pixel(0, 0), &bg);
+        assert_eq!(cropped.get_pixel(1, 0), &Rgba([1, 1, 0, 255]));
+    }
+
+    #[test]
+    fn rotate_image_arbitrary_angle_sizes_canvas() {
+        let img = DynamicImage::ImageRgba8(image::RgbaImage::from_pixel(
+            100,
+            50,
+            Rgba([200, 0, 0, 255]),
+        ));
+        let bg = Rgba([0, 0, 255, 255]);
+
+        let expanded = rotate_image(&img, 30.0, bg, true);
+        // 100·cos30 + 50·sin30 ≈ 111.6, 100·sin30 + 50·cos30 ≈ 93.3
+        assert_eq!(expanded.dimensions(), (112, 94));
+        assert_eq!(expanded.get_pixel(0, 0), &bg);
+        assert_eq!(expanded.get_pixel(56, 47), &Rgba([200, 0, 0, 255]));
+
+        let kept = rotate_image(&img, 30.0, bg, false);
+        assert_eq!(kept.dimensions(), (100, 50));
+        assert_eq!(kept.get_pixel(0, 0), &bg);
+    }
+
+    #[test]
+    fn collage_layout_includes_gaps() {
+        assert_eq!(collage_extent(3, 100, 10), Some(320));
+        assert_eq!(collage_extent(1, 100, 10), Some(100));
+        assert_eq!(collage_extent(2, MAX_GENERATED_DIMENSION, 0), None);
+        assert_eq!(collage_cell_origin(0, 3, 100, 50, 10), (0, 0));
+        assert_eq!(collage_cell_origin(4, 3, 100, 50, 10), (110, 60));
+    }
+
+    #[test]
+    fn mosaic_picks_nearest_tile_color() {
+        let tiles = [[0.0, 0.0, 0.0], [250.0, 10.0, 10.0], [255.0, 255.0, 255.0]];
+        assert_eq!(closest_tile([200.0, 30.0, 20.0], &tiles), 1);
+        assert_eq!(closest_tile([180.0, 180.0, 190.0], &tiles), 2);
+
+        let block = image::RgbImage::from_fn(2, 1, |x, _| {
+            if x == 0 {
+                image::Rgb([0, 100, 200])
+            } else {
+                image::Rgb([100, 100, 0])
+            }
+        });
+        assert_eq!(average_rgb(block.pixels()), [50.0, 100.0, 100.0]);
+    }
+
+    #[test]
+    fn canny_strength_lowers_thresholds() {
+        let (weak_low, weak_high) = canny_thresholds(0.0);
+        let (strong_low, strong_high) = canny_thresholds(1.0);
+        assert!(strong_high < weak_high);
+        assert!(weak_low < weak_high && strong_low < strong_high);
+        assert_eq!(canny_thresholds(5.0), canny_thresholds(1.0));
+    }
+
+    #[test]
+    fn white_balance_keeps_green_as_reference() {
+        let neutral = white_balance_multipliers(NEUTRAL_KELVIN);
+        assert!(neutral.iter().all(|m| (m - 1.0).abs() < 1e-6));
+
+        let tungsten = white_balance_multipliers(2700.0);
+        assert_eq!(tungsten[1], 1.0);
+        assert!(tungsten[0] < 1.0 && tungsten[2] > 1.0);
+
+        let shade = white_balance_multipliers(10000.0);
+        assert_eq!(shade[1], 1.0);
+        assert!(shade[2] < 1.0);
+    }
+
+    #[test]
+    fn require_exists_policy_never_creates_dirs() {
+        let missing = std::env::temp_dir().join("rustine-policy-test-missing/nested");
+        let policy = CreateDirPolicy::parse(Some("require_exists")).unwrap();
+        assert!(policy.prepare(&missing).is_err());
+        assert!(!missing.exists());
+        assert!(policy.prepare(&std::env::temp_dir()).is_ok());
+        assert_eq!(CreateDirPolicy::parse(None).unwrap(), CreateDirPolicy::Auto);
+        assert!(CreateDirPolicy::parse(Some("sometimes")).is_err());
+    }
+
+    #[test]
+    fn smart_crop_finds_detailed_region() {
+        // Flat gray image with a checkerboard patch in the bottom-right corner
+        let img = image::GrayImage::from_fn(200, 100, |x, y| {
+            if x >= 150 && y >= 50 && (x / 4 + y / 4) % 2 == 0 {
+                image::Luma([255])
+            } else {
+                image::Luma([128])
+            }
+        });
+        let (x, y) = smart_crop_origin(&DynamicImage::ImageLuma8(img), 60, 60);
+        assert!(x >= 130 && y == 40, "got ({}, {})", x, y);
+    }
+
+    #[test]
+    fn smart_crop_on_flat_image_stays_centered() {
+        let img = DynamicImage::new_rgb8(100, 50);
+        assert_eq!(smart_crop_origin(&img, 40, 40), (30, 5));
+    }
+
+    #[test]
+    fn crop_rect_is_clamped_to_image_bounds() {
+        assert_eq!(
+            clamp_crop_rect(10, 20, 50, 50, 100, 100),
+            Some((10, 20, 50, 50))
+        );
+        assert_eq!(
+            clamp_crop_rect(80, 90, 50, 50, 100, 100),
+            Some((80, 90, 20, 10))
+        );
+        assert_eq!(clamp_crop_rect(100, 0, 10, 10, 100, 100), None);
+        assert_eq!(clamp_crop_rect(0, 0, 0, 10, 100, 100), None);
+    }
+
+    #[test]
+    fn run_with_timeout_returns_fast_results() {
+        let outcome = run_with_timeout(
+            Arc::new(|input: &str, _: &Path| Ok((input.to_string(), None))),
+            "a.png",
+            Path::new("out"),
+            1_000,
+        );
+        assert_eq!(outcome, Ok(("a.png".to_string(), None)));
+    }
+
+    #[test]
+    fn run_with_timeout_gives_up_on_hung_files() {
+        let outcome = run_with_timeout(
+            Arc::new(|_: &str, _: &Path| {
+                thread::sleep(Duration::from_millis(500));
+                Ok((String::new(), None))
+            }),
+            "slow.png",
+            Path::new("out"),
+            20,
+        );
+        assert_eq!(outcome, Err("Timed out after 20ms".to_string()));
+    }
+
+    #[test]
+    fn resize_preset_lookup_is_case_insensitive() {
+        let preset = find_resize_preset("HD").unwrap();
+        assert_eq!(
+            (preset.mode, preset.width, preset.height),
+            ("fit_down", 1920, 1080)
+        );
+        assert_eq!(find_resize_preset("web").unwrap().mode, "width");
+    }
+
+    #[test]
+    fn unknown_resize_preset_lists_valid_names() {
+        let err = find_resize_preset("poster").unwrap_err();
+        assert!(err.contains("'poster'"));
+        assert!(err.contains("icon, thumbnail, hd, 4k, web"));
+    }
+
     #[test]
     fn all_failed_sets_every_result_to_error() {
         let paths = vec!["a.png".to_string(), "b.png".to_string()];
@@ -854,6 +4290,127 @@ mod tests {
         assert_eq!(r.output_width, 800);
     }
 
+    #[test]
+    fn exif_orientation_swaps_dimensions_for_quarter_turns() {
+        let img = DynamicImage::new_rgba8(40, 20);
+        for orientation in [5, 6, 7, 8] {
+            let oriented = apply_exif_orientation(img.clone(), orientation);
+            assert_eq!((oriented.width(), oriented.height()), (20, 40));
+        }
+        for orientation in [1, 2, 3, 4] {
+            let oriented = apply_exif_orientation(img.clone(), orientation);
+            assert_eq!((oriented.width(), oriented.height()), (40, 20));
+        }
+    }
+
+    #[test]
+    fn deuteranopia_maps_pure_red_to_olive() {
+        let matrix = color_blindness_matrix("deuteranopia").unwrap();
+        assert_eq!(simulate_pixel([255, 0, 0], &matrix), [147, 147, 0]);
+    }
+
+    #[test]
+    fn color_blindness_simulation_keeps_greys() {
+        for kind in ["protanopia", "deuteranopia", "tritanopia"] {
+            let matrix = color_blindness_matrix(kind).unwrap();
+            assert_eq!(simulate_pixel([0, 0, 0], &matrix), [0, 0, 0]);
+            assert_eq!(simulate_pixel([255, 255, 255], &matrix), [255, 255, 255]);
+        }
+        assert!(color_blindness_matrix("achromatopsia").is_none());
+    }
+
+    fn two_stop_gradient() -> Vec<GradientStop> {
+        vec![
+            GradientStop {
+                position: 0.0,
+                color: [0, 0, 0, 255],
+            },
+            GradientStop {
+                position: 1.0,
+                color: [255, 255, 255, 255],
+            },
+        ]
+    }
+
+    #[test]
+    fn sample_gradient_interpolates_and_clamps() {
+        let stops = two_stop_gradient();
+        assert_eq!(sample_gradient(&stops, -1.0), [0, 0, 0, 255]);
+        assert_eq!(sample_gradient(&stops, 0.5), [128, 128, 128, 255]);
+        assert_eq!(sample_gradient(&stops, 2.0), [255, 255, 255, 255]);
+    }
+
+    #[test]
+    fn linear_gradient_runs_left_to_right_at_zero_degrees() {
+        let canvas = render_gradient(10, 2, "linear", 0.0, &two_stop_gradient()).unwrap();
+        assert!(canvas.get_pixel(0, 0)[0] < canvas.get_pixel(9, 0)[0]);
+        assert_eq!(canvas.get_pixel(0, 0), canvas.get_pixel(0, 1));
+        assert!(render_gradient(10, 2, "conic", 0.0, &two_stop_gradient()).is_err());
+    }
+
+    #[test]
+    fn text_block_splits_lines_and_spaces_them() {
+        let font = FontArc::try_from_slice(BUNDLED_FONT).unwrap();
+        let scale = PxScale::from(20.0);
+        let block = measure_text_block("Copyright\r\nAll rights", &font, scale, 20.0, 1.5);
+        assert_eq!(block.lines, vec!["Copyright", "All rights"]);
+        assert_eq!(block.line_height, 30);
+        assert_eq!(block.height, 30 + 20);
+        assert_eq!(block.width, *block.line_widths.iter().max().unwrap());
+    }
+
+    #[test]
+    fn text_sprite_rotation_dimensions() {
+        let font = FontArc::try_from_slice(BUNDLED_FONT).unwrap();
+        let scale = PxScale::from(24.0);
+        let block = measure_text_block("CONFIDENTIAL", &font, scale, 24.0, 1.2);
+        let color = Rgba([255, 0, 0, 128]);
+        let (w, h) = (block.width as u32, block.height as u32);
+
+        let flat = render_text_sprite(&block, 0.0, scale, &font, color, 0.0);
+        assert_eq!(flat.dimensions(), (w, h));
+        assert!(flat.pixels().any(|p| p[3] > 0));
+        assert!(flat.pixels().all(|p| p[3] <= 128 && p[0] == 255));
+
+        let upright = render_text_sprite(&block, 0.0, scale, &font, color, 90.0);
+        assert_eq!(upright.dimensions(), (h, w));
+
+        let diagonal = render_text_sprite(&block, 0.0, scale, &font, color, 45.0);
+        let expected = (w + h) as f32 * std::f32::consts::FRAC_1_SQRT_2;
+        let (dw, dh) = diagonal.dimensions();
+        assert!((dw as f32 - expected).abs() <= 1.0 && (dh as f32 - expected).abs() <= 1.0);
+        assert!(diagonal.pixels().any(|p| p[3] > 0));
+    }
+
+    #[test]
+    fn fit_within_preserves_aspect_ratio() {
+        assert_eq!(fit_within(4000, 2000, 1000, 1000), (1000, 500));
+        assert_eq!(fit_within(1000, 3000, 600, 600), (200, 600));
+        assert_eq!(fit_within(10_000, 1, 100, 100), (100, 1));
+    }
+
+    #[test]
+    fn nearest_filter_doubles_pixel_perfect() {
+        let mut src = image::RgbaImage::new(3, 2);
+        for (x, y, pixel) in src.enumerate_pixels_mut() {
+            *pixel = Rgba([(x * 80) as u8, (y * 120) as u8, 200, 255]);
+        }
+        let filter = parse_filter_type(Some("nearest")).unwrap();
+        let doubled = DynamicImage::ImageRgba8(src.clone())
+            .resize_exact(6, 4, filter)
+            .to_rgba8();
+        for (x, y, pixel) in doubled.enumerate_pixels() {
+            assert_eq!(pixel, src.get_pixel(x / 2, y / 2));
+        }
+        assert!(parse_filter_type(Some("bicubic")).is_err());
+    }
+
+    #[test]
+    fn bundled_font_parses() {
+        assert!(FontArc::try_from_slice(BUNDLED_FONT).is_ok());
+        assert!(load_watermark_font(Some("/nonexistent/font.ttf")).is_ok());
+    }
+
     #[test]
     fn build_result_failure() {
         let r = build_result("/tmp/bad.jpg", Err("decode error".to_string()), None);
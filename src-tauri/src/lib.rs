@@ -1,7 +1,9 @@
+mod cancel;
 mod color_ops;
 mod favicon_ops;
 mod gif_ops;
 mod image_ops;
+mod logging;
 mod metadata_ops;
 mod pdf_builder_ops;
 mod pdf_ops;
@@ -14,21 +16,32 @@ mod sprite_ops;
 mod svg_ops;
 mod utils;
 
-use color_ops::PaletteResult;
-use favicon_ops::FaviconResult;
-use gif_ops::AnimationResult;
-use image_ops::BatchProgress;
-use metadata_ops::ImageMetadata;
+use color_ops::{
+    AverageColorResult, ColorDistanceResult, ColorInfo, HistogramResult, PaletteImageResult,
+    PaletteResult,
+};
+use favicon_ops::{FaviconResult, IcoResult};
+use gif_ops::{AnimationResult, GifInfo, SlideshowResult};
+use image_ops::{
+    BatchCsvResult, BatchOptions, BatchProgress, CollageResult, ConvertOptions, CreateDirPolicy,
+    DeduplicateResult, GradientResult, GradientStop, MaskToAlphaResult, MosaicResult,
+    PlaceholderResult, ValidationResult,
+};
+use metadata_ops::{FormatDetectResult, ImageMetadata, MetadataExportResult};
 use pdf_builder_ops::{MergePdfOptions, MergePdfResult, PageThumbnail, PdfBuilderItem};
 use pdf_ops::{
-    ImagesToPdfResult, PdfCompressResult, PdfExtractionResult, PdfProtectResult, PdfToImagesResult,
+    FontListResult, ImagesToPdfResult, PdfCompressResult, PdfDocumentMetadata, PdfExtractionResult,
+    PdfIntegrityResult, PdfPageInfo, PdfProtectResult, PdfRasterizeResult, PdfToImagesResult,
+    SearchResult, SearchablePdfResult, SinglePageRenderResult,
+};
+use pdf_split_ops::{
+    PageLabelRange, PdfCropMarginsResult, PdfDuplicateResult, PdfPageEditResult, PdfSplitResult,
 };
-use pdf_split_ops::PdfSplitResult;
 use pdf_watermark_ops::PdfWatermarkResult;
 use pdfium_render::prelude::Pdfium;
-use qr_ops::QrResult;
+use qr_ops::{QrBatchItem, QrBatchResult, QrMatrixResult, QrResult};
 use rename_ops::RenameResult;
-use sprite_ops::SpriteSheetResult;
+use sprite_ops::{AnimationGroup, SpriteCssResult, SpriteSheetResult};
 use std::path::{Component, Path};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, LazyLock};
@@ -60,6 +73,9 @@ fn require_pdfium(state: &PdfiumState) -> Result<Arc<SendPdfium>, String> {
         .ok_or_else(|| "Pdfium library not found — PDF features are unavailable. Please reinstall the application.".to_string())
 }
 
+/// Resolved pdfium library path (empty when not found), for display in the settings UI.
+pub struct PdfiumPath(String);
+
 /// Shared cancellation flag for batch operations.
 /// Set to `true` to request early termination of the current batch.
 pub struct CancellationToken(pub Arc<AtomicBool>);
@@ -163,6 +179,32 @@ fn validate_path(path: &str) -> Result<(), String> {
     Ok(())
 }
 
+/// Shortest accepted per-file timeout; anything lower times out every file while
+/// its worker keeps running in the background.
+const MIN_PER_FILE_TIMEOUT_MS: u64 = 100;
+
+/// Parse batch settings and enforce the directory policy before any work starts.
+fn batch_options(
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<&str>,
+    output_dir: &str,
+) -> Result<BatchOptions, String> {
+    if let Some(timeout_ms) = per_file_timeout_ms.filter(|&t| t < MIN_PER_FILE_TIMEOUT_MS) {
+        return Err(format!(
+            "Per-file timeout of {}ms is too short (minimum {}ms)",
+            timeout_ms, MIN_PER_FILE_TIMEOUT_MS
+        ));
+    }
+    let create_dir_policy = CreateDirPolicy::parse(create_dir_policy)?;
+    if create_dir_policy == CreateDirPolicy::RequireExists {
+        create_dir_policy.prepare(Path::new(output_dir))?;
+    }
+    Ok(BatchOptions {
+        per_file_timeout_ms,
+        create_dir_policy,
+    })
+}
+
 fn validate_paths(paths: &[String]) -> Result<(), String> {
     for p in paths {
         validate_path(p)?;
@@ -170,6 +212,7 @@ fn validate_paths(paths: &[String]) -> Result<(), String> {
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 async fn compress_webp(
     app_handle: tauri::AppHandle,
@@ -177,13 +220,29 @@ async fn compress_webp(
     input_paths: Vec<String>,
     quality: f32,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+    lossless: Option<bool>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        image_ops::compress_to_webp(input_paths, quality, output_dir, app_handle, cancel)
+        image_ops::compress_to_webp(
+            input_paths,
+            quality,
+            lossless.unwrap_or(false),
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
@@ -197,33 +256,71 @@ async fn compress_jpeg(
     input_paths: Vec<String>,
     quality: u8,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        image_ops::compress_to_jpeg(input_paths, quality, output_dir, app_handle, cancel)
+        image_ops::compress_to_jpeg(
+            input_paths,
+            quality,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
     Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 async fn convert_images(
     app_handle: tauri::AppHandle,
     token: tauri::State<'_, CancellationToken>,
     input_paths: Vec<String>,
-    output_format: String,
+    // Deprecated: kept for callers that still send a single format
+    output_format: Option<String>,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+    convert_options: Option<ConvertOptions>,
+    output_formats: Option<Vec<String>>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    let output_formats = match (output_formats, output_format) {
+        (Some(formats), _) if !formats.is_empty() => formats,
+        (_, Some(format)) => vec![format],
+        _ => return Err("No output format given".to_string()),
+    };
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        image_ops::convert_images(input_paths, output_format, output_dir, app_handle, cancel)
+        image_ops::convert_images(
+            input_paths,
+            output_formats,
+            convert_options.unwrap_or_default(),
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
@@ -269,9 +366,21 @@ async fn resize_images(
     height: u32,
     percentage: u32,
     output_dir: String,
+    fill_color: Option<[u8; 4]>,
+    allow_upscale: Option<bool>,
+    filter: Option<String>,
+    preset: Option<String>,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+    megapixels: Option<f64>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
@@ -281,9 +390,15 @@ async fn resize_images(
             width,
             height,
             percentage,
+            megapixels,
+            fill_color,
+            allow_upscale.unwrap_or(false),
+            filter,
+            preset,
             output_dir,
             app_handle,
             cancel,
+            options,
         )
     })
     .await
@@ -297,13 +412,54 @@ async fn strip_metadata(
     token: tauri::State<'_, CancellationToken>,
     input_paths: Vec<String>,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::strip_metadata(input_paths, output_dir, app_handle, cancel, options)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn strip_metadata_selective(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    strip_tags: Vec<String>,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        image_ops::strip_metadata(input_paths, output_dir, app_handle, cancel)
+        image_ops::strip_metadata_selective(
+            input_paths,
+            strip_tags,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
@@ -322,11 +478,25 @@ async fn add_watermark(
     font_size: f32,
     color: String,
     output_dir: String,
+    font_path: Option<String>,
+    line_spacing: Option<f32>,
+    rotation_degrees: Option<f32>,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    if let Some(ref path) = font_path {
+        validate_path(path)?;
+    }
     let font_size = font_size.clamp(1.0, 500.0);
     let opacity = opacity.clamp(0.0, 1.0);
+    let line_spacing = line_spacing.unwrap_or(1.2).clamp(0.5, 5.0);
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
@@ -337,9 +507,13 @@ async fn add_watermark(
             opacity,
             font_size,
             color,
+            font_path,
+            line_spacing,
+            rotation_degrees.unwrap_or(0.0),
             output_dir,
             app_handle,
             cancel,
+            options,
         )
     })
     .await
@@ -358,12 +532,19 @@ async fn add_image_watermark(
     opacity: f32,
     scale: f32,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_path(&watermark_path)?;
     validate_paths(&input_paths)?;
     let opacity = opacity.clamp(0.0, 1.0);
     let scale = scale.clamp(0.01, 10.0);
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
@@ -376,6 +557,7 @@ async fn add_image_watermark(
             output_dir,
             app_handle,
             cancel,
+            options,
         )
     })
     .await
@@ -384,58 +566,33 @@ async fn add_image_watermark(
 }
 
 #[tauri::command]
-async fn images_to_pdf(
+async fn apply_lut_images(
     app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
     input_paths: Vec<String>,
-    output_path: String,
-) -> Result<ImagesToPdfResult, String> {
-    validate_path(&output_path)?;
+    lut_path: String,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_path(&lut_path)?;
     validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        pdf_ops::images_to_pdf(input_paths, &output_path, &app_handle)
-    })
-    .await
-    .map_err(|e| format!("Task failed: {}", e))?;
-    Ok(result)
-}
-
-#[tauri::command]
-async fn read_metadata(file_path: String) -> Result<ImageMetadata, String> {
-    validate_path(&file_path)?;
-    tokio::task::spawn_blocking(move || metadata_ops::read_image_metadata(&file_path))
-        .await
-        .map_err(|e| format!("Task failed: {}", e))?
-}
-
-#[tauri::command]
-async fn get_pdf_page_count(
-    pdfium_state: tauri::State<'_, PdfiumState>,
-    pdf_path: String,
-) -> Result<usize, String> {
-    validate_path(&pdf_path)?;
-    let pdfium = require_pdfium(&pdfium_state)?;
-    tokio::task::spawn_blocking(move || {
-        pdf_builder_ops::get_pdf_page_count(&pdf_path, pdfium.inner())
-    })
-    .await
-    .map_err(|e| format!("Task failed: {}", e))?
-}
-
-#[tauri::command]
-async fn generate_pdf_thumbnails(
-    pdfium_state: tauri::State<'_, PdfiumState>,
-    file_paths: Vec<String>,
-    start_page: Option<usize>,
-    max_pages: Option<usize>,
-) -> Result<Vec<PageThumbnail>, String> {
-    validate_paths(&file_paths)?;
-    let pdfium = require_pdfium(&pdfium_state)?;
-    let result = tokio::task::spawn_blocking(move || {
-        pdf_builder_ops::generate_thumbnails_batch(
-            file_paths,
-            pdfium.inner(),
-            start_page,
-            max_pages,
+        image_ops::apply_lut_images(
+            input_paths,
+            lut_path,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
         )
     })
     .await
@@ -443,74 +600,117 @@ async fn generate_pdf_thumbnails(
     Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-async fn merge_to_pdf(
+async fn vignette_images(
     app_handle: tauri::AppHandle,
-    items: Vec<PdfBuilderItem>,
-    options: MergePdfOptions,
-) -> Result<MergePdfResult, String> {
-    validate_path(&options.output_path)?;
-    let item_paths: Vec<String> = items.iter().map(|i| i.source_path.clone()).collect();
-    validate_paths(&item_paths)?;
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    strength: f32,
+    radius: f32,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let strength = strength.clamp(0.0, 1.0);
+    let radius = radius.clamp(0.0, 1.0);
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        pdf_builder_ops::merge_to_pdf(items, options, &app_handle)
+        image_ops::vignette_images(
+            input_paths,
+            strength,
+            radius,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
     Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-async fn optimize_images(
+async fn pixel_sort_images(
     app_handle: tauri::AppHandle,
     token: tauri::State<'_, CancellationToken>,
     input_paths: Vec<String>,
+    direction: String,
+    threshold_low: u8,
+    threshold_high: u8,
+    channel: String,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        image_ops::optimize_lossless(input_paths, output_dir, app_handle, cancel)
+        image_ops::pixel_sort_images(
+            input_paths,
+            direction,
+            threshold_low,
+            threshold_high,
+            channel,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
     Ok(result)
 }
 
-#[tauri::command]
 #[allow(clippy::too_many_arguments)]
-async fn crop_images(
+#[tauri::command]
+async fn line_extract_images(
     app_handle: tauri::AppHandle,
     token: tauri::State<'_, CancellationToken>,
     input_paths: Vec<String>,
-    ratio: String,
-    anchor: String,
-    width: u32,
-    height: u32,
-    crop_x: Option<u32>,
-    crop_y: Option<u32>,
+    strength: f32,
+    invert: bool,
     output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
 ) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
     validate_paths(&input_paths)?;
-    let width = width.max(1);
-    let height = height.max(1);
+    let strength = strength.clamp(0.0, 1.0);
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
     let cancel = (*token).0.clone();
     cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        image_ops::crop_images(
+        image_ops::line_extract_images(
             input_paths,
-            ratio,
-            anchor,
-            width,
-            height,
-            crop_x,
-            crop_y,
+            strength,
+            invert,
             output_dir,
             app_handle,
             cancel,
+            options,
         )
     })
     .await
@@ -519,29 +719,39 @@ async fn crop_images(
 }
 
 #[tauri::command]
-async fn pdf_to_images(
+async fn white_balance_images(
     app_handle: tauri::AppHandle,
-    pdfium_state: tauri::State<'_, PdfiumState>,
-    pdf_path: String,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    temperature_kelvin: f32,
     output_dir: String,
-    format: String,
-    dpi: u32,
-    output_stem: Option<String>,
-) -> Result<PdfToImagesResult, String> {
-    validate_path(&pdf_path)?;
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
-    let output_stem = output_stem.map(|s| utils::sanitize_stem(&s)).transpose()?;
-    let dpi = dpi.clamp(72, 1200);
-    let pdfium = require_pdfium(&pdfium_state)?;
+    validate_paths(&input_paths)?;
+    let (min_k, max_k) = image_ops::WHITE_BALANCE_KELVIN_RANGE;
+    if !(min_k..=max_k).contains(&temperature_kelvin) {
+        return Err(format!(
+            "Color temperature must be between {} K and {} K",
+            min_k, max_k
+        ));
+    }
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        pdf_ops::pdf_to_images(
-            &pdf_path,
-            &output_dir,
-            pdfium.inner(),
-            &format,
-            dpi,
-            output_stem.as_deref(),
-            &app_handle,
+        image_ops::white_balance_images(
+            input_paths,
+            temperature_kelvin,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
         )
     })
     .await
@@ -550,23 +760,32 @@ async fn pdf_to_images(
 }
 
 #[tauri::command]
-async fn split_pdf(
+async fn simulate_color_blindness(
     app_handle: tauri::AppHandle,
-    pdf_path: String,
-    ranges: String,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    blindness_type: String,
     output_dir: String,
-    output_stem: Option<String>,
-) -> Result<PdfSplitResult, String> {
-    validate_path(&pdf_path)?;
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
-    let output_stem = output_stem.map(|s| utils::sanitize_stem(&s)).transpose()?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        pdf_split_ops::split_pdf(
-            &pdf_path,
-            &ranges,
-            &output_dir,
-            output_stem.as_deref(),
-            &app_handle,
+        image_ops::simulate_color_blindness(
+            input_paths,
+            blindness_type,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
         )
     })
     .await
@@ -575,65 +794,1281 @@ async fn split_pdf(
 }
 
 #[tauri::command]
-async fn extract_palette(image_path: String, num_colors: usize) -> Result<PaletteResult, String> {
-    validate_path(&image_path)?;
-    tokio::task::spawn_blocking(move || color_ops::extract_palette(&image_path, num_colors))
-        .await
-        .map_err(|e| format!("Task failed: {}", e))?
+async fn generate_placeholder(
+    width: u32,
+    height: u32,
+    fill_color: [u8; 4],
+    text: Option<String>,
+    text_color: [u8; 4],
+    output_path: String,
+) -> Result<PlaceholderResult, String> {
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        image_ops::generate_placeholder(width, height, fill_color, text, text_color, &output_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
 }
 
 #[tauri::command]
-async fn compress_pdf_cmd(
-    app_handle: tauri::AppHandle,
-    pdf_path: String,
-    quality: u8,
-    output_dir: String,
-) -> Result<PdfCompressResult, String> {
-    validate_path(&pdf_path)?;
-    validate_path(&output_dir)?;
-    let result = tokio::task::spawn_blocking(move || {
-        pdf_ops::compress_pdf(&pdf_path, quality, &output_dir, &app_handle)
+async fn generate_gradient(
+    width: u32,
+    height: u32,
+    gradient_type: String,
+    angle_degrees: f32,
+    stops: Vec<GradientStop>,
+    output_path: String,
+) -> Result<GradientResult, String> {
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        image_ops::generate_gradient(
+            width,
+            height,
+            &gradient_type,
+            angle_degrees,
+            &stops,
+            &output_path,
+        )
     })
     .await
-    .map_err(|e| format!("Task failed: {}", e))?;
-    Ok(result)
+    .map_err(|e| format!("Task failed: {}", e))?
 }
 
 #[tauri::command]
-async fn generate_favicons(
+async fn flatten_transparency(
     app_handle: tauri::AppHandle,
-    image_path: String,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    background_color: [u8; 3],
     output_dir: String,
-) -> Result<FaviconResult, String> {
-    validate_path(&image_path)?;
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
     validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
     let result = tokio::task::spawn_blocking(move || {
-        favicon_ops::generate_favicons(&image_path, &output_dir, &app_handle)
+        image_ops::flatten_transparency(
+            input_paths,
+            background_color,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
     Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
-async fn create_gif(
+async fn rotate_images(
     app_handle: tauri::AppHandle,
-    image_paths: Vec<String>,
-    delay_ms: u16,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    angle: f32,
+    background_color: [u8; 4],
+    expand_canvas: bool,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    if !angle.is_finite() {
+        return Err("Rotation angle must be a finite number".to_string());
+    }
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::rotate_images(
+            input_paths,
+            angle,
+            background_color,
+            expand_canvas,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn alpha_to_mask(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::alpha_to_mask(input_paths, output_dir, app_handle, cancel, options)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn image_channels_split(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::image_channels_split(input_paths, output_dir, app_handle, cancel, options)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn mask_to_alpha(
+    image_path: String,
+    mask_path: String,
+    output_dir: String,
+) -> Result<MaskToAlphaResult, String> {
+    validate_path(&image_path)?;
+    validate_path(&mask_path)?;
+    validate_path(&output_dir)?;
+    tokio::task::spawn_blocking(move || {
+        image_ops::mask_to_alpha(&image_path, &mask_path, &output_dir)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn create_collage(
+    input_paths: Vec<String>,
+    rows: u32,
+    cols: u32,
+    cell_width: u32,
+    cell_height: u32,
+    gap: u32,
+    background_color: [u8; 4],
+    output_path: String,
+) -> Result<CollageResult, String> {
+    validate_paths(&input_paths)?;
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        image_ops::create_collage(
+            &input_paths,
+            rows,
+            cols,
+            cell_width,
+            cell_height,
+            gap,
+            background_color,
+            &output_path,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn create_mosaic(
+    target_path: String,
+    tile_dir: String,
+    tile_size: u32,
+    output_path: String,
+) -> Result<MosaicResult, String> {
+    validate_path(&target_path)?;
+    validate_path(&tile_dir)?;
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        image_ops::create_mosaic(&target_path, &tile_dir, tile_size, &output_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn image_border_gradient(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    border_width: u32,
+    gradient_stops: Vec<GradientStop>,
+    gradient_angle: f32,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::image_border_gradient(
+            input_paths,
+            border_width,
+            gradient_stops,
+            gradient_angle,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn images_to_pdf_grid(
+    app_handle: tauri::AppHandle,
+    input_paths: Vec<String>,
+    cols_per_page: u32,
+    rows_per_page: u32,
+    page_format: String,
+    margin_px: u32,
+    gap_px: u32,
+    output_path: String,
+) -> Result<ImagesToPdfResult, String> {
+    validate_path(&output_path)?;
+    validate_paths(&input_paths)?;
+    let page_format = page_format.to_lowercase();
+    if !matches!(page_format.as_str(), "a4" | "letter") {
+        return Err(format!("Unknown page format: {}", page_format));
+    }
+    if !(1..=10).contains(&cols_per_page) || !(1..=10).contains(&rows_per_page) {
+        return Err("Grid must be between 1 and 10 cells in each direction".to_string());
+    }
+    let margin = margin_px.min(200) as f32;
+    let gap = gap_px.min(100) as f32;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::images_to_pdf_grid(
+            &input_paths,
+            cols_per_page,
+            rows_per_page,
+            &page_format,
+            margin,
+            gap,
+            &output_path,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn images_to_pdf(
+    app_handle: tauri::AppHandle,
+    input_paths: Vec<String>,
+    output_path: String,
+    archive_mode: Option<bool>,
+    page_format: Option<String>,
+    margin_px: Option<u32>,
+) -> Result<ImagesToPdfResult, String> {
+    validate_path(&output_path)?;
+    validate_paths(&input_paths)?;
+    let archive_mode = archive_mode.unwrap_or(false);
+    let page_format = page_format
+        .unwrap_or_else(|| "fit".to_string())
+        .to_lowercase();
+    if !matches!(page_format.as_str(), "fit" | "a4" | "letter") {
+        return Err(format!("Unknown page format: {}", page_format));
+    }
+    let margin = margin_px.unwrap_or(0).min(200) as f32;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::images_to_pdf(
+            input_paths,
+            &output_path,
+            &page_format,
+            margin,
+            archive_mode,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn image_to_pdf_page(
+    image_path: String,
+    page_width_pt: f32,
+    page_height_pt: f32,
+    x_pt: f32,
+    y_pt: f32,
+    draw_width_pt: f32,
+    draw_height_pt: f32,
+    jpeg_quality: u8,
+    output_path: String,
+) -> Result<String, String> {
+    validate_path(&image_path)?;
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        utils::image_to_pdf_page(
+            &image_path,
+            page_width_pt,
+            page_height_pt,
+            x_pt,
+            y_pt,
+            draw_width_pt,
+            draw_height_pt,
+            jpeg_quality,
+            &output_path,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn validate_images(input_paths: Vec<String>) -> Result<Vec<ValidationResult>, String> {
+    validate_paths(&input_paths)?;
+    tokio::task::spawn_blocking(move || image_ops::validate_images(&input_paths))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))
+}
+
+#[tauri::command]
+async fn deduplicate_images(
+    app_handle: tauri::AppHandle,
+    input_paths: Vec<String>,
+    threshold: Option<u32>,
+    keep: String,
+) -> Result<DeduplicateResult, String> {
+    validate_paths(&input_paths)?;
+    let threshold = threshold
+        .unwrap_or(image_ops::DEFAULT_DEDUP_THRESHOLD)
+        .min(64);
+    tokio::task::spawn_blocking(move || {
+        image_ops::deduplicate_images(&input_paths, threshold, &keep, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn read_metadata(file_path: String) -> Result<ImageMetadata, String> {
+    validate_path(&file_path)?;
+    tokio::task::spawn_blocking(move || metadata_ops::read_image_metadata(&file_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn batch_metadata_export(
+    input_paths: Vec<String>,
+    output_path: String,
+) -> Result<MetadataExportResult, String> {
+    validate_paths(&input_paths)?;
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        metadata_ops::batch_metadata_export(&input_paths, &output_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn batch_results_to_csv(
+    progress: BatchProgress,
+    output_path: String,
+) -> Result<BatchCsvResult, String> {
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || image_ops::batch_results_to_csv(&progress, &output_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn detect_image_format(image_path: String) -> Result<FormatDetectResult, String> {
+    validate_path(&image_path)?;
+    tokio::task::spawn_blocking(move || metadata_ops::detect_image_format(&image_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn get_pdf_page_count(
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_path: String,
+) -> Result<usize, String> {
+    validate_path(&pdf_path)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    tokio::task::spawn_blocking(move || {
+        pdf_builder_ops::get_pdf_page_count(&pdf_path, pdfium.inner())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn generate_pdf_thumbnails(
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    file_paths: Vec<String>,
+    start_page: Option<usize>,
+    max_pages: Option<usize>,
+    thumbnail_format: Option<String>,
+) -> Result<Vec<PageThumbnail>, String> {
+    validate_paths(&file_paths)?;
+    let mime = pdf_builder_ops::thumbnail_mime(thumbnail_format.as_deref())?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_builder_ops::generate_thumbnails_batch(
+            file_paths,
+            pdfium.inner(),
+            start_page,
+            max_pages,
+            mime,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn merge_to_pdf(
+    app_handle: tauri::AppHandle,
+    items: Vec<PdfBuilderItem>,
+    options: MergePdfOptions,
+) -> Result<MergePdfResult, String> {
+    validate_path(&options.output_path)?;
+    let item_paths: Vec<String> = items.iter().map(|i| i.source_path.clone()).collect();
+    validate_paths(&item_paths)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_builder_ops::merge_to_pdf(items, options, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn optimize_images(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::optimize_lossless(input_paths, output_dir, app_handle, cancel, options)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+async fn crop_images(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    ratio: Option<String>,
+    anchor: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    crop_x: Option<u32>,
+    crop_y: Option<u32>,
+    auto_orient: Option<bool>,
+    output_dir: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+    mode: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let mode = mode.unwrap_or_else(|| "ratio".to_string());
+    // Square mode derives everything from the image, so the rest may be omitted
+    let ratio = ratio.unwrap_or_else(|| "free".to_string());
+    let anchor = anchor.unwrap_or_else(|| "center".to_string());
+    let (width, height) = (width.unwrap_or(0), height.unwrap_or(0));
+    // Rect mode reports zero-size rectangles as errors instead of bumping them to 1px
+    let (width, height) = if mode == "rect" {
+        (width, height)
+    } else {
+        (width.max(1), height.max(1))
+    };
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::crop_images(
+            input_paths,
+            ratio,
+            anchor,
+            width,
+            height,
+            crop_x,
+            crop_y,
+            auto_orient.unwrap_or(false),
+            mode,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn crop_to_ratio(
+    app_handle: tauri::AppHandle,
+    token: tauri::State<'_, CancellationToken>,
+    input_paths: Vec<String>,
+    ratio: String,
+    output_dir: String,
+    anchor: String,
+    per_file_timeout_ms: Option<u64>,
+    create_dir_policy: Option<String>,
+) -> Result<BatchProgress, String> {
+    validate_path(&output_dir)?;
+    validate_paths(&input_paths)?;
+    let options = batch_options(
+        per_file_timeout_ms,
+        create_dir_policy.as_deref(),
+        &output_dir,
+    )?;
+    let cancel = (*token).0.clone();
+    cancel.store(false, Ordering::Relaxed);
+    let result = tokio::task::spawn_blocking(move || {
+        image_ops::crop_to_ratio(
+            input_paths,
+            ratio,
+            anchor,
+            output_dir,
+            app_handle,
+            cancel,
+            options,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_to_images(
+    app_handle: tauri::AppHandle,
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_path: String,
+    output_dir: String,
+    format: String,
+    dpi: u32,
+    output_stem: Option<String>,
+) -> Result<PdfToImagesResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let output_stem = output_stem.map(|s| utils::sanitize_stem(&s)).transpose()?;
+    pdf_ops::validate_export_dpi(dpi)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::pdf_to_images(
+            &pdf_path,
+            &output_dir,
+            pdfium.inner(),
+            &format,
+            dpi,
+            output_stem.as_deref(),
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn batch_pdf_to_images(
+    app_handle: tauri::AppHandle,
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_paths: Vec<String>,
+    output_dir: String,
+    format: String,
+    dpi: u32,
+) -> Result<Vec<PdfToImagesResult>, String> {
+    validate_paths(&pdf_paths)?;
+    validate_path(&output_dir)?;
+    pdf_ops::validate_export_dpi(dpi)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    let results = tokio::task::spawn_blocking(move || {
+        pdf_ops::batch_pdf_to_images(
+            &pdf_paths,
+            &output_dir,
+            pdfium.inner(),
+            &format,
+            dpi,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(results)
+}
+
+#[tauri::command]
+async fn pdf_rasterize(
+    app_handle: tauri::AppHandle,
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_path: String,
+    dpi: u32,
+    output_dir: String,
+    password: Option<String>,
+) -> Result<PdfRasterizeResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    pdf_ops::validate_export_dpi(dpi)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::pdf_rasterize(
+            &pdf_path,
+            dpi,
+            &output_dir,
+            password.as_deref(),
+            pdfium.inner(),
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+async fn pdf_render_page(
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_path: String,
+    page_number: usize,
+    output_dir: String,
+    format: String,
+    dpi: u32,
+    password: Option<String>,
+) -> Result<SinglePageRenderResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    pdf_ops::validate_export_dpi(dpi)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::pdf_render_page(
+            &pdf_path,
+            page_number,
+            &output_dir,
+            pdfium.inner(),
+            &format,
+            dpi,
+            password.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_page_info(
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_path: String,
+    password: Option<String>,
+) -> Result<Vec<PdfPageInfo>, String> {
+    validate_path(&pdf_path)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    tokio::task::spawn_blocking(move || {
+        pdf_ops::pdf_page_info(&pdf_path, pdfium.inner(), password.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn search_pdf_text(
+    pdfium_state: tauri::State<'_, PdfiumState>,
+    pdf_path: String,
+    query: String,
+    case_sensitive: bool,
+    password: Option<String>,
+    max_matches: Option<usize>,
+) -> Result<SearchResult, String> {
+    validate_path(&pdf_path)?;
+    let pdfium = require_pdfium(&pdfium_state)?;
+    tokio::task::spawn_blocking(move || {
+        pdf_ops::search_pdf_text(
+            &pdf_path,
+            &query,
+            case_sensitive,
+            max_matches,
+            pdfium.inner(),
+            password.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn image_to_pdf_searchable(
+    app_handle: tauri::AppHandle,
+    input_paths: Vec<String>,
+    ocr_engine: String,
+    language: String,
+    output_path: String,
+) -> Result<SearchablePdfResult, String> {
+    validate_path(&output_path)?;
+    validate_paths(&input_paths)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::image_to_pdf_searchable(
+            &input_paths,
+            &ocr_engine,
+            &language,
+            &output_path,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_verify_integrity(
+    pdf_path: String,
+    password: Option<String>,
+) -> Result<PdfIntegrityResult, String> {
+    validate_path(&pdf_path)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::pdf_verify_integrity(&pdf_path, password.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_read_metadata(
+    pdf_path: String,
+    password: Option<String>,
+) -> Result<PdfDocumentMetadata, String> {
+    validate_path(&pdf_path)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::pdf_read_metadata(&pdf_path, password.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_extract_fonts(pdf_path: String) -> Result<FontListResult, String> {
+    validate_path(&pdf_path)?;
+    let result = tokio::task::spawn_blocking(move || pdf_ops::pdf_extract_fonts(&pdf_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn split_pdf(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    ranges: String,
+    output_dir: String,
+    output_stem: Option<String>,
+) -> Result<PdfSplitResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let output_stem = output_stem.map(|s| utils::sanitize_stem(&s)).transpose()?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::split_pdf(
+            &pdf_path,
+            &ranges,
+            &output_dir,
+            output_stem.as_deref(),
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_split_by_bookmarks(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    output_dir: String,
+) -> Result<PdfSplitResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::pdf_split_by_bookmarks(&pdf_path, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn delete_pdf_pages(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    page_numbers: Vec<usize>,
+    output_dir: String,
+) -> Result<PdfPageEditResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::delete_pdf_pages(&pdf_path, &page_numbers, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn insert_blank_pdf_pages(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    after_pages: Vec<usize>,
+    page_format: String,
+    output_dir: String,
+) -> Result<PdfPageEditResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let page_format = page_format.to_lowercase();
+    if !matches!(page_format.as_str(), "a4" | "letter" | "match") {
+        return Err(format!("Unknown page format: {}", page_format));
+    }
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::insert_blank_pdf_pages(
+            &pdf_path,
+            &after_pages,
+            &page_format,
+            &output_dir,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_page_reorder(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    new_order: Vec<usize>,
+    output_dir: String,
+) -> Result<PdfPageEditResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::pdf_page_reorder(&pdf_path, &new_order, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_add_page_labels(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    labels: Vec<PageLabelRange>,
+    output_dir: String,
+) -> Result<PdfPageEditResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::pdf_add_page_labels(&pdf_path, &labels, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn duplicate_pdf_pages(
+    pdf_path: String,
+    page_repeats: Vec<(usize, usize)>,
+    output_dir: String,
+) -> Result<PdfDuplicateResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::duplicate_pdf_pages(&pdf_path, &page_repeats, &output_dir)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_crop_margins(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    margin_points: f32,
+    output_dir: String,
+) -> Result<PdfCropMarginsResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::pdf_crop_margins(&pdf_path, margin_points, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn split_odd_even(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    output_dir: String,
+) -> Result<PdfSplitResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::split_odd_even(&pdf_path, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn pdf_add_signature_field(
+    pdf_path: String,
+    page_number: usize,
+    rect: [f32; 4],
+    label: Option<String>,
+    output_dir: String,
+) -> Result<PdfPageEditResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_split_ops::pdf_add_signature_field(
+            &pdf_path,
+            page_number,
+            rect,
+            label.as_deref(),
+            &output_dir,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn extract_palette(
+    image_path: String,
+    num_colors: usize,
+    algorithm: Option<String>,
+) -> Result<PaletteResult, String> {
+    validate_path(&image_path)?;
+    tokio::task::spawn_blocking(move || {
+        color_ops::extract_palette(&image_path, num_colors, algorithm.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+fn color_distance(
+    color_a: String,
+    color_b: String,
+    method: String,
+    threshold: Option<f64>,
+) -> Result<ColorDistanceResult, String> {
+    color_ops::color_distance(&color_a, &color_b, &method, threshold)
+}
+
+#[tauri::command]
+async fn export_palette_image(
+    colors: Vec<ColorInfo>,
+    swatch_width: u32,
+    swatch_height: u32,
+    label: bool,
+    output_path: String,
+) -> Result<PaletteImageResult, String> {
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        color_ops::export_palette_image(&colors, swatch_width, swatch_height, label, &output_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn image_average_color(
+    image_path: String,
+    ignore_transparent: bool,
+) -> Result<AverageColorResult, String> {
+    validate_path(&image_path)?;
+    tokio::task::spawn_blocking(move || color_ops::average_color(&image_path, ignore_transparent))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn image_histogram(image_path: String, bins: u32) -> Result<HistogramResult, String> {
+    validate_path(&image_path)?;
+    tokio::task::spawn_blocking(move || color_ops::image_histogram(&image_path, bins))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[tauri::command]
+async fn compress_pdf_cmd(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    quality: u8,
+    output_dir: String,
+    compress_streams: Option<bool>,
+) -> Result<PdfCompressResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let compress_streams = compress_streams.unwrap_or(false);
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::compress_pdf(
+            &pdf_path,
+            quality,
+            compress_streams,
+            &output_dir,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn compress_pdf_lossless(
+    app_handle: tauri::AppHandle,
+    pdf_path: String,
+    output_dir: String,
+) -> Result<PdfCompressResult, String> {
+    validate_path(&pdf_path)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        pdf_ops::compress_pdf_lossless(&pdf_path, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn image_to_ico(
+    image_path: String,
+    sizes: Option<Vec<u32>>,
+    output_dir: String,
+) -> Result<IcoResult, String> {
+    validate_path(&image_path)?;
+    validate_path(&output_dir)?;
+    let sizes = sizes.unwrap_or_else(|| favicon_ops::DEFAULT_ICO_FILE_SIZES.to_vec());
+    let result = tokio::task::spawn_blocking(move || {
+        favicon_ops::image_to_ico(&image_path, &sizes, &output_dir)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn generate_favicons(
+    app_handle: tauri::AppHandle,
+    image_path: String,
+    output_dir: String,
+    ico_color_depth: Option<u8>,
+    svg_override_size: Option<u32>,
+    og_background: Option<[u8; 3]>,
+    og_logo_scale: Option<f32>,
+) -> Result<FaviconResult, String> {
+    validate_path(&image_path)?;
+    validate_path(&output_dir)?;
+    let ico_color_depth = ico_color_depth.unwrap_or(favicon_ops::DEFAULT_ICO_COLOR_DEPTH);
+    let svg_override_size = svg_override_size.map(|s| s.clamp(16, 4096));
+    let og_background = og_background.unwrap_or(favicon_ops::DEFAULT_OG_BACKGROUND);
+    let og_logo_scale = og_logo_scale.unwrap_or(favicon_ops::DEFAULT_OG_LOGO_SCALE);
+    if !og_logo_scale.is_finite() || og_logo_scale <= 0.0 || og_logo_scale > 1.0 {
+        return Err("og_logo_scale must be between 0 and 1".to_string());
+    }
+    let result = tokio::task::spawn_blocking(move || {
+        favicon_ops::generate_favicons(
+            &image_path,
+            ico_color_depth,
+            svg_override_size,
+            og_background,
+            og_logo_scale,
+            &output_dir,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn create_gif(
+    app_handle: tauri::AppHandle,
+    image_paths: Vec<String>,
+    delay_ms: u16,
     loop_count: u16,
     output_dir: String,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
 ) -> Result<AnimationResult, String> {
     validate_paths(&image_paths)?;
     validate_path(&output_dir)?;
     let delay_ms = delay_ms.max(10);
+    let max_width = max_width.filter(|&w| w > 0);
+    let max_height = max_height.filter(|&h| h > 0);
+    let result = tokio::task::spawn_blocking(move || {
+        gif_ops::create_gif(
+            &image_paths,
+            delay_ms,
+            loop_count,
+            max_width,
+            max_height,
+            &output_dir,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn create_slideshow(
+    app_handle: tauri::AppHandle,
+    input_paths: Vec<String>,
+    frame_duration_ms: u32,
+    output_path: String,
+    resolution: Option<(u32, u32)>,
+    quality: u32,
+) -> Result<SlideshowResult, String> {
+    validate_paths(&input_paths)?;
+    validate_path(&output_path)?;
+    let frame_duration_ms = frame_duration_ms.max(10);
+    let resolution = resolution.map(|(w, h)| (w.clamp(2, 7680), h.clamp(2, 4320)));
+    let result = tokio::task::spawn_blocking(move || {
+        gif_ops::create_slideshow(
+            &input_paths,
+            frame_duration_ms,
+            &output_path,
+            resolution,
+            quality,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn gif_speed(
+    app_handle: tauri::AppHandle,
+    gif_path: String,
+    speed_factor: f32,
+    output_dir: String,
+) -> Result<AnimationResult, String> {
+    validate_path(&gif_path)?;
+    validate_path(&output_dir)?;
+    if !speed_factor.is_finite() || speed_factor <= 0.0 {
+        return Err("Speed factor must be a positive number".to_string());
+    }
+    let speed_factor = speed_factor.clamp(0.01, 100.0);
     let result = tokio::task::spawn_blocking(move || {
-        gif_ops::create_gif(&image_paths, delay_ms, loop_count, &output_dir, &app_handle)
+        gif_ops::gif_speed(&gif_path, speed_factor, &output_dir, &app_handle)
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
     Ok(result)
 }
 
+#[tauri::command]
+async fn gif_info(gif_path: String) -> Result<GifInfo, String> {
+    validate_path(&gif_path)?;
+    tokio::task::spawn_blocking(move || gif_ops::gif_info(&gif_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 async fn generate_spritesheet(
     app_handle: tauri::AppHandle,
@@ -641,19 +2076,110 @@ async fn generate_spritesheet(
     columns: u32,
     padding: u32,
     output_dir: String,
+    background_color: Option<[u8; 4]>,
+    animation_groups: Option<Vec<serde_json::Value>>,
+    atlas_format: Option<String>,
+    uniform_cell: Option<(u32, u32)>,
 ) -> Result<SpriteSheetResult, String> {
     validate_paths(&image_paths)?;
     validate_path(&output_dir)?;
     let columns = columns.clamp(1, 100);
     let padding = padding.min(200);
+    if let Some((w, h)) = uniform_cell {
+        if w == 0 || h == 0 || w > 4096 || h > 4096 {
+            return Err("Uniform cell size must be between 1 and 4096 pixels".to_string());
+        }
+    }
+    let animation_groups = animation_groups
+        .unwrap_or_default()
+        .into_iter()
+        .map(|value| {
+            serde_json::from_value::<AnimationGroup>(value)
+                .map_err(|e| format!("Invalid animation group: {}", e))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let result = tokio::task::spawn_blocking(move || {
+        sprite_ops::generate_spritesheet(
+            &image_paths,
+            columns,
+            padding,
+            background_color,
+            &animation_groups,
+            atlas_format.as_deref(),
+            uniform_cell,
+            &output_dir,
+            &app_handle,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn sprite_sheet_from_gif(
+    app_handle: tauri::AppHandle,
+    gif_path: String,
+    columns: u32,
+    padding: u32,
+    output_dir: String,
+) -> Result<SpriteSheetResult, String> {
+    validate_path(&gif_path)?;
+    validate_path(&output_dir)?;
+    let columns = columns.clamp(1, 100);
+    let padding = padding.min(200);
+    let result = tokio::task::spawn_blocking(move || {
+        sprite_ops::sprite_sheet_from_gif(&gif_path, columns, padding, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn spritesheet_to_gif(
+    app_handle: tauri::AppHandle,
+    sheet_path: String,
+    atlas_path: String,
+    frame_order: Option<Vec<String>>,
+    delay_ms: u16,
+    loop_count: u16,
+    output_dir: String,
+) -> Result<AnimationResult, String> {
+    validate_path(&sheet_path)?;
+    validate_path(&atlas_path)?;
+    validate_path(&output_dir)?;
     let result = tokio::task::spawn_blocking(move || {
-        sprite_ops::generate_spritesheet(&image_paths, columns, padding, &output_dir, &app_handle)
+        sprite_ops::spritesheet_to_gif(
+            &sheet_path,
+            &atlas_path,
+            frame_order.as_deref(),
+            delay_ms,
+            loop_count,
+            &output_dir,
+            &app_handle,
+        )
     })
     .await
     .map_err(|e| format!("Task failed: {}", e))?;
     Ok(result)
 }
 
+#[tauri::command]
+async fn export_sprites_css(
+    sheet_image_path: String,
+    atlas_path: String,
+    output_path: String,
+) -> Result<SpriteCssResult, String> {
+    validate_path(&atlas_path)?;
+    validate_path(&output_path)?;
+    tokio::task::spawn_blocking(move || {
+        sprite_ops::export_sprites_css(&sheet_image_path, &atlas_path, &output_path)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?
+}
+
 #[tauri::command]
 async fn protect_pdf_cmd(
     app_handle: tauri::AppHandle,
@@ -749,6 +2275,24 @@ async fn watermark_pdf_image_cmd(
     Ok(result)
 }
 
+#[tauri::command]
+async fn bulk_rename_from_map(
+    app_handle: tauri::AppHandle,
+    map_path: String,
+    source_dir: String,
+    output_dir: String,
+) -> Result<RenameResult, String> {
+    validate_path(&map_path)?;
+    validate_path(&source_dir)?;
+    validate_path(&output_dir)?;
+    let result = tokio::task::spawn_blocking(move || {
+        rename_ops::bulk_rename_from_map(&map_path, &source_dir, &output_dir, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
 #[tauri::command]
 async fn bulk_rename_cmd(
     app_handle: tauri::AppHandle,
@@ -783,6 +2327,60 @@ async fn generate_qr_cmd(text: String, size: u32, output_dir: String) -> Result<
     Ok(result)
 }
 
+#[tauri::command]
+async fn generate_qr_pdf(
+    text: String,
+    page_format: String,
+    qr_size_mm: f32,
+    include_crop_marks: bool,
+    label_text: Option<String>,
+    output_path: String,
+) -> Result<QrResult, String> {
+    validate_path(&output_path)?;
+    if !matches!(page_format.as_str(), "a4" | "letter") {
+        return Err(format!("Unsupported page format: {}", page_format));
+    }
+    let qr_size_mm = qr_size_mm.clamp(10.0, 180.0);
+    let result = tokio::task::spawn_blocking(move || {
+        qr_ops::generate_qr_pdf(
+            &text,
+            &page_format,
+            qr_size_mm,
+            include_crop_marks,
+            label_text.as_deref(),
+            &output_path,
+        )
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
+#[tauri::command]
+async fn generate_qr_data(
+    text: String,
+    error_correction: String,
+) -> Result<QrMatrixResult, String> {
+    qr_ops::generate_qr_data(&text, Some(&error_correction))
+}
+
+#[tauri::command]
+async fn bulk_qr_generate(
+    items: Vec<QrBatchItem>,
+    size: u32,
+    error_correction: Option<String>,
+    output_dir: String,
+) -> Result<QrBatchResult, String> {
+    validate_path(&output_dir)?;
+    let size = size.clamp(64, 4096);
+    let result = tokio::task::spawn_blocking(move || {
+        qr_ops::bulk_qr_generate(&items, size, error_correction.as_deref(), &output_dir)
+    })
+    .await
+    .map_err(|e| format!("Task failed: {}", e))?;
+    Ok(result)
+}
+
 #[tauri::command]
 async fn rasterize_svg_cmd(
     input_path: String,
@@ -801,6 +2399,27 @@ async fn rasterize_svg_cmd(
     result
 }
 
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct VersionInfo {
+    pub version: String,
+    pub build_target: String,
+    pub pdfium_path: String,
+}
+
+#[tauri::command]
+fn get_app_version(pdfium_path: tauri::State<'_, PdfiumPath>) -> VersionInfo {
+    VersionInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        build_target: env!("TARGET").to_string(),
+        pdfium_path: pdfium_path.0.clone(),
+    }
+}
+
+#[tauri::command]
+fn get_log_path(log_state: tauri::State<'_, logging::LogState>) -> String {
+    log_state.current_log_path().to_string_lossy().to_string()
+}
+
 #[tauri::command]
 fn cancel_processing(token: tauri::State<'_, CancellationToken>) {
     (*token).0.store(true, Ordering::Relaxed);
@@ -811,32 +2430,70 @@ fn reset_cancel(token: tauri::State<'_, CancellationToken>) {
     (*token).0.store(false, Ordering::Relaxed);
 }
 
+/// Read a file into a `data:` URL, with the MIME type taken from its extension.
+fn file_to_data_url(image_path: &str) -> Result<String, String> {
+    let bytes = std::fs::read(image_path).map_err(|e| format!("Cannot read file: {}", e))?;
+    let ext = Path::new(image_path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_else(|| "png".to_string());
+    let mime = match ext.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "ico" => "image/x-icon",
+        "svg" => "image/svg+xml",
+        "tiff" | "tif" => "image/tiff",
+        _ => "application/octet-stream",
+    };
+    let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
+    Ok(format!("data:{};base64,{}", mime, b64))
+}
+
 #[tauri::command]
 async fn image_to_base64(image_path: String) -> Result<String, String> {
     validate_path(&image_path)?;
+    tokio::task::spawn_blocking(move || file_to_data_url(&image_path))
+        .await
+        .map_err(|e| format!("Task failed: {}", e))?
+}
+
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct Base64Result {
+    pub path: String,
+    pub data_url: Option<String>,
+    pub error: Option<String>,
+}
+
+/// `image_to_base64` for many files in one IPC call. Files are read in parallel and
+/// failures are reported per file.
+#[tauri::command]
+async fn batch_to_base64(image_paths: Vec<String>) -> Result<Vec<Base64Result>, String> {
+    use rayon::prelude::*;
+
+    validate_paths(&image_paths)?;
     tokio::task::spawn_blocking(move || {
-        let bytes = std::fs::read(&image_path).map_err(|e| format!("Cannot read file: {}", e))?;
-        let ext = Path::new(&image_path)
-            .extension()
-            .and_then(|e| e.to_str())
-            .map(|e| e.to_lowercase())
-            .unwrap_or_else(|| "png".to_string());
-        let mime = match ext.as_str() {
-            "png" => "image/png",
-            "jpg" | "jpeg" => "image/jpeg",
-            "gif" => "image/gif",
-            "webp" => "image/webp",
-            "bmp" => "image/bmp",
-            "ico" => "image/x-icon",
-            "svg" => "image/svg+xml",
-            "tiff" | "tif" => "image/tiff",
-            _ => "application/octet-stream",
-        };
-        let b64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &bytes);
-        Ok(format!("data:{};base64,{}", mime, b64))
+        image_paths
+            .par_iter()
+            .map(|path| match file_to_data_url(path) {
+                Ok(data_url) => Base64Result {
+                    path: path.clone(),
+                    data_url: Some(data_url),
+                    error: None,
+                },
+                Err(e) => Base64Result {
+                    path: path.clone(),
+                    data_url: None,
+                    error: Some(e),
+                },
+            })
+            .collect()
     })
     .await
-    .map_err(|e| format!("Task failed: {}", e))?
+    .map_err(|e| format!("Task failed: {}", e))
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -854,34 +2511,108 @@ pub fn run() {
             extract_pdf_images,
             resize_images,
             strip_metadata,
+            strip_metadata_selective,
             add_watermark,
             add_image_watermark,
             optimize_images,
             crop_images,
+            crop_to_ratio,
+            simulate_color_blindness,
+            white_balance_images,
+            line_extract_images,
+            pixel_sort_images,
+            vignette_images,
+            apply_lut_images,
+            flatten_transparency,
+            rotate_images,
+            alpha_to_mask,
+            image_channels_split,
+            mask_to_alpha,
+            create_mosaic,
+            create_collage,
+            generate_placeholder,
+            generate_gradient,
+            image_border_gradient,
             images_to_pdf,
+            image_to_pdf_page,
+            images_to_pdf_grid,
             read_metadata,
+            batch_metadata_export,
+            batch_results_to_csv,
+            validate_images,
+            deduplicate_images,
+            detect_image_format,
             get_pdf_page_count,
             generate_pdf_thumbnails,
             merge_to_pdf,
             pdf_to_images,
+            batch_pdf_to_images,
+            pdf_rasterize,
+            pdf_render_page,
+            pdf_extract_fonts,
+            pdf_page_info,
+            search_pdf_text,
+            pdf_verify_integrity,
+            pdf_read_metadata,
+            image_to_pdf_searchable,
             split_pdf,
+            pdf_split_by_bookmarks,
+            delete_pdf_pages,
+            insert_blank_pdf_pages,
+            pdf_page_reorder,
+            pdf_add_page_labels,
+            pdf_add_signature_field,
+            split_odd_even,
+            pdf_crop_margins,
+            duplicate_pdf_pages,
             extract_palette,
+            export_palette_image,
+            color_distance,
+            image_average_color,
+            image_histogram,
             compress_pdf_cmd,
+            compress_pdf_lossless,
             generate_favicons,
+            image_to_ico,
             create_gif,
+            create_slideshow,
+            gif_speed,
+            gif_info,
             generate_spritesheet,
+            sprite_sheet_from_gif,
+            spritesheet_to_gif,
+            export_sprites_css,
             protect_pdf_cmd,
             unlock_pdf_cmd,
             watermark_pdf_text_cmd,
             watermark_pdf_image_cmd,
             image_to_base64,
+            batch_to_base64,
             generate_qr_cmd,
+            generate_qr_pdf,
+            bulk_qr_generate,
+            generate_qr_data,
             bulk_rename_cmd,
+            bulk_rename_from_map,
             rasterize_svg_cmd,
             cancel_processing,
+            get_app_version,
+            get_log_path,
             reset_cancel
         ])
         .setup(|app| {
+            // File logging first so later startup steps are captured
+            let log_dir = app
+                .path()
+                .app_data_dir()
+                .map(|d| d.join("logs"))
+                .unwrap_or_else(|_| std::env::temp_dir().join("rustine-logs"));
+            let log_state = logging::setup_logging(&log_dir).unwrap_or_else(|e| {
+                eprintln!("Warning: {} — file logging disabled", e);
+                logging::LogState::disabled(log_dir.clone())
+            });
+            app.manage(log_state);
+
             let png_bytes = include_bytes!("../icons/icon.png");
             if let Ok(img) = image::load_from_memory(png_bytes) {
                 let rgba = img.to_rgba8();
@@ -913,6 +2644,9 @@ pub fn run() {
                 }
             };
             app.manage(PdfiumState(pdfium_instance));
+            app.manage(PdfiumPath(
+                resolve_pdfium_path(app.handle()).unwrap_or_default(),
+            ));
             app.manage(CancellationToken(Arc::new(AtomicBool::new(false))));
 
             Ok(())
@@ -979,6 +2713,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn batch_options_rejects_tiny_timeouts() {
+        let dir = std::env::temp_dir().to_string_lossy().to_string();
+        assert!(batch_options(Some(0), None, &dir).is_err());
+        assert!(batch_options(Some(MIN_PER_FILE_TIMEOUT_MS - 1), None, &dir).is_err());
+        let options = batch_options(Some(MIN_PER_FILE_TIMEOUT_MS), None, &dir).unwrap();
+        assert_eq!(options.per_file_timeout_ms, Some(MIN_PER_FILE_TIMEOUT_MS));
+        assert!(batch_options(None, None, &dir).is_ok());
+    }
+
     #[test]
     fn validate_path_rejects_null_bytes() {
         assert!(validate_path("/home/test/\0evil.png").is_err());
@@ -0,0 +1,187 @@
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+use tracing_appender::non_blocking::WorkerGuard;
+
+use crate::utils::ensure_output_dir;
+
+const LOG_PREFIX: &str = "rustine";
+const LOG_SUFFIX: &str = "log";
+/// Log files older than this are removed at startup.
+const LOG_RETENTION: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Log directory plus the non-blocking writer guard.
+/// Dropping the guard flushes and stops the writer, so it lives in app state.
+pub struct LogState {
+    pub dir: PathBuf,
+    _guard: Option<WorkerGuard>,
+}
+
+impl LogState {
+    /// State used when file logging could not be set up.
+    pub fn disabled(dir: PathBuf) -> Self {
+        LogState { dir, _guard: None }
+    }
+
+    /// Path of today's log file (`rustine-YYYY-MM-DD.log`, UTC date).
+    pub fn current_log_path(&self) -> PathBuf {
+        self.dir.join(log_file_name(OffsetDateTime::now_utc()))
+    }
+}
+
+/// `rustine-YYYY-MM-DD.log` for the UTC date of `now`.
+fn log_file_name(now: OffsetDateTime) -> String {
+    format!(
+        "{}-{:04}-{:02}-{:02}.{}",
+        LOG_PREFIX,
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        LOG_SUFFIX
+    )
+}
+
+/// Append-only writer that switches to a new `rustine-YYYY-MM-DD.log` when the
+/// UTC date changes. tracing-appender's own roller always inserts a `.` after
+/// the prefix, which does not give this name.
+struct DailyLogWriter {
+    dir: PathBuf,
+    current: Option<(String, File)>,
+}
+
+impl DailyLogWriter {
+    fn file(&mut self) -> std::io::Result<&mut File> {
+        let name = log_file_name(OffsetDateTime::now_utc());
+        if self.current.as_ref().map(|(n, _)| n) != Some(&name) {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(&name))?;
+            self.current = Some((name, file));
+        }
+        match self.current.as_mut() {
+            Some((_, file)) => Ok(file),
+            None => Err(std::io::Error::other("Log file is not open")),
+        }
+    }
+}
+
+impl Write for DailyLogWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self.current.as_mut() {
+            Some((_, file)) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Initialize the global tracing subscriber writing JSON lines to a daily-rotated
+/// file in `log_dir`, after pruning logs older than the retention window.
+pub fn setup_logging(log_dir: &Path) -> Result<LogState, String> {
+    ensure_output_dir(log_dir)?;
+    prune_old_logs(log_dir, LOG_RETENTION, SystemTime::now());
+
+    let mut appender = DailyLogWriter {
+        dir: log_dir.to_path_buf(),
+        current: None,
+    };
+    appender
+        .file()
+        .map_err(|e| format!("Cannot create log file: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    tracing_subscriber::fmt()
+        .json()
+        .with_ansi(false)
+        .with_writer(writer)
+        .try_init()
+        .map_err(|e| format!("Cannot initialize logging: {}", e))?;
+
+    Ok(LogState {
+        dir: log_dir.to_path_buf(),
+        _guard: Some(guard),
+    })
+}
+
+/// Delete `rustine-*.log` files last modified before `now - max_age`.
+/// Returns how many files were removed; unreadable entries are skipped.
+fn prune_old_logs(log_dir: &Path, max_age: Duration, now: SystemTime) -> usize {
+    let Ok(entries) = std::fs::read_dir(log_dir) else {
+        return 0;
+    };
+    let mut removed = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !name.starts_with(&format!("{}-", LOG_PREFIX)) || !name.ends_with(LOG_SUFFIX) {
+            continue;
+        }
+        let expired = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > max_age);
+        if expired && std::fs::remove_file(entry.path()).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prune_removes_only_expired_log_files() {
+        let dir = std::env::temp_dir().join("rustine-log-prune-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("rustine-2020-01-01.log"), b"{}").unwrap();
+        std::fs::write(dir.join("notes.txt"), b"keep").unwrap();
+
+        let later = SystemTime::now() + Duration::from_secs(30 * 24 * 60 * 60);
+        assert_eq!(prune_old_logs(&dir, LOG_RETENTION, later), 1);
+        assert!(dir.join("notes.txt").exists());
+        assert_eq!(prune_old_logs(&dir, LOG_RETENTION, SystemTime::now()), 0);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn current_log_path_uses_prefix_and_suffix() {
+        let state = LogState::disabled(PathBuf::from("logs"));
+        let name = state.current_log_path();
+        let name = name.file_name().unwrap().to_string_lossy();
+        assert!(name.starts_with("rustine-") && name.ends_with(".log"));
+        assert_eq!(name.len(), "rustine-2026-01-01.log".len());
+    }
+
+    #[test]
+    fn log_file_name_matches_requested_format() {
+        let date = time::macros::datetime!(2026-03-07 23:59 UTC);
+        assert_eq!(log_file_name(date), "rustine-2026-03-07.log");
+    }
+
+    #[test]
+    fn daily_writer_appends_to_dated_file() {
+        let dir = std::env::temp_dir().join("rustine-log-writer-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut writer = DailyLogWriter {
+            dir: dir.clone(),
+            current: None,
+        };
+        writer.write_all(b"one\n").unwrap();
+        writer.write_all(b"two\n").unwrap();
+        writer.flush().unwrap();
+        let path = dir.join(log_file_name(OffsetDateTime::now_utc()));
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "one\ntwo\n");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
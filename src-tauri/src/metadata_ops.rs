@@ -1,9 +1,12 @@
-use exif::{In, Tag};
+use exif::{Context, In, Tag};
 use image::ImageDecoder;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+use crate::utils::{ensure_output_dir, write_atomic};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MetadataEntry {
     pub tag: String,
@@ -142,3 +145,436 @@ pub fn read_image_metadata(path: &str) -> Result<ImageMetadata, String> {
         exif: exif_entries,
     })
 }
+
+/// Read the raw EXIF Orientation value (1–8), if the file carries one.
+/// `read_image_metadata` only exposes the human-readable label, so callers that
+/// need to act on the orientation use this instead.
+pub fn read_exif_orientation(path: &str) -> Option<u32> {
+    let file = fs::File::open(path).ok()?;
+    let mut buf_reader = std::io::BufReader::new(&file);
+    let exif_data = exif::Reader::new()
+        .read_from_container(&mut buf_reader)
+        .ok()?;
+    exif_data
+        .get_field(Tag::Orientation, In::PRIMARY)
+        .and_then(|field| field.value.get_uint(0))
+        .filter(|o| (1..=8).contains(o))
+}
+
+// --- Selective EXIF removal ---
+
+/// Resolve `EXIF_TAGS` labels (case-insensitive) to their tags.
+pub(crate) fn exif_tags_for_labels(labels: &[String]) -> Result<Vec<Tag>, String> {
+    labels
+        .iter()
+        .map(|label| {
+            EXIF_TAGS
+                .iter()
+                .find(|(_, l)| l.eq_ignore_ascii_case(label.trim()))
+                .map(|&(tag, _)| tag)
+                .ok_or_else(|| format!("Unknown EXIF tag: {}", label))
+        })
+        .collect()
+}
+
+const MALFORMED_EXIF: &str = "Malformed EXIF data";
+
+fn tiff_u16(tiff: &[u8], at: usize, le: bool) -> Result<u16, String> {
+    let bytes: [u8; 2] = tiff
+        .get(at..at + 2)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(MALFORMED_EXIF)?;
+    Ok(if le {
+        u16::from_le_bytes(bytes)
+    } else {
+        u16::from_be_bytes(bytes)
+    })
+}
+
+fn tiff_u32(tiff: &[u8], at: usize, le: bool) -> Result<u32, String> {
+    let bytes: [u8; 4] = tiff
+        .get(at..at + 4)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(MALFORMED_EXIF)?;
+    Ok(if le {
+        u32::from_le_bytes(bytes)
+    } else {
+        u32::from_be_bytes(bytes)
+    })
+}
+
+fn put_tiff_bytes(tiff: &mut [u8], at: usize, bytes: &[u8]) -> Result<(), String> {
+    tiff.get_mut(at..at + bytes.len())
+        .ok_or(MALFORMED_EXIF)?
+        .copy_from_slice(bytes);
+    Ok(())
+}
+
+/// Byte size of one value of a TIFF field type; unknown types count as 1.
+fn tiff_type_size(field_type: u16) -> usize {
+    match field_type {
+        3 | 8 => 2,
+        4 | 9 | 11 => 4,
+        5 | 10 | 12 => 8,
+        _ => 1,
+    }
+}
+
+/// Offset of the sub-IFD that `pointer_tag` in the IFD at `ifd` points to.
+fn tiff_sub_ifd(
+    tiff: &[u8],
+    ifd: usize,
+    le: bool,
+    pointer_tag: u16,
+) -> Result<Option<usize>, String> {
+    let count = tiff_u16(tiff, ifd, le)? as usize;
+    for i in 0..count {
+        let entry = ifd + 2 + i * 12;
+        if tiff_u16(tiff, entry, le)? == pointer_tag {
+            return Ok(Some(tiff_u32(tiff, entry + 8, le)? as usize));
+        }
+    }
+    Ok(None)
+}
+
+/// Drop the entries of one IFD that match `tags` in `context`. The remaining
+/// entries move up, the freed slots and the removed values are zeroed, and the
+/// segment keeps its length. Returns how many entries were removed.
+fn remove_ifd_entries(
+    tiff: &mut [u8],
+    ifd: usize,
+    le: bool,
+    context: Context,
+    tags: &[Tag],
+) -> Result<usize, String> {
+    let count = tiff_u16(tiff, ifd, le)? as usize;
+    let entries_start = ifd + 2;
+    let next_ifd_at = entries_start + count * 12;
+    let next_ifd = tiff_u32(tiff, next_ifd_at, le)?;
+
+    let mut kept: Vec<[u8; 12]> = Vec::with_capacity(count);
+    for i in 0..count {
+        let at = entries_start + i * 12;
+        let entry: [u8; 12] = tiff
+            .get(at..at + 12)
+            .and_then(|b| b.try_into().ok())
+            .ok_or(MALFORMED_EXIF)?;
+        let number = tiff_u16(&entry, 0, le)?;
+        if !tags
+            .iter()
+            .any(|t| t.context() == context && t.number() == number)
+        {
+            kept.push(entry);
+            continue;
+        }
+        // Values over four bytes live elsewhere in the segment; blank them too
+        let size = tiff_type_size(tiff_u16(&entry, 2, le)?)
+            .saturating_mul(tiff_u32(&entry, 4, le)? as usize);
+        if size > 4 {
+            let offset = tiff_u32(&entry, 8, le)? as usize;
+            if let Some(value) = tiff.get_mut(offset..offset.saturating_add(size)) {
+                value.fill(0);
+            }
+        }
+    }
+
+    let removed = count - kept.len();
+    if removed == 0 {
+        return Ok(0);
+    }
+    let new_count = kept.len() as u16;
+    let count_bytes = if le {
+        new_count.to_le_bytes()
+    } else {
+        new_count.to_be_bytes()
+    };
+    put_tiff_bytes(tiff, ifd, &count_bytes)?;
+    for (i, entry) in kept.iter().enumerate() {
+        put_tiff_bytes(tiff, entries_start + i * 12, entry)?;
+    }
+    let new_next_at = entries_start + kept.len() * 12;
+    let next_bytes = if le {
+        next_ifd.to_le_bytes()
+    } else {
+        next_ifd.to_be_bytes()
+    };
+    put_tiff_bytes(tiff, new_next_at, &next_bytes)?;
+    put_tiff_bytes(tiff, new_next_at + 4, &vec![0; removed * 12])?;
+    Ok(removed)
+}
+
+/// Remove `tags` from a TIFF-structured EXIF block (IFD0, Exif and GPS IFDs).
+fn strip_tiff_tags(tiff: &mut [u8], tags: &[Tag]) -> Result<usize, String> {
+    let le = match tiff.get(0..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return Err(MALFORMED_EXIF.to_string()),
+    };
+    let ifd0 = tiff_u32(tiff, 4, le)? as usize;
+
+    let mut removed = 0;
+    for (pointer_tag, context) in [(0x8769, Context::Exif), (0x8825, Context::Gps)] {
+        if let Some(sub_ifd) = tiff_sub_ifd(tiff, ifd0, le, pointer_tag)? {
+            removed += remove_ifd_entries(tiff, sub_ifd, le, context, tags)?;
+        }
+    }
+    removed += remove_ifd_entries(tiff, ifd0, le, Context::Tiff, tags)?;
+    Ok(removed)
+}
+
+/// Remove `tags` from a JPEG's EXIF APP1 segment in place, leaving every other
+/// byte of the file untouched. Returns how many entries were removed (0 when the
+/// file has no EXIF segment).
+pub(crate) fn strip_jpeg_exif_tags(jpeg: &mut [u8], tags: &[Tag]) -> Result<usize, String> {
+    if !jpeg.starts_with(&[0xFF, 0xD8]) {
+        return Err("Not a JPEG file".to_string());
+    }
+    let mut pos = 2;
+    while pos + 4 <= jpeg.len() {
+        if jpeg[pos] != 0xFF {
+            return Err("Malformed JPEG marker".to_string());
+        }
+        let marker = jpeg[pos + 1];
+        // Metadata segments all come before the image data
+        if marker == 0xDA || marker == 0xD9 {
+            break;
+        }
+        let len = u16::from_be_bytes([jpeg[pos + 2], jpeg[pos + 3]]) as usize;
+        let segment_end = pos + 2 + len;
+        if len < 2 || segment_end > jpeg.len() {
+            return Err("Malformed JPEG segment".to_string());
+        }
+        if marker == 0xE1 && jpeg[pos + 4..segment_end].starts_with(b"Exif\0\0") {
+            return strip_tiff_tags(&mut jpeg[pos + 10..segment_end], tags);
+        }
+        pos = segment_end;
+    }
+    Ok(0)
+}
+
+// --- Format detection ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FormatDetectResult {
+    pub claimed_extension: String,
+    pub actual_format: String,
+    #[serde(rename = "match")]
+    pub matches: bool,
+}
+
+/// Identify a file format from its leading magic bytes.
+/// Returns `"unknown"` when no known signature matches.
+fn detect_format_from_magic(header: &[u8]) -> &'static str {
+    if header.starts_with(b"\x89PNG") {
+        "png"
+    } else if header.starts_with(b"\xFF\xD8") {
+        "jpeg"
+    } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        "gif"
+    } else if header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+        "webp"
+    } else if header.starts_with(b"II*\x00") || header.starts_with(b"MM\x00*") {
+        "tiff"
+    } else if header.starts_with(b"BM") {
+        "bmp"
+    } else if header.starts_with(b"%PDF") {
+        "pdf"
+    } else if header.starts_with(b"\x00\x00\x01\x00") {
+        "ico"
+    } else {
+        "unknown"
+    }
+}
+
+/// Map extension aliases (jpg/jpeg, tif/tiff) onto the names used by `detect_format_from_magic`.
+fn normalize_extension(ext: &str) -> String {
+    match ext {
+        "jpg" | "jpeg" => "jpeg".to_string(),
+        "tif" | "tiff" => "tiff".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Read the first 16 bytes of a file and compare its real format to its extension.
+pub fn detect_image_format(path: &str) -> Result<FormatDetectResult, String> {
+    use std::io::Read;
+
+    let mut file = fs::File::open(path).map_err(|e| format!("Cannot open file: {}", e))?;
+    let mut header = [0u8; 16];
+    let mut read = 0;
+    // A single read() may return fewer bytes than requested; loop until EOF or full.
+    while read < header.len() {
+        match file.read(&mut header[read..]) {
+            Ok(0) => break,
+            Ok(n) => read += n,
+            Err(e) => return Err(format!("Cannot read file: {}", e)),
+        }
+    }
+
+    let claimed_extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    let actual_format = detect_format_from_magic(&header[..read]);
+
+    Ok(FormatDetectResult {
+        matches: normalize_extension(&claimed_extension) == actual_format,
+        claimed_extension,
+        actual_format: actual_format.to_string(),
+    })
+}
+
+// --- Batch export ---
+
+/// One file in a metadata export: its metadata, or the reason it could not be read.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum MetadataExportEntry {
+    Read(ImageMetadata),
+    Failed { path: String, error: String },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MetadataExportResult {
+    pub output_path: String,
+    pub total: usize,
+    pub failed: usize,
+}
+
+fn collect_metadata(input_paths: &[String]) -> Vec<MetadataExportEntry> {
+    input_paths
+        .par_iter()
+        .map(|path| match read_image_metadata(path) {
+            Ok(metadata) => MetadataExportEntry::Read(metadata),
+            Err(error) => MetadataExportEntry::Failed {
+                path: path.clone(),
+                error,
+            },
+        })
+        .collect()
+}
+
+/// Read metadata for every file in parallel and write it as one pretty-printed JSON
+/// array at `output_path` (atomically). Unreadable files appear with an `error` field.
+pub fn batch_metadata_export(
+    input_paths: &[String],
+    output_path: &str,
+) -> Result<MetadataExportResult, String> {
+    let entries = collect_metadata(input_paths);
+    let failed = entries
+        .iter()
+        .filter(|e| matches!(e, MetadataExportEntry::Failed { .. }))
+        .count();
+    let json = serde_json::to_string_pretty(&entries)
+        .map_err(|e| format!("Cannot serialize metadata: {}", e))?;
+
+    let output = Path::new(output_path);
+    if let Some(parent) = output.parent() {
+        ensure_output_dir(parent)?;
+    }
+    write_atomic(output, json.as_bytes())?;
+
+    Ok(MetadataExportResult {
+        output_path: output_path.to_string(),
+        total: entries.len(),
+        failed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Little-endian EXIF block: IFD0 with Make and a GPS pointer, and a GPS IFD
+    /// holding a three-rational latitude stored at offset 56.
+    fn sample_exif_tiff() -> Vec<u8> {
+        let mut tiff = b"II\x2a\x00\x08\x00\x00\x00".to_vec();
+        tiff.extend([2, 0]);
+        tiff.extend([0x0F, 0x01, 2, 0, 4, 0, 0, 0, b'A', b'B', b'C', 0]);
+        tiff.extend([0x25, 0x88, 4, 0, 1, 0, 0, 0, 38, 0, 0, 0]);
+        tiff.extend([0, 0, 0, 0]);
+        tiff.extend([1, 0]);
+        tiff.extend([0x02, 0x00, 5, 0, 3, 0, 0, 0, 56, 0, 0, 0]);
+        tiff.extend([0, 0, 0, 0]);
+        for degrees in [10u32, 20, 30] {
+            tiff.extend(degrees.to_le_bytes());
+            tiff.extend(1u32.to_le_bytes());
+        }
+        tiff
+    }
+
+    #[test]
+    fn selective_strip_removes_only_requested_tags() {
+        let tiff = sample_exif_tiff();
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend(((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend(b"Exif\0\0");
+        jpeg.extend(&tiff);
+        jpeg.extend([0xFF, 0xD9]);
+        let original_len = jpeg.len();
+
+        let tags = exif_tags_for_labels(&["gps latitude".to_string()]).unwrap();
+        assert_eq!(strip_jpeg_exif_tags(&mut jpeg, &tags).unwrap(), 1);
+        assert_eq!(jpeg.len(), original_len);
+
+        let stripped = jpeg[10..jpeg.len() - 2].to_vec();
+        assert!(stripped[56..80].iter().all(|&b| b == 0));
+        let exif = exif::Reader::new().read_raw(stripped).unwrap();
+        assert!(exif.get_field(Tag::GPSLatitude, In::PRIMARY).is_none());
+        assert!(exif.get_field(Tag::Make, In::PRIMARY).is_some());
+    }
+
+    #[test]
+    fn selective_strip_rejects_unknown_labels_and_non_jpeg() {
+        assert!(exif_tags_for_labels(&["Shoe Size".to_string()]).is_err());
+        assert!(strip_jpeg_exif_tags(&mut b"\x89PNG".to_vec(), &[Tag::Make]).is_err());
+        assert_eq!(
+            strip_jpeg_exif_tags(&mut vec![0xFF, 0xD8, 0xFF, 0xD9], &[Tag::Make]).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn detects_common_signatures() {
+        assert_eq!(detect_format_from_magic(b"\x89PNG\r\n\x1a\n"), "png");
+        assert_eq!(detect_format_from_magic(b"\xFF\xD8\xFF\xE0"), "jpeg");
+        assert_eq!(detect_format_from_magic(b"GIF89a"), "gif");
+        assert_eq!(
+            detect_format_from_magic(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            "webp"
+        );
+        assert_eq!(detect_format_from_magic(b"II*\x00"), "tiff");
+        assert_eq!(detect_format_from_magic(b"MM\x00*"), "tiff");
+        assert_eq!(detect_format_from_magic(b"BM"), "bmp");
+        assert_eq!(detect_format_from_magic(b"%PDF-1.7"), "pdf");
+        assert_eq!(detect_format_from_magic(b"\x00\x00\x01\x00"), "ico");
+    }
+
+    #[test]
+    fn unreadable_files_are_exported_with_error() {
+        let entries = collect_metadata(&["/definitely/missing.png".to_string()]);
+        let json = serde_json::to_value(&entries).unwrap();
+        assert_eq!(json[0]["path"], "/definitely/missing.png");
+        assert!(json[0]["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Cannot open file"));
+    }
+
+    #[test]
+    fn riff_without_webp_is_unknown() {
+        assert_eq!(
+            detect_format_from_magic(b"RIFF\x00\x00\x00\x00WAVE"),
+            "unknown"
+        );
+        assert_eq!(detect_format_from_magic(b""), "unknown");
+    }
+
+    #[test]
+    fn extension_aliases_normalize() {
+        assert_eq!(normalize_extension("jpg"), "jpeg");
+        assert_eq!(normalize_extension("tif"), "tiff");
+        assert_eq!(normalize_extension("png"), "png");
+    }
+}
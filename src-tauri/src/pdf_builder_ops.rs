@@ -1,4 +1,5 @@
 use image::codecs::jpeg::JpegEncoder;
+use lopdf::content::{Content, Operation};
 use lopdf::{dictionary, Document as LopdfDocument, Object, Stream};
 use pdfium_render::prelude::*;
 use rayon::prelude::*;
@@ -18,6 +19,8 @@ pub struct PageThumbnail {
     pub source_path: String,
     pub page_number: usize,
     pub thumbnail_b64: String,
+    /// MIME type of the encoded thumbnail, e.g. "image/jpeg".
+    pub thumbnail_mime: String,
     pub source_type: String,
 }
 
@@ -35,6 +38,13 @@ pub struct MergePdfOptions {
     pub margin_px: u32,
     pub image_quality: u32,
     pub output_path: String,
+    /// Prepend a clickable table of contents listing each source file.
+    #[serde(default)]
+    pub generate_toc: bool,
+    /// Prepend a plain index page (file names and start pages, no links).
+    /// Ignored when `generate_toc` is set, since the TOC already lists the same entries.
+    #[serde(default)]
+    pub generate_index: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -46,18 +56,39 @@ pub struct MergePdfResult {
 
 // --- Thumbnail generation ---
 
-fn encode_image_to_b64_jpeg(img: &image::DynamicImage, max_width: u32) -> Result<String, String> {
-    let owned_resized;
-    let to_encode: &image::DynamicImage = if img.width() > max_width {
-        owned_resized = img.resize(
+/// Map a thumbnail format name to its MIME type. Defaults to JPEG.
+pub fn thumbnail_mime(format: Option<&str>) -> Result<&'static str, String> {
+    match format.map(|f| f.to_lowercase()).as_deref() {
+        None | Some("jpeg") | Some("jpg") => Ok("image/jpeg"),
+        Some("png") => Ok("image/png"),
+        Some("webp") => Ok("image/webp"),
+        Some(other) => Err(format!("Unknown thumbnail format: {}", other)),
+    }
+}
+
+/// Downscale to `max_width` if needed, borrowing the original when it is already small enough.
+fn thumbnail_source(
+    img: &image::DynamicImage,
+    max_width: u32,
+) -> std::borrow::Cow<'_, image::DynamicImage> {
+    if img.width() > max_width {
+        std::borrow::Cow::Owned(img.resize(
             max_width,
             max_width * 2,
             image::imageops::FilterType::Triangle,
-        );
-        &owned_resized
+        ))
     } else {
-        img
-    };
+        std::borrow::Cow::Borrowed(img)
+    }
+}
+
+fn to_b64(bytes: &[u8]) -> String {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn encode_image_to_b64_jpeg(img: &image::DynamicImage, max_width: u32) -> Result<String, String> {
+    let to_encode = thumbnail_source(img, max_width);
 
     let mut jpeg_buf: Vec<u8> = Vec::new();
     let mut cursor = Cursor::new(&mut jpeg_buf);
@@ -66,13 +97,45 @@ fn encode_image_to_b64_jpeg(img: &image::DynamicImage, max_width: u32) -> Result
         .write_with_encoder(encoder)
         .map_err(|e| format!("JPEG encode failed: {}", e))?;
 
-    use base64::Engine;
-    Ok(base64::engine::general_purpose::STANDARD.encode(&jpeg_buf))
+    Ok(to_b64(&jpeg_buf))
+}
+
+/// Lossless thumbnails keep small text in screenshots legible.
+fn encode_image_to_b64_png(img: &image::DynamicImage, max_width: u32) -> Result<String, String> {
+    let to_encode = thumbnail_source(img, max_width);
+
+    let mut png_buf: Vec<u8> = Vec::new();
+    to_encode
+        .write_to(&mut Cursor::new(&mut png_buf), image::ImageFormat::Png)
+        .map_err(|e| format!("PNG encode failed: {}", e))?;
+
+    Ok(to_b64(&png_buf))
+}
+
+fn encode_image_to_b64_webp(img: &image::DynamicImage, max_width: u32) -> Result<String, String> {
+    let rgba = thumbnail_source(img, max_width).to_rgba8();
+    let (w, h) = rgba.dimensions();
+    let webp_data = webp::Encoder::from_rgba(&rgba, w, h).encode(60.0);
+
+    Ok(to_b64(&webp_data))
+}
+
+/// Encode a thumbnail for the given MIME type (as returned by `thumbnail_mime`).
+fn encode_thumbnail(
+    img: &image::DynamicImage,
+    max_width: u32,
+    mime: &str,
+) -> Result<String, String> {
+    match mime {
+        "image/png" => encode_image_to_b64_png(img, max_width),
+        "image/webp" => encode_image_to_b64_webp(img, max_width),
+        _ => encode_image_to_b64_jpeg(img, max_width),
+    }
 }
 
-pub fn generate_image_thumbnail(path: &str) -> Result<PageThumbnail, String> {
+pub fn generate_image_thumbnail(path: &str, mime: &str) -> Result<PageThumbnail, String> {
     let img = image::open(path).map_err(|e| format!("Cannot open image '{}': {}", path, e))?;
-    let b64 = encode_image_to_b64_jpeg(&img, 120)?;
+    let b64 = encode_thumbnail(&img, 120, mime)?;
     let filename = Path::new(path)
         .file_name()
         .and_then(|f| f.to_str())
@@ -83,6 +146,7 @@ pub fn generate_image_thumbnail(path: &str) -> Result<PageThumbnail, String> {
         source_path: path.to_string(),
         page_number: 0,
         thumbnail_b64: b64,
+        thumbnail_mime: mime.to_string(),
         source_type: "image".to_string(),
     })
 }
@@ -92,6 +156,7 @@ fn generate_pdf_page_thumbnails(
     pdfium: &Pdfium,
     start_page: Option<usize>,
     max_pages: Option<usize>,
+    mime: &str,
 ) -> Result<Vec<PageThumbnail>, String> {
     let document = pdfium
         .load_pdf_from_file(pdf_path, None)
@@ -109,8 +174,9 @@ fn generate_pdf_page_thumbnails(
     let limit = max_pages.unwrap_or(total_pages);
     let end_idx = (start_idx + limit).min(total_pages);
 
+    // Pdfium is not thread-safe: render bitmaps sequentially first
     let capacity = end_idx.saturating_sub(start_idx);
-    let mut thumbnails: Vec<PageThumbnail> = Vec::with_capacity(capacity);
+    let mut rendered: Vec<(usize, Option<image::DynamicImage>)> = Vec::with_capacity(capacity);
 
     for (page_index, page) in document.pages().iter().enumerate() {
         if page_index < start_idx {
@@ -126,45 +192,11 @@ fn generate_pdf_page_thumbnails(
         );
 
         match render_result {
-            Ok(bitmap) => {
-                let dynamic_image = bitmap.as_image();
-                match encode_image_to_b64_jpeg(&dynamic_image, 120) {
-                    Ok(b64) => {
-                        thumbnails.push(PageThumbnail {
-                            id: format!("pdf_{}_p{}", pdf_stem, page_index + 1),
-                            source_path: pdf_path.to_string(),
-                            page_number: page_index + 1,
-                            thumbnail_b64: b64,
-                            source_type: "pdf".to_string(),
-                        });
-                    }
-                    Err(e) => {
-                        thumbnails.push(PageThumbnail {
-                            id: format!("pdf_{}_p{}", pdf_stem, page_index + 1),
-                            source_path: pdf_path.to_string(),
-                            page_number: page_index + 1,
-                            thumbnail_b64: String::new(),
-                            source_type: "pdf".to_string(),
-                        });
-                        eprintln!(
-                            "Warning: thumbnail encode failed for {} page {}: {}",
-                            pdf_path,
-                            page_index + 1,
-                            e
-                        );
-                    }
-                }
-            }
+            Ok(bitmap) => rendered.push((page_index, Some(bitmap.as_image()))),
             Err(e) => {
-                thumbnails.push(PageThumbnail {
-                    id: format!("pdf_{}_p{}", pdf_stem, page_index + 1),
-                    source_path: pdf_path.to_string(),
-                    page_number: page_index + 1,
-                    thumbnail_b64: String::new(),
-                    source_type: "pdf".to_string(),
-                });
-                eprintln!(
-                    "Warning: render failed for {} page {}: {}",
+                rendered.push((page_index, None));
+                tracing::warn!(
+                    "Render failed for {} page {}: {}",
                     pdf_path,
                     page_index + 1,
                     e
@@ -173,6 +205,33 @@ fn generate_pdf_page_thumbnails(
         }
     }
 
+    // Encoding is pure Rust, so it runs in parallel; collect() keeps page order
+    let thumbnails: Vec<PageThumbnail> = rendered
+        .par_iter()
+        .map(|(page_index, image)| {
+            let thumbnail_b64 = match image {
+                Some(img) => encode_thumbnail(img, 120, mime).unwrap_or_else(|e| {
+                    tracing::warn!(
+                        "Thumbnail encode failed for {} page {}: {}",
+                        pdf_path,
+                        page_index + 1,
+                        e
+                    );
+                    String::new()
+                }),
+                None => String::new(),
+            };
+            PageThumbnail {
+                id: format!("pdf_{}_p{}", pdf_stem, page_index + 1),
+                source_path: pdf_path.to_string(),
+                page_number: page_index + 1,
+                thumbnail_b64,
+                thumbnail_mime: mime.to_string(),
+                source_type: "pdf".to_string(),
+            }
+        })
+        .collect();
+
     Ok(thumbnails)
 }
 
@@ -189,6 +248,7 @@ pub fn generate_thumbnails_batch(
     pdfium: &Pdfium,
     start_page: Option<usize>,
     max_pages: Option<usize>,
+    mime: &str,
 ) -> Vec<PageThumbnail> {
     let mut image_paths: Vec<String> = Vec::new();
     let mut pdf_paths: Vec<String> = Vec::new();
@@ -210,17 +270,14 @@ pub fn generate_thumbnails_batch(
     // Generate image thumbnails in parallel with rayon
     let mut all_thumbnails: Vec<PageThumbnail> = image_paths
         .par_iter()
-        .filter_map(|path| generate_image_thumbnail(path).ok())
+        .filter_map(|path| generate_image_thumbnail(path, mime).ok())
         .collect();
 
     // Use the shared Pdfium instance for all PDF thumbnails
     for pdf_path in &pdf_paths {
-        match generate_pdf_page_thumbnails(pdf_path, pdfium, start_page, max_pages) {
+        match generate_pdf_page_thumbnails(pdf_path, pdfium, start_page, max_pages, mime) {
             Ok(thumbs) => all_thumbnails.extend(thumbs),
-            Err(e) => eprintln!(
-                "Warning: PDF thumbnail generation failed for {}: {}",
-                pdf_path, e
-            ),
+            Err(e) => tracing::error!("PDF thumbnail generation failed for {}: {}", pdf_path, e),
         }
     }
 
@@ -229,7 +286,7 @@ pub fn generate_thumbnails_batch(
 
 // --- PDF Merge ---
 
-fn get_page_dimensions(format: &str, orientation: &str) -> (f32, f32) {
+pub(crate) fn get_page_dimensions(format: &str, orientation: &str) -> (f32, f32) {
     let (w, h) = match format {
         "a4" => (595.28, 841.89),
         "letter" => (612.0, 792.0),
@@ -263,7 +320,15 @@ fn add_image_page(
     let quality = options.image_quality.clamp(1, 100) as u8;
     let margin = options.margin_px as f32;
 
-    embed_image_as_pdf_page(doc, pages_id, image_path, page_w, page_h, margin, quality)
+    embed_image_as_pdf_page(
+        doc,
+        pages_id,
+        image_path,
+        page_w,
+        page_h,
+        margin,
+        Some(quality),
+    )
 }
 
 // Copies a single page from an already-loaded source PDF into the destination.
@@ -371,6 +436,159 @@ fn clone_object_recursive(
     }
 }
 
+// --- Table of Contents ---
+
+const TOC_MAX_TITLE_CHARS: usize = 60;
+const TOC_MARGIN: f32 = 50.0;
+const TOC_TITLE_SIZE: f32 = 18.0;
+const TOC_ENTRY_SIZE: f32 = 11.0;
+const TOC_LINE_HEIGHT: f32 = 16.0;
+/// Helvetica digit advance width (all digits share it), in 1/1000 em.
+const HELVETICA_DIGIT_WIDTH: f32 = 0.556;
+
+/// A TOC line: the source file and the index of its first page among the merged pages.
+struct TocEntry {
+    title: String,
+    first_page_index: usize,
+}
+
+/// File name truncated to the TOC width, reduced to ASCII since Helvetica's
+/// standard encoding cannot show other characters.
+fn toc_title(path: &str) -> String {
+    let name: String = filename_or_default(path)
+        .chars()
+        .map(|c| {
+            if c.is_ascii() && !c.is_ascii_control() {
+                c
+            } else {
+                '?'
+            }
+        })
+        .collect();
+    if name.chars().count() <= TOC_MAX_TITLE_CHARS {
+        name
+    } else {
+        let mut truncated: String = name.chars().take(TOC_MAX_TITLE_CHARS - 3).collect();
+        truncated.push_str("...");
+        truncated
+    }
+}
+
+fn toc_entries_per_page(page_h: f32) -> usize {
+    let usable = page_h - 2.0 * TOC_MARGIN - TOC_TITLE_SIZE * 2.0;
+    ((usable / TOC_LINE_HEIGHT).floor() as usize).max(1)
+}
+
+fn text_op(x: f32, y: f32, size: f32, text: &str) -> Vec<Operation> {
+    vec![
+        Operation::new("BT", vec![]),
+        Operation::new("Tf", vec!["F1".into(), Object::Real(size)]),
+        Operation::new("Td", vec![Object::Real(x), Object::Real(y)]),
+        Operation::new("Tj", vec![Object::string_literal(text)]),
+        Operation::new("ET", vec![]),
+    ]
+}
+
+/// Build the TOC pages (as many as needed). With `with_links`, each entry gets a GoTo
+/// link to its page; without, the pages form a text-only "Index".
+/// `content_page_ids` are the merged pages the entries point at.
+fn build_toc_pages(
+    doc: &mut LopdfDocument,
+    pages_id: lopdf::ObjectId,
+    entries: &[TocEntry],
+    content_page_ids: &[Object],
+    page_w: f32,
+    page_h: f32,
+    with_links: bool,
+) -> Result<Vec<lopdf::ObjectId>, String> {
+    let title = if with_links {
+        "Table of Contents"
+    } else {
+        "Index"
+    };
+    let per_page = toc_entries_per_page(page_h);
+    let toc_page_count = entries.len().div_ceil(per_page).max(1);
+
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding"
+    });
+
+    let mut toc_page_ids = Vec::with_capacity(toc_page_count);
+    for (toc_index, chunk) in entries.chunks(per_page).enumerate() {
+        let mut operations = Vec::new();
+        let mut annotations: Vec<Object> = Vec::new();
+
+        let heading = if toc_index == 0 {
+            title.to_string()
+        } else {
+            format!("{} ({})", title, toc_index + 1)
+        };
+        let mut y = page_h - TOC_MARGIN - TOC_TITLE_SIZE;
+        operations.extend(text_op(TOC_MARGIN, y, TOC_TITLE_SIZE, &heading));
+        y -= TOC_TITLE_SIZE;
+
+        for entry in chunk {
+            y -= TOC_LINE_HEIGHT;
+            let page_label = (toc_page_count + entry.first_page_index + 1).to_string();
+            let label_w = page_label.len() as f32 * HELVETICA_DIGIT_WIDTH * TOC_ENTRY_SIZE;
+            operations.extend(text_op(TOC_MARGIN, y, TOC_ENTRY_SIZE, &entry.title));
+            operations.extend(text_op(
+                page_w - TOC_MARGIN - label_w,
+                y,
+                TOC_ENTRY_SIZE,
+                &page_label,
+            ));
+
+            if !with_links {
+                continue;
+            }
+            if let Some(target) = content_page_ids.get(entry.first_page_index) {
+                annotations.push(Object::Dictionary(dictionary! {
+                    "Type" => "Annot",
+                    "Subtype" => "Link",
+                    "Rect" => vec![
+                        Object::Real(TOC_MARGIN),
+                        Object::Real(y - 3.0),
+                        Object::Real(page_w - TOC_MARGIN),
+                        Object::Real(y + TOC_ENTRY_SIZE),
+                    ],
+                    "Border" => vec![Object::Integer(0), Object::Integer(0), Object::Integer(0)],
+                    "A" => dictionary! {
+                        "S" => "GoTo",
+                        "D" => vec![target.clone(), "Fit".into()]
+                    }
+                }));
+            }
+        }
+
+        let content_bytes = Content { operations }
+            .encode()
+            .map_err(|e| format!("Content encode error: {}", e))?;
+        let content_id = doc.add_object(Stream::new(dictionary! {}, content_bytes));
+
+        toc_page_ids.push(doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+            "MediaBox" => vec![
+                Object::Integer(0),
+                Object::Integer(0),
+                Object::Real(page_w),
+                Object::Real(page_h),
+            ],
+            "Resources" => dictionary! {
+                "Font" => dictionary! { "F1" => font_id }
+            },
+            "Contents" => content_id,
+            "Annots" => annotations
+        }));
+    }
+
+    Ok(toc_page_ids)
+}
+
 pub fn merge_to_pdf(
     items: Vec<PdfBuilderItem>,
     options: MergePdfOptions,
@@ -412,8 +630,20 @@ pub fn merge_to_pdf(
     }
 
     let total_items = items.len();
+    let list_sources = options.generate_toc || options.generate_index;
+    let mut toc_entries: Vec<TocEntry> = Vec::new();
+    let mut last_source: Option<&str> = None;
 
     for (idx, item) in items.iter().enumerate() {
+        // One TOC entry per run of consecutive pages from the same file
+        let first_page_index = page_ids.len();
+        if list_sources && last_source != Some(item.source_path.as_str()) {
+            toc_entries.push(TocEntry {
+                title: toc_title(&item.source_path),
+                first_page_index,
+            });
+        }
+
         match item.source_type.as_str() {
             "image" => match add_image_page(&mut doc, pages_id, &item.source_path, &options) {
                 Ok(page_id) => {
@@ -471,6 +701,12 @@ pub fn merge_to_pdf(
                     .push(format!("Unknown source type: {}", other));
             }
         }
+        if page_ids.len() > first_page_index {
+            last_source = Some(item.source_path.as_str());
+        } else if list_sources && last_source != Some(item.source_path.as_str()) {
+            // Nothing was added for this item — drop its TOC entry
+            toc_entries.pop();
+        }
         emit_progress_simple(app_handle, idx + 1, total_items, &item.source_path);
     }
 
@@ -481,6 +717,25 @@ pub fn merge_to_pdf(
         return result;
     }
 
+    if list_sources && !toc_entries.is_empty() {
+        let (page_w, page_h) = get_page_dimensions(&options.page_format, &options.orientation);
+        match build_toc_pages(
+            &mut doc,
+            pages_id,
+            &toc_entries,
+            &page_ids,
+            page_w,
+            page_h,
+            options.generate_toc,
+        ) {
+            Ok(toc_page_ids) => {
+                result.page_count += toc_page_ids.len();
+                page_ids.splice(0..0, toc_page_ids.into_iter().map(Object::Reference));
+            }
+            Err(e) => result.errors.push(format!("Table of contents: {}", e)),
+        }
+    }
+
     let pages = dictionary! {
         "Type" => "Pages",
         "Kids" => page_ids,
@@ -508,3 +763,53 @@ pub fn merge_to_pdf(
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toc_title_truncates_long_names() {
+        assert_eq!(toc_title("/docs/report.pdf"), "report.pdf");
+        let long = format!("/docs/{}.pdf", "a".repeat(80));
+        let title = toc_title(&long);
+        assert_eq!(title.chars().count(), TOC_MAX_TITLE_CHARS);
+        assert!(title.ends_with("..."));
+    }
+
+    #[test]
+    fn toc_title_replaces_non_ascii() {
+        assert_eq!(toc_title("/docs/résumé.pdf"), "r?sum?.pdf");
+    }
+
+    #[test]
+    fn index_pages_have_no_links() {
+        let mut doc = LopdfDocument::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let entries = vec![
+            TocEntry {
+                title: "a.pdf".to_string(),
+                first_page_index: 0,
+            },
+            TocEntry {
+                title: "b.png".to_string(),
+                first_page_index: 3,
+            },
+        ];
+        let targets = vec![Object::Null; 4];
+        let (w, h) = get_page_dimensions("letter", "landscape");
+        let ids = build_toc_pages(&mut doc, pages_id, &entries, &targets, w, h, false).unwrap();
+        assert_eq!(ids.len(), 1);
+        let page = doc.get_dictionary(ids[0]).unwrap();
+        assert!(page.get(b"Annots").unwrap().as_array().unwrap().is_empty());
+        let media_box = page.get(b"MediaBox").unwrap().as_array().unwrap();
+        assert_eq!(media_box[2].as_float().unwrap(), 792.0);
+    }
+
+    #[test]
+    fn toc_entries_per_page_fits_a4() {
+        let (_, h) = get_page_dimensions("a4", "portrait");
+        let per_page = toc_entries_per_page(h);
+        assert!(per_page > 30 && per_page < 60);
+    }
+}
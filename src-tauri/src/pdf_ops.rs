@@ -1,10 +1,22 @@
-use lopdf::{dictionary, Document as LopdfDocument, Object};
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document as LopdfDocument, Object, Stream, StringFormat};
 use pdfium_render::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::PathBuf;
+use std::process::Command;
+use time::OffsetDateTime;
 
-use crate::progress::emit_progress_simple;
-use crate::utils::{embed_image_as_pdf_page, ensure_output_dir, file_stem, filename_or_default};
+use crate::pdf_builder_ops::get_page_dimensions;
+use crate::pdf_split_ops::decode_pdf_text;
+use crate::progress::{emit_batch_pdf_progress, emit_progress_simple};
+use crate::utils::{
+    add_jpeg_image_xobject, embed_image_as_pdf_page, ensure_output_dir, file_size, file_stem,
+    filename_or_default, PHOTO_JPEG_QUALITY,
+};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PdfExtractionResult {
@@ -93,21 +105,34 @@ pub fn extract_images_from_pdf(
 pub struct ImagesToPdfResult {
     pub output_path: String,
     pub page_count: usize,
+    pub archive_compliant: bool,
     pub errors: Vec<String>,
 }
 
+/// Combine images into a PDF, one image per page.
+/// `page_format` "fit" sizes each page to its image (plus margins); "a4" and "letter"
+/// use the standard page size with the image scaled down and centered inside `margin`.
+/// With `archive_mode`, the output carries the PDF/A-1b metadata (XMP identification,
+/// MarkInfo, sRGB OutputIntent) and a 1.4 header. Pages are already PDF/A-safe:
+/// `embed_image_as_pdf_page` writes opaque DeviceRGB JPEGs with no transparency.
+/// JPEG quality is picked per page, so diagrams and screenshots stay legible.
 pub fn images_to_pdf(
     input_paths: Vec<String>,
     output_path: &str,
+    page_format: &str,
+    margin: f32,
+    archive_mode: bool,
     app_handle: &tauri::AppHandle,
 ) -> ImagesToPdfResult {
     let mut result = ImagesToPdfResult {
         output_path: output_path.to_string(),
         page_count: 0,
+        archive_compliant: false,
         errors: Vec::new(),
     };
 
-    let mut doc = LopdfDocument::with_version("1.7");
+    let version = if archive_mode { "1.4" } else { "1.7" };
+    let mut doc = LopdfDocument::with_version(version);
     let pages_id = doc.new_object_id();
     let mut page_ids: Vec<Object> = Vec::new();
 
@@ -129,7 +154,14 @@ pub fn images_to_pdf(
             }
         };
 
-        match embed_image_as_pdf_page(&mut doc, pages_id, input_path, width, height, 0.0, 85) {
+        let (page_w, page_h) = if page_format == "fit" {
+            (width + 2.0 * margin, height + 2.0 * margin)
+        } else {
+            get_page_dimensions(page_format, "portrait")
+        };
+
+        match embed_image_as_pdf_page(&mut doc, pages_id, input_path, page_w, page_h, margin, None)
+        {
             Ok(page_id) => {
                 page_ids.push(Object::Reference(page_id));
                 result.page_count += 1;
@@ -143,126 +175,1911 @@ pub fn images_to_pdf(
         emit_progress_simple(app_handle, idx + 1, total, input_path);
     }
 
-    if result.page_count == 0 {
-        result
-            .errors
-            .push("No images could be added to the PDF".to_string());
-        return result;
+    if result.page_count == 0 {
+        result
+            .errors
+            .push("No images could be added to the PDF".to_string());
+        return result;
+    }
+
+    let pages = dictionary! {
+        "Type" => "Pages",
+        "Kids" => page_ids,
+        "Count" => result.page_count as i64
+    };
+    doc.objects.insert(pages_id, Object::Dictionary(pages));
+
+    let mut catalog = dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    };
+    if archive_mode {
+        add_pdfa1b_metadata(&mut doc, &mut catalog, output_path);
+    }
+    let catalog_id = doc.add_object(catalog);
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+
+    if let Err(e) = doc.save(output_path) {
+        result.errors.push(format!("Cannot save PDF: {}", e));
+        result.page_count = 0;
+        return result;
+    }
+
+    result.archive_compliant = archive_mode;
+    result
+}
+
+// --- Images to PDF grid ---
+
+/// Grid images are downsampled to this resolution at their printed cell size.
+const GRID_IMAGE_DPI: f32 = 300.0;
+
+/// Size of one grid cell in points, or `None` when margins and gaps leave no room.
+fn grid_cell_size(
+    (page_w, page_h): (f32, f32),
+    cols: u32,
+    rows: u32,
+    margin: f32,
+    gap: f32,
+) -> Option<(f32, f32)> {
+    let cell_w = (page_w - 2.0 * margin - (cols - 1) as f32 * gap) / cols as f32;
+    let cell_h = (page_h - 2.0 * margin - (rows - 1) as f32 * gap) / rows as f32;
+    (cell_w >= 1.0 && cell_h >= 1.0).then_some((cell_w, cell_h))
+}
+
+/// Bottom-left corner (PDF coordinates) of slot `index`, filling rows top to bottom.
+fn grid_cell_origin(
+    index: u32,
+    cols: u32,
+    page_h: f32,
+    (cell_w, cell_h): (f32, f32),
+    margin: f32,
+    gap: f32,
+) -> (f32, f32) {
+    let (row, col) = ((index / cols) as f32, (index % cols) as f32);
+    (
+        margin + col * (cell_w + gap),
+        page_h - margin - (row + 1.0) * cell_h - row * gap,
+    )
+}
+
+/// Largest centered crop of a `width × height` image with the given aspect ratio.
+fn centered_aspect_crop(width: u32, height: u32, aspect: f32) -> (u32, u32, u32, u32) {
+    if width as f32 / height as f32 > aspect {
+        let crop_w = ((height as f32 * aspect).round() as u32).clamp(1, width);
+        ((width - crop_w) / 2, 0, crop_w, height)
+    } else {
+        let crop_h = ((width as f32 / aspect).round() as u32).clamp(1, height);
+        (0, (height - crop_h) / 2, width, crop_h)
+    }
+}
+
+/// Load an image cropped to the cell's aspect ratio and capped at `GRID_IMAGE_DPI`.
+fn load_grid_image(path: &str, (cell_w, cell_h): (f32, f32)) -> Result<image::RgbImage, String> {
+    let img = image::open(path).map_err(|e| format!("Cannot open image: {}", e))?;
+    let (x, y, w, h) = centered_aspect_crop(img.width(), img.height(), cell_w / cell_h);
+    let cropped = img.crop_imm(x, y, w, h);
+    let max_w = (cell_w / 72.0 * GRID_IMAGE_DPI).ceil() as u32;
+    let max_h = (cell_h / 72.0 * GRID_IMAGE_DPI).ceil() as u32;
+    let fitted = if w > max_w || h > max_h {
+        cropped.resize_exact(max_w, max_h, image::imageops::FilterType::Lanczos3)
+    } else {
+        cropped
+    };
+    Ok(fitted.into_rgb8())
+}
+
+fn add_grid_page(
+    doc: &mut LopdfDocument,
+    pages_id: lopdf::ObjectId,
+    (page_w, page_h): (f32, f32),
+    operations: Vec<Operation>,
+    xobjects: Dictionary,
+) -> Result<lopdf::ObjectId, String> {
+    let content = Content { operations }
+        .encode()
+        .map_err(|e| format!("Content encode error: {}", e))?;
+    let content_id = doc.add_object(Stream::new(dictionary! {}, content));
+    Ok(doc.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "MediaBox" => vec![
+            Object::Integer(0),
+            Object::Integer(0),
+            Object::Real(page_w),
+            Object::Real(page_h),
+        ],
+        "Resources" => dictionary! { "XObject" => xobjects },
+        "Contents" => content_id
+    }))
+}
+
+/// Contact sheet: place `cols × rows` images per page, each center-cropped to the
+/// cell aspect ratio, adding pages as needed. Images that fail to load are
+/// reported and their slot goes to the next image.
+#[allow(clippy::too_many_arguments)]
+pub fn images_to_pdf_grid(
+    input_paths: &[String],
+    cols: u32,
+    rows: u32,
+    page_format: &str,
+    margin: f32,
+    gap: f32,
+    output_path: &str,
+    app_handle: &tauri::AppHandle,
+) -> ImagesToPdfResult {
+    let mut result = ImagesToPdfResult {
+        output_path: output_path.to_string(),
+        page_count: 0,
+        archive_compliant: false,
+        errors: Vec::new(),
+    };
+
+    if cols == 0 || rows == 0 {
+        result
+            .errors
+            .push("Grid needs at least one row and one column".to_string());
+        return result;
+    }
+    let page = get_page_dimensions(page_format, "portrait");
+    let Some(cell) = grid_cell_size(page, cols, rows, margin, gap) else {
+        result
+            .errors
+            .push("Margins and gaps leave no room for the grid".to_string());
+        return result;
+    };
+
+    let mut doc = LopdfDocument::with_version("1.7");
+    let pages_id = doc.new_object_id();
+    let mut page_ids: Vec<Object> = Vec::new();
+    let per_page = cols * rows;
+    let mut slot = 0u32;
+    let mut operations: Vec<Operation> = Vec::new();
+    let mut xobjects = Dictionary::new();
+
+    let total = input_paths.len();
+    for (idx, input_path) in input_paths.iter().enumerate() {
+        let placed = load_grid_image(input_path, cell)
+            .and_then(|img| add_jpeg_image_xobject(&mut doc, &img, None));
+        match placed {
+            Ok(image_id) => {
+                let name = format!("Img{}", slot);
+                let (x, y) = grid_cell_origin(slot, cols, page.1, cell, margin, gap);
+                operations.extend([
+                    Operation::new("q", vec![]),
+                    Operation::new(
+                        "cm",
+                        vec![
+                            Object::Real(cell.0),
+                            Object::Integer(0),
+                            Object::Integer(0),
+                            Object::Real(cell.1),
+                            Object::Real(x),
+                            Object::Real(y),
+                        ],
+                    ),
+                    Operation::new("Do", vec![Object::Name(name.clone().into_bytes())]),
+                    Operation::new("Q", vec![]),
+                ]);
+                xobjects.set(name, image_id);
+                slot += 1;
+            }
+            Err(e) => result
+                .errors
+                .push(format!("{}: {}", filename_or_default(input_path), e)),
+        }
+
+        let last = idx + 1 == total;
+        if slot == per_page || (last && slot > 0) {
+            let ops = std::mem::take(&mut operations);
+            let objects = std::mem::take(&mut xobjects);
+            match add_grid_page(&mut doc, pages_id, page, ops, objects) {
+                Ok(page_id) => page_ids.push(Object::Reference(page_id)),
+                Err(e) => result.errors.push(e),
+            }
+            slot = 0;
+        }
+        emit_progress_simple(app_handle, idx + 1, total, input_path);
+    }
+
+    if page_ids.is_empty() {
+        result
+            .errors
+            .push("No images could be added to the PDF".to_string());
+        return result;
+    }
+
+    result.page_count = page_ids.len();
+    let pages = dictionary! {
+        "Type" => "Pages",
+        "Kids" => page_ids,
+        "Count" => result.page_count as i64
+    };
+    doc.objects.insert(pages_id, Object::Dictionary(pages));
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    });
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+
+    if let Some(parent) = std::path::Path::new(output_path).parent() {
+        if let Err(e) = ensure_output_dir(parent) {
+            result.errors.push(e);
+            result.page_count = 0;
+            return result;
+        }
+    }
+    if let Err(e) = doc.save(output_path) {
+        result.errors.push(format!("Cannot save PDF: {}", e));
+        result.page_count = 0;
+    }
+    result
+}
+
+// --- Searchable PDF (OCR) ---
+
+/// Rough Helvetica advance width in em, used to stretch OCR words to their boxes.
+const OCR_AVG_CHAR_WIDTH_EM: f32 = 0.5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchablePdfResult {
+    pub output_path: String,
+    pub page_count: usize,
+    pub word_count: usize,
+    pub errors: Vec<String>,
+}
+
+/// One recognized word with its pixel box (origin top-left).
+#[derive(Debug, Clone, PartialEq)]
+struct OcrWord {
+    left: f32,
+    top: f32,
+    width: f32,
+    height: f32,
+    text: String,
+}
+
+/// Parse Tesseract's TSV output, keeping word-level rows (level 5) with text.
+fn parse_tesseract_tsv(tsv: &str) -> Vec<OcrWord> {
+    tsv.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split('\t').collect();
+            if cols.len() < 12 || cols[0] != "5" {
+                return None;
+            }
+            let text = cols[11].trim();
+            let conf: f32 = cols[10].parse().ok()?;
+            if text.is_empty() || conf < 0.0 {
+                return None;
+            }
+            Some(OcrWord {
+                left: cols[6].parse().ok()?,
+                top: cols[7].parse().ok()?,
+                width: cols[8].parse().ok()?,
+                height: cols[9].parse().ok()?,
+                text: text.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Run the `tesseract` CLI on one image and return its words.
+/// The engine is invoked as a subprocess so no native OCR libraries are linked.
+fn run_tesseract(image_path: &str, language: &str) -> Result<Vec<OcrWord>, String> {
+    let output = Command::new("tesseract")
+        .args([image_path, "stdout", "-l", language, "tsv"])
+        .output()
+        .map_err(|e| format!("Cannot run tesseract (is it installed and on PATH?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Tesseract failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(parse_tesseract_tsv(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Encode text for a WinAnsi standard font; characters outside Latin-1 become '?'.
+pub(crate) fn pdf_latin1_bytes(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Content operations placing each word as invisible text (render mode 3) over its box.
+/// Page units equal image pixels, matching the "fit" layout of `images_to_pdf`.
+fn build_ocr_text_ops(words: &[OcrWord], page_h: f32) -> Vec<Operation> {
+    let mut ops = vec![Operation::new("q", vec![])];
+    for word in words {
+        let font_size = word.height.max(1.0);
+        let natural_w = word.text.chars().count() as f32 * OCR_AVG_CHAR_WIDTH_EM * font_size;
+        let h_scale = if natural_w > 0.0 {
+            word.width / natural_w * 100.0
+        } else {
+            100.0
+        };
+        let x = word.left;
+        let y = page_h - (word.top + word.height);
+        ops.extend([
+            Operation::new("BT", vec![]),
+            Operation::new("Tr", vec![Object::Integer(3)]),
+            Operation::new(
+                "Tf",
+                vec![Object::Name(b"OcrF1".to_vec()), Object::Real(font_size)],
+            ),
+            Operation::new("Tz", vec![Object::Real(h_scale)]),
+            Operation::new("Td", vec![Object::Real(x), Object::Real(y)]),
+            Operation::new(
+                "Tj",
+                vec![Object::String(
+                    pdf_latin1_bytes(&word.text),
+                    StringFormat::Literal,
+                )],
+            ),
+            Operation::new("ET", vec![]),
+        ]);
+    }
+    ops.push(Operation::new("Q", vec![]));
+    ops
+}
+
+/// Append an OCR text layer to a page created by `embed_image_as_pdf_page`.
+fn add_text_layer(
+    doc: &mut LopdfDocument,
+    page_id: lopdf::ObjectId,
+    font_id: lopdf::ObjectId,
+    words: &[OcrWord],
+    page_h: f32,
+) -> Result<(), String> {
+    let content = Content {
+        operations: build_ocr_text_ops(words, page_h),
+    };
+    let bytes = content
+        .encode()
+        .map_err(|e| format!("Content encode error: {}", e))?;
+    let text_id = doc.add_object(Stream::new(dictionary! {}, bytes));
+
+    let page = doc
+        .get_object_mut(page_id)
+        .and_then(|o| o.as_dict_mut())
+        .map_err(|e| format!("Cannot update page: {}", e))?;
+    let image_content = page
+        .get(b"Contents")
+        .and_then(|c| c.as_reference())
+        .map_err(|e| format!("Page has no content stream: {}", e))?;
+    page.set(
+        "Contents",
+        vec![Object::Reference(image_content), Object::Reference(text_id)],
+    );
+    let resources = page
+        .get_mut(b"Resources")
+        .and_then(|r| r.as_dict_mut())
+        .map_err(|e| format!("Page has no resources: {}", e))?;
+    resources.set("Font", dictionary! { "OcrF1" => font_id });
+    Ok(())
+}
+
+/// Build a searchable PDF: each image fills its page and the OCR words sit on top
+/// as an invisible text layer that search tools and screen readers can use.
+pub fn image_to_pdf_searchable(
+    input_paths: &[String],
+    ocr_engine: &str,
+    language: &str,
+    output_path: &str,
+    app_handle: &tauri::AppHandle,
+) -> SearchablePdfResult {
+    let mut result = SearchablePdfResult {
+        output_path: output_path.to_string(),
+        page_count: 0,
+        word_count: 0,
+        errors: Vec::new(),
+    };
+
+    if ocr_engine != "tesseract" {
+        result
+            .errors
+            .push(format!("Unsupported OCR engine: {}", ocr_engine));
+        return result;
+    }
+    let valid_language = !language.is_empty()
+        && language
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '+');
+    if !valid_language {
+        result
+            .errors
+            .push(format!("Invalid OCR language: {}", language));
+        return result;
+    }
+
+    let mut doc = LopdfDocument::with_version("1.7");
+    let pages_id = doc.new_object_id();
+    let font_id = doc.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+        "Encoding" => "WinAnsiEncoding"
+    });
+    let mut page_ids: Vec<Object> = Vec::new();
+
+    let total = input_paths.len();
+    for (idx, input_path) in input_paths.iter().enumerate() {
+        let name = filename_or_default(input_path);
+        let page = image::ImageReader::open(input_path)
+            .and_then(|r| r.with_guessed_format())
+            .map_err(|e| e.to_string())
+            .and_then(|r| r.into_dimensions().map_err(|e| e.to_string()))
+            .and_then(|(w, h)| {
+                let words = run_tesseract(input_path, language)?;
+                let (page_w, page_h) = (w as f32, h as f32);
+                let page_id = embed_image_as_pdf_page(
+                    &mut doc,
+                    pages_id,
+                    input_path,
+                    page_w,
+                    page_h,
+                    0.0,
+                    Some(PHOTO_JPEG_QUALITY),
+                )?;
+                add_text_layer(&mut doc, page_id, font_id, &words, page_h)?;
+                Ok((page_id, words.len()))
+            });
+        match page {
+            Ok((page_id, word_count)) => {
+                page_ids.push(Object::Reference(page_id));
+                result.page_count += 1;
+                result.word_count += word_count;
+            }
+            Err(e) => result.errors.push(format!("{}: {}", name, e)),
+        }
+        emit_progress_simple(app_handle, idx + 1, total, input_path);
+    }
+
+    if result.page_count == 0 {
+        result
+            .errors
+            .push("No images could be added to the PDF".to_string());
+        return result;
+    }
+
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids,
+            "Count" => result.page_count as i64
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    });
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+
+    if let Err(e) = doc.save(output_path) {
+        result.errors.push(format!("Cannot save PDF: {}", e));
+        result.page_count = 0;
+    }
+    result
+}
+
+// --- PDF/A-1b ---
+
+const SRGB_OUTPUT_CONDITION: &str = "sRGB IEC61966-2.1";
+const PDF_PRODUCER: &str = "Rust-ine";
+
+/// Attach the catalog entries and trailer data PDF/A-1b requires:
+/// XMP metadata with the pdfaid schema, MarkInfo, an sRGB OutputIntent,
+/// and a matching Info dictionary / file ID.
+fn add_pdfa1b_metadata(doc: &mut LopdfDocument, catalog: &mut Dictionary, output_path: &str) {
+    let now = OffsetDateTime::now_utc();
+    let xmp_date = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    // The Info dictionary dates must describe the same instant as the XMP dates
+    let pdf_date = format!(
+        "D:{:04}{:02}{:02}{:02}{:02}{:02}Z",
+        now.year(),
+        now.month() as u8,
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+
+    let xmp = format!(
+        r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+<rdf:Description rdf:about="" xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/">
+<pdfaid:part>1</pdfaid:part>
+<pdfaid:conformance>B</pdfaid:conformance>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+<pdf:Producer>{producer}</pdf:Producer>
+</rdf:Description>
+<rdf:Description rdf:about="" xmlns:xmp="http://ns.adobe.com/xap/1.0/">
+<xmp:CreateDate>{date}</xmp:CreateDate>
+<xmp:ModifyDate>{date}</xmp:ModifyDate>
+</rdf:Description>
+</rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#,
+        bom = '\u{feff}',
+        producer = PDF_PRODUCER,
+        date = xmp_date
+    );
+
+    // PDF/A forbids filters on the metadata stream — it must stay plain XML
+    let mut metadata_stream = Stream::new(
+        dictionary! {
+            "Type" => "Metadata",
+            "Subtype" => "XML"
+        },
+        xmp.into_bytes(),
+    );
+    metadata_stream.allows_compression = false;
+    let metadata_id = doc.add_object(metadata_stream);
+
+    let icc_id = doc.add_object(Stream::new(
+        dictionary! { "N" => 3_i64 },
+        srgb_icc_profile(),
+    ));
+    let output_intent = dictionary! {
+        "Type" => "OutputIntent",
+        "S" => "GTS_PDFA1",
+        "OutputConditionIdentifier" => Object::string_literal(SRGB_OUTPUT_CONDITION),
+        "Info" => Object::string_literal(SRGB_OUTPUT_CONDITION),
+        "DestOutputProfile" => icc_id
+    };
+
+    catalog.set("Metadata", metadata_id);
+    catalog.set("MarkInfo", dictionary! { "Marked" => true });
+    catalog.set("OutputIntents", vec![Object::Dictionary(output_intent)]);
+
+    let info_id = doc.add_object(dictionary! {
+        "Producer" => Object::string_literal(PDF_PRODUCER),
+        "CreationDate" => Object::string_literal(pdf_date.clone()),
+        "ModDate" => Object::string_literal(pdf_date)
+    });
+    doc.trailer.set("Info", info_id);
+
+    let file_id = md5::compute(format!("{}{}", output_path, xmp_date))
+        .0
+        .to_vec();
+    doc.trailer.set(
+        "ID",
+        vec![
+            Object::String(file_id.clone(), StringFormat::Hexadecimal),
+            Object::String(file_id, StringFormat::Hexadecimal),
+        ],
+    );
+}
+
+/// Append an ICC `s15Fixed16Number` (big-endian).
+fn push_s15_fixed16(buf: &mut Vec<u8>, value: f64) {
+    buf.extend_from_slice(&((value * 65536.0).round() as i32).to_be_bytes());
+}
+
+/// Build a minimal ICC v2 display profile describing sRGB (IEC 61966-2.1):
+/// D50-adapted primaries and a 1024-entry sRGB transfer curve.
+fn srgb_icc_profile() -> Vec<u8> {
+    let description = SRGB_OUTPUT_CONDITION.as_bytes();
+    let copyright = b"No copyright, use freely";
+
+    // textDescriptionType: ASCII part followed by empty Unicode and ScriptCode parts
+    let mut desc = Vec::new();
+    desc.extend_from_slice(b"desc\0\0\0\0");
+    desc.extend_from_slice(&(description.len() as u32 + 1).to_be_bytes());
+    desc.extend_from_slice(description);
+    desc.push(0);
+    desc.extend_from_slice(&[0u8; 4 + 4 + 2 + 1 + 67]);
+
+    let mut cprt = Vec::new();
+    cprt.extend_from_slice(b"text\0\0\0\0");
+    cprt.extend_from_slice(copyright);
+    cprt.push(0);
+
+    let xyz = |x: f64, y: f64, z: f64| {
+        let mut tag = Vec::new();
+        tag.extend_from_slice(b"XYZ \0\0\0\0");
+        push_s15_fixed16(&mut tag, x);
+        push_s15_fixed16(&mut tag, y);
+        push_s15_fixed16(&mut tag, z);
+        tag
+    };
+
+    const TRC_ENTRIES: usize = 1024;
+    let mut trc = Vec::new();
+    trc.extend_from_slice(b"curv\0\0\0\0");
+    trc.extend_from_slice(&(TRC_ENTRIES as u32).to_be_bytes());
+    for i in 0..TRC_ENTRIES {
+        let v = i as f64 / (TRC_ENTRIES - 1) as f64;
+        let linear = if v <= 0.04045 {
+            v / 12.92
+        } else {
+            ((v + 0.055) / 1.055).powf(2.4)
+        };
+        trc.extend_from_slice(&((linear * 65535.0).round() as u16).to_be_bytes());
+    }
+
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", desc),
+        (b"cprt", cprt),
+        (b"wtpt", xyz(0.9505, 1.0, 1.0891)),
+        (b"rXYZ", xyz(0.4361, 0.2225, 0.0139)),
+        (b"gXYZ", xyz(0.3851, 0.7169, 0.0971)),
+        (b"bXYZ", xyz(0.1431, 0.0606, 0.7141)),
+        (b"rTRC", trc),
+    ];
+    // gTRC and bTRC point at the rTRC data block
+    let tag_count = tags.len() + 2;
+
+    let mut table = Vec::new();
+    let mut data = Vec::new();
+    let data_start = 128 + 4 + tag_count * 12;
+    let mut trc_entry = (0u32, 0u32);
+    for (sig, bytes) in &tags {
+        let offset = (data_start + data.len()) as u32;
+        let size = bytes.len() as u32;
+        table.extend_from_slice(*sig);
+        table.extend_from_slice(&offset.to_be_bytes());
+        table.extend_from_slice(&size.to_be_bytes());
+        if *sig == b"rTRC" {
+            trc_entry = (offset, size);
+        }
+        data.extend_from_slice(bytes);
+        // Tag data elements are 4-byte aligned
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+    }
+    for sig in [b"gTRC", b"bTRC"] {
+        table.extend_from_slice(sig);
+        table.extend_from_slice(&trc_entry.0.to_be_bytes());
+        table.extend_from_slice(&trc_entry.1.to_be_bytes());
+    }
+
+    let total_size = (data_start + data.len()) as u32;
+    let mut profile = Vec::with_capacity(total_size as usize);
+    profile.extend_from_slice(&total_size.to_be_bytes());
+    profile.extend_from_slice(&[0u8; 4]); // preferred CMM
+    profile.extend_from_slice(&[0x02, 0x10, 0x00, 0x00]); // version 2.1
+    profile.extend_from_slice(b"mntr");
+    profile.extend_from_slice(b"RGB ");
+    profile.extend_from_slice(b"XYZ ");
+    for part in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&part.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    // platform, flags, manufacturer, model, attributes, rendering intent (perceptual)
+    profile.extend_from_slice(&[0u8; 4 + 4 + 4 + 4 + 8 + 4]);
+    push_s15_fixed16(&mut profile, 0.9642); // D50 illuminant
+    push_s15_fixed16(&mut profile, 1.0);
+    push_s15_fixed16(&mut profile, 0.8249);
+    profile.resize(128, 0);
+
+    profile.extend_from_slice(&(tag_count as u32).to_be_bytes());
+    profile.extend_from_slice(&table);
+    profile.extend_from_slice(&data);
+    profile
+}
+
+// --- PDF to Images ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfToImagesResult {
+    pub pdf_path: String,
+    pub output_dir: String,
+    pub exported_count: usize,
+    pub page_infos: Vec<PageImageInfo>,
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageImageInfo {
+    pub page_number: usize,
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Export DPI bounds: lower values give unreadable images, higher ones risk OOM on large pages.
+const MIN_EXPORT_DPI: u32 = 36;
+const MAX_EXPORT_DPI: u32 = 600;
+
+pub fn validate_export_dpi(dpi: u32) -> Result<(), String> {
+    if !(MIN_EXPORT_DPI..=MAX_EXPORT_DPI).contains(&dpi) {
+        return Err(format!(
+            "DPI must be between {} and {} (got {})",
+            MIN_EXPORT_DPI, MAX_EXPORT_DPI, dpi
+        ));
+    }
+    Ok(())
+}
+
+/// Render a page at the given DPI (pdfium's native unit is 72 DPI).
+fn render_page_at_dpi(page: &PdfPage, dpi: u32) -> Result<image::DynamicImage, PdfiumError> {
+    let scale = dpi as f32 / 72.0;
+    let page_w = page.width().value * scale;
+    let page_h = page.height().value * scale;
+
+    let render_config = PdfRenderConfig::new()
+        .set_target_width(page_w as i32)
+        .set_maximum_height(page_h as i32);
+
+    page.render_with_config(&render_config)
+        .map(|bitmap| bitmap.as_image())
+}
+
+/// Save a rendered page as JPEG ("jpg") or PNG (anything else).
+fn save_rendered_page(
+    img: &image::DynamicImage,
+    out_path: &std::path::Path,
+    format: &str,
+) -> Result<(), image::ImageError> {
+    if format == "jpg" {
+        img.to_rgb8().save(out_path)
+    } else {
+        img.save(out_path)
+    }
+}
+
+fn rendered_page_ext(format: &str) -> &'static str {
+    if format == "jpg" {
+        "jpg"
+    } else {
+        "png"
+    }
+}
+
+/// Subdirectory name for each PDF of a batch: its stem, suffixed `-2`, `-3`, …
+/// when several inputs share one.
+fn batch_pdf_subdirs(pdf_paths: &[String]) -> Vec<String> {
+    let mut used: HashSet<String> = HashSet::new();
+    pdf_paths
+        .iter()
+        .map(|path| {
+            let base = file_stem(path);
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !used.insert(name.to_lowercase()) {
+                name = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
+}
+
+/// Export several PDFs one after another (pdfium is not thread-safe), each into
+/// `output_dir/<pdf stem>/`, with a `"batch-pdf-progress"` event after every file.
+pub fn batch_pdf_to_images(
+    pdf_paths: &[String],
+    output_dir: &str,
+    pdfium: &Pdfium,
+    format: &str,
+    dpi: u32,
+    app_handle: &tauri::AppHandle,
+) -> Vec<PdfToImagesResult> {
+    let out_dir = PathBuf::from(output_dir);
+    let total = pdf_paths.len();
+    pdf_paths
+        .iter()
+        .zip(batch_pdf_subdirs(pdf_paths))
+        .enumerate()
+        .map(|(idx, (pdf_path, subdir))| {
+            let target = out_dir.join(subdir);
+            let result = pdf_to_images(
+                pdf_path,
+                &target.to_string_lossy(),
+                pdfium,
+                format,
+                dpi,
+                None,
+                app_handle,
+            );
+            emit_batch_pdf_progress(app_handle, idx + 1, total, pdf_path, result.exported_count);
+            result
+        })
+        .collect()
+}
+
+pub fn pdf_to_images(
+    pdf_path: &str,
+    output_dir: &str,
+    pdfium: &Pdfium,
+    format: &str,
+    dpi: u32,
+    output_stem: Option<&str>,
+    app_handle: &tauri::AppHandle,
+) -> PdfToImagesResult {
+    let mut result = PdfToImagesResult {
+        pdf_path: pdf_path.to_string(),
+        output_dir: output_dir.to_string(),
+        exported_count: 0,
+        page_infos: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    if let Err(e) = validate_export_dpi(dpi) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let document = match pdfium.load_pdf_from_file(pdf_path, None) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot open PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let pdf_stem = output_stem
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| file_stem(pdf_path));
+
+    let total_pages = document.pages().len() as usize;
+
+    for (page_index, page) in document.pages().iter().enumerate() {
+        let mut info = PageImageInfo {
+            page_number: page_index + 1,
+            output_path: String::new(),
+            width: 0,
+            height: 0,
+            success: false,
+            error: None,
+        };
+        match render_page_at_dpi(&page, dpi) {
+            Ok(dynamic_image) => {
+                let ext = rendered_page_ext(format);
+                let out_path =
+                    out_dir.join(format!("{}_page_{}.{}", pdf_stem, page_index + 1, ext));
+
+                match save_rendered_page(&dynamic_image, &out_path, format) {
+                    Ok(_) => {
+                        result.exported_count += 1;
+                        info.output_path = out_path.to_string_lossy().to_string();
+                        info.width = dynamic_image.width();
+                        info.height = dynamic_image.height();
+                        info.success = true;
+                    }
+                    Err(e) => info.error = Some(format!("failed to save — {}", e)),
+                }
+            }
+            Err(e) => info.error = Some(format!("render failed — {}", e)),
+        }
+        if let Some(e) = &info.error {
+            result
+                .errors
+                .push(format!("Page {}: {}", page_index + 1, e));
+        }
+        result.page_infos.push(info);
+        emit_progress_simple(app_handle, page_index + 1, total_pages, pdf_path);
+    }
+
+    result
+}
+
+// --- Rasterize ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfRasterizeResult {
+    pub output_path: String,
+    pub page_count: usize,
+    pub output_size_bytes: u64,
+    pub errors: Vec<String>,
+}
+
+/// A page rendered to disk, with the size (in points) of the page it came from.
+struct RasterPage {
+    image_path: String,
+    width_pt: f32,
+    height_pt: f32,
+}
+
+/// Render every page to a PNG in `work_dir`. Failed pages are reported and skipped.
+fn render_pages_to_dir(
+    document: &PdfDocument,
+    dpi: u32,
+    work_dir: &std::path::Path,
+    pdf_path: &str,
+    app_handle: &tauri::AppHandle,
+    errors: &mut Vec<String>,
+) -> Vec<RasterPage> {
+    let total_pages = document.pages().len() as usize;
+    let mut pages = Vec::with_capacity(total_pages);
+    for (page_index, page) in document.pages().iter().enumerate() {
+        let image_path = work_dir.join(format!("page_{:05}.png", page_index + 1));
+        let rendered = render_page_at_dpi(&page, dpi)
+            .map_err(|e| format!("render failed — {}", e))
+            .and_then(|img| {
+                img.save(&image_path)
+                    .map_err(|e| format!("failed to save — {}", e))
+            });
+        match rendered {
+            Ok(()) => pages.push(RasterPage {
+                image_path: image_path.to_string_lossy().to_string(),
+                width_pt: page.width().value,
+                height_pt: page.height().value,
+            }),
+            Err(e) => errors.push(format!("Page {}: {}", page_index + 1, e)),
+        }
+        emit_progress_simple(app_handle, page_index + 1, total_pages, pdf_path);
+    }
+    pages
+}
+
+/// Assemble image-only pages (one per raster, at its original page size) and save.
+fn save_raster_pdf(pages: &[RasterPage], output_path: &str) -> Result<usize, String> {
+    let mut doc = LopdfDocument::with_version("1.7");
+    let pages_id = doc.new_object_id();
+    let mut page_ids: Vec<Object> = Vec::with_capacity(pages.len());
+    for (index, page) in pages.iter().enumerate() {
+        let page_id = embed_image_as_pdf_page(
+            &mut doc,
+            pages_id,
+            &page.image_path,
+            page.width_pt,
+            page.height_pt,
+            0.0,
+            None,
+        )
+        .map_err(|e| format!("Page {}: {}", index + 1, e))?;
+        page_ids.push(Object::Reference(page_id));
+    }
+
+    let count = page_ids.len();
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => page_ids,
+            "Count" => count as i64
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    });
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+    doc.save(output_path)
+        .map_err(|e| format!("Cannot save PDF: {}", e))?;
+    Ok(count)
+}
+
+/// Flatten a PDF to images so no text can be selected or copied. Pages are rendered
+/// to PNGs in a temporary directory, then embedded the same way `images_to_pdf` does,
+/// but at each source page's own size so the printed dimensions do not change.
+/// Saves to `{stem}-rasterized.pdf`; the temporary PNGs are always removed.
+pub fn pdf_rasterize(
+    pdf_path: &str,
+    dpi: u32,
+    output_dir: &str,
+    password: Option<&str>,
+    pdfium: &Pdfium,
+    app_handle: &tauri::AppHandle,
+) -> PdfRasterizeResult {
+    let mut result = PdfRasterizeResult {
+        output_path: String::new(),
+        page_count: 0,
+        output_size_bytes: 0,
+        errors: Vec::new(),
+    };
+
+    if let Err(e) = validate_export_dpi(dpi) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let document = match pdfium.load_pdf_from_file(pdf_path, password) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot open PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    let work_dir = std::env::temp_dir().join(format!(
+        "rustine-rasterize-{}-{}",
+        std::process::id(),
+        nanos
+    ));
+    if let Err(e) = std::fs::create_dir_all(&work_dir) {
+        result
+            .errors
+            .push(format!("Cannot create temporary directory: {}", e));
+        return result;
+    }
+
+    let pages = render_pages_to_dir(
+        &document,
+        dpi,
+        &work_dir,
+        pdf_path,
+        app_handle,
+        &mut result.errors,
+    );
+    let output_path = out_dir.join(format!("{}-rasterized.pdf", file_stem(pdf_path)));
+    let output_path = output_path.to_string_lossy().to_string();
+    let saved = if pages.is_empty() {
+        Err("No pages could be rendered".to_string())
+    } else {
+        save_raster_pdf(&pages, &output_path)
+    };
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    match saved {
+        Ok(count) => {
+            result.page_count = count;
+            result.output_size_bytes = file_size(&output_path);
+            result.output_path = output_path;
+        }
+        Err(e) => result.errors.push(e),
+    }
+    result
+}
+
+// --- Single Page Render ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SinglePageRenderResult {
+    pub output_path: String,
+    pub width: u32,
+    pub height: u32,
+    pub errors: Vec<String>,
+}
+
+/// Render only `page_number` (1-based) of a PDF — used for previews where
+/// converting every page would be wasted work.
+pub fn pdf_render_page(
+    pdf_path: &str,
+    page_number: usize,
+    output_dir: &str,
+    pdfium: &Pdfium,
+    format: &str,
+    dpi: u32,
+    password: Option<&str>,
+) -> SinglePageRenderResult {
+    let mut result = SinglePageRenderResult {
+        output_path: String::new(),
+        width: 0,
+        height: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let document = match pdfium.load_pdf_from_file(pdf_path, password) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot open PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let total_pages = document.pages().len() as usize;
+    if page_number == 0 || page_number > total_pages {
+        result.errors.push(format!(
+            "Page {} is out of range (document has {} pages)",
+            page_number, total_pages
+        ));
+        return result;
+    }
+
+    let page = match document.pages().get((page_number - 1) as PdfPageIndex) {
+        Ok(p) => p,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Page {}: cannot load — {}", page_number, e));
+            return result;
+        }
+    };
+
+    let dynamic_image = match render_page_at_dpi(&page, dpi) {
+        Ok(img) => img,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Page {}: render failed — {}", page_number, e));
+            return result;
+        }
+    };
+
+    let out_path = out_dir.join(format!(
+        "{}_page_{}.{}",
+        file_stem(pdf_path),
+        page_number,
+        rendered_page_ext(format)
+    ));
+    match save_rendered_page(&dynamic_image, &out_path, format) {
+        Ok(_) => {
+            result.output_path = out_path.to_string_lossy().to_string();
+            result.width = dynamic_image.width();
+            result.height = dynamic_image.height();
+        }
+        Err(e) => result
+            .errors
+            .push(format!("Page {}: failed to save — {}", page_number, e)),
+    }
+
+    result
+}
+
+// --- Page Info ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfPageInfo {
+    pub page_number: usize,
+    pub width_pt: f32,
+    pub height_pt: f32,
+    pub rotation: i32,
+    pub media_box: [f32; 4],
+    pub crop_box: Option<[f32; 4]>,
+}
+
+fn rect_to_array(rect: &PdfRect) -> [f32; 4] {
+    [
+        rect.left().value,
+        rect.bottom().value,
+        rect.right().value,
+        rect.top().value,
+    ]
+}
+
+fn rotation_degrees(rotation: PdfPageRenderRotation) -> i32 {
+    match rotation {
+        PdfPageRenderRotation::None => 0,
+        PdfPageRenderRotation::Degrees90 => 90,
+        PdfPageRenderRotation::Degrees180 => 180,
+        PdfPageRenderRotation::Degrees270 => 270,
+    }
+}
+
+/// Report size, `/Rotate` and page boxes for every page, all in PDF points.
+/// Pages without an explicit MediaBox fall back to `[0, 0, width, height]`.
+pub fn pdf_page_info(
+    pdf_path: &str,
+    pdfium: &Pdfium,
+    password: Option<&str>,
+) -> Result<Vec<PdfPageInfo>, String> {
+    let document = pdfium
+        .load_pdf_from_file(pdf_path, password)
+        .map_err(|e| format!("Cannot open PDF '{}': {}", pdf_path, e))?;
+
+    let mut pages = Vec::new();
+    for (i, page) in document.pages().iter().enumerate() {
+        let width_pt = page.width().value;
+        let height_pt = page.height().value;
+        let boundaries = page.boundaries();
+        let media_box = boundaries
+            .media()
+            .map(|b| rect_to_array(&b.bounds))
+            .unwrap_or([0.0, 0.0, width_pt, height_pt]);
+        let crop_box = boundaries.crop().ok().map(|b| rect_to_array(&b.bounds));
+        let rotation = page.rotation().map(rotation_degrees).unwrap_or(0);
+
+        pages.push(PdfPageInfo {
+            page_number: i + 1,
+            width_pt,
+            height_pt,
+            rotation,
+            media_box,
+            crop_box,
+        });
+    }
+    Ok(pages)
+}
+
+// --- Text Search ---
+
+const SEARCH_CONTEXT_CHARS: usize = 80;
+const DEFAULT_MAX_SEARCH_MATCHES: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TextMatch {
+    pub page_number: usize,
+    pub context: String,
+    pub char_index: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SearchResult {
+    pub matches: Vec<TextMatch>,
+    pub total_matches: usize,
+}
+
+fn fold_char(c: char, case_sensitive: bool) -> char {
+    if case_sensitive {
+        c
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+/// Collect literal matches of `query` in one page's text, stopping once `matches` holds `limit`.
+/// `char_index` counts characters (not bytes) from the start of the page text.
+fn find_text_matches(
+    text: &str,
+    query: &str,
+    case_sensitive: bool,
+    page_number: usize,
+    limit: usize,
+    matches: &mut Vec<TextMatch>,
+) {
+    let chars: Vec<char> = text.chars().collect();
+    let haystack: Vec<char> = chars
+        .iter()
+        .map(|&c| fold_char(c, case_sensitive))
+        .collect();
+    let needle: Vec<char> = query
+        .chars()
+        .map(|c| fold_char(c, case_sensitive))
+        .collect();
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return;
+    }
+
+    let mut i = 0;
+    while i + needle.len() <= haystack.len() && matches.len() < limit {
+        if haystack[i..i + needle.len()] == needle[..] {
+            let margin = SEARCH_CONTEXT_CHARS.saturating_sub(needle.len()) / 2;
+            let start = i.saturating_sub(margin);
+            let end = (i + needle.len() + margin)
+                .min(chars.len())
+                .min(start + SEARCH_CONTEXT_CHARS);
+            let context: String = chars[start..end]
+                .iter()
+                .map(|&c| if c.is_whitespace() { ' ' } else { c })
+                .collect();
+            matches.push(TextMatch {
+                page_number,
+                context,
+                char_index: i,
+            });
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+}
+
+/// Search every page's text layer for a literal string.
+/// Matching is plain substring search; regex queries are a possible future extension.
+pub fn search_pdf_text(
+    pdf_path: &str,
+    query: &str,
+    case_sensitive: bool,
+    max_matches: Option<usize>,
+    pdfium: &Pdfium,
+    password: Option<&str>,
+) -> Result<SearchResult, String> {
+    if query.is_empty() {
+        return Err("Search query is empty".to_string());
+    }
+    let limit = max_matches.unwrap_or(DEFAULT_MAX_SEARCH_MATCHES).max(1);
+
+    let document = pdfium
+        .load_pdf_from_file(pdf_path, password)
+        .map_err(|e| format!("Cannot open PDF '{}': {}", pdf_path, e))?;
+
+    let mut matches = Vec::new();
+    for (i, page) in document.pages().iter().enumerate() {
+        if matches.len() >= limit {
+            break;
+        }
+        let text = page
+            .text()
+            .map_err(|e| format!("Page {}: cannot read text: {}", i + 1, e))?;
+        find_text_matches(
+            &text.all(),
+            query,
+            case_sensitive,
+            i + 1,
+            limit,
+            &mut matches,
+        );
+    }
+
+    Ok(SearchResult {
+        total_matches: matches.len(),
+        matches,
+    })
+}
+
+// --- Font Listing ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FontInfo {
+    pub name: String,
+    pub subtype: String,
+    pub embedded: bool,
+    pub encoding: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FontListResult {
+    pub fonts: Vec<FontInfo>,
+    pub errors: Vec<String>,
+}
+
+/// Follow a single indirect reference, returning direct objects unchanged.
+pub(crate) fn resolve_object<'a>(doc: &'a LopdfDocument, obj: &'a Object) -> Option<&'a Object> {
+    match obj {
+        Object::Reference(id) => doc.get_object(*id).ok(),
+        other => Some(other),
+    }
+}
+
+fn resolve_dict<'a>(doc: &'a LopdfDocument, obj: &'a Object) -> Option<&'a Dictionary> {
+    resolve_object(doc, obj).and_then(|o| o.as_dict().ok())
+}
+
+fn name_of(doc: &LopdfDocument, dict: &Dictionary, key: &[u8]) -> Option<String> {
+    dict.get(key)
+        .ok()
+        .and_then(|o| resolve_object(doc, o))
+        .and_then(|o| o.as_name().ok())
+        .map(|n| String::from_utf8_lossy(n).to_string())
+}
+
+/// Page Resources, inherited from ancestor Pages nodes when the page has none.
+fn page_resources(doc: &LopdfDocument, page_id: lopdf::ObjectId) -> Option<&Dictionary> {
+    let mut current = doc.get_object(page_id).and_then(|o| o.as_dict()).ok();
+    for _ in 0..64 {
+        let node = current?;
+        if let Some(resources) = node
+            .get(b"Resources")
+            .ok()
+            .and_then(|r| resolve_dict(doc, r))
+        {
+            return Some(resources);
+        }
+        current = node.get(b"Parent").ok().and_then(|p| resolve_dict(doc, p));
+    }
+    None
+}
+
+/// A font counts as embedded when its descriptor carries a font program.
+/// Type0 fonts keep the descriptor on their descendant CIDFont.
+fn describe_font(doc: &LopdfDocument, font: &Dictionary) -> FontInfo {
+    let name = name_of(doc, font, b"BaseFont").unwrap_or_else(|| "(unnamed)".to_string());
+    let mut subtype = name_of(doc, font, b"Subtype").unwrap_or_else(|| "Unknown".to_string());
+
+    let encoding = font
+        .get(b"Encoding")
+        .ok()
+        .and_then(|o| resolve_object(doc, o))
+        .and_then(|o| match o {
+            Object::Name(n) => Some(String::from_utf8_lossy(n).to_string()),
+            Object::Dictionary(d) => {
+                Some(name_of(doc, d, b"BaseEncoding").unwrap_or_else(|| "Custom".to_string()))
+            }
+            _ => None,
+        });
+
+    let descendant = font
+        .get(b"DescendantFonts")
+        .ok()
+        .and_then(|o| resolve_object(doc, o))
+        .and_then(|o| o.as_array().ok())
+        .and_then(|arr| arr.first())
+        .and_then(|o| resolve_dict(doc, o));
+    if let Some(cid_subtype) = descendant.and_then(|d| name_of(doc, d, b"Subtype")) {
+        subtype = format!("{} ({})", subtype, cid_subtype);
+    }
+
+    let descriptor = descendant
+        .unwrap_or(font)
+        .get(b"FontDescriptor")
+        .ok()
+        .and_then(|o| resolve_dict(doc, o));
+    let has_font_file = descriptor
+        .is_some_and(|d| d.has(b"FontFile") || d.has(b"FontFile2") || d.has(b"FontFile3"));
+    // Type3 glyphs are content streams inside the font itself
+    let embedded = has_font_file || subtype == "Type3";
+
+    FontInfo {
+        name,
+        subtype,
+        embedded,
+        encoding,
+    }
+}
+
+/// List the fonts referenced from every page's resources, deduplicated by object.
+pub fn pdf_extract_fonts(pdf_path: &str) -> FontListResult {
+    let mut result = FontListResult {
+        fonts: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result.errors.push(format!("Cannot load PDF: {}", e));
+            return result;
+        }
+    };
+
+    let mut seen_ids: std::collections::HashSet<lopdf::ObjectId> = std::collections::HashSet::new();
+    let mut seen_inline: std::collections::HashSet<(String, String)> =
+        std::collections::HashSet::new();
+
+    for (page_num, page_id) in doc.get_pages() {
+        let Some(fonts) = page_resources(&doc, page_id)
+            .and_then(|r| r.get(b"Font").ok())
+            .and_then(|f| resolve_dict(&doc, f))
+        else {
+            continue;
+        };
+
+        for (key, value) in fonts.iter() {
+            let font = match resolve_dict(&doc, value) {
+                Some(f) => f,
+                None => {
+                    result.errors.push(format!(
+                        "Page {}: font /{} is not a dictionary",
+                        page_num,
+                        String::from_utf8_lossy(key)
+                    ));
+                    continue;
+                }
+            };
+
+            let info = describe_font(&doc, font);
+            let is_new = match value {
+                Object::Reference(id) => seen_ids.insert(*id),
+                _ => seen_inline.insert((info.name.clone(), info.subtype.clone())),
+            };
+            if is_new {
+                result.fonts.push(info);
+            }
+        }
+    }
+
+    result
+}
+
+// --- Integrity Check ---
+
+/// Depth limit for the page tree walk; real documents stay far below this.
+const MAX_INTEGRITY_TREE_DEPTH: usize = 256;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfIntegrityResult {
+    pub valid: bool,
+    pub page_count: usize,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Push every indirect reference found inside `obj` onto `out`.
+fn collect_references(obj: &Object, out: &mut Vec<lopdf::ObjectId>) {
+    match obj {
+        Object::Reference(id) => out.push(*id),
+        Object::Array(items) => items.iter().for_each(|o| collect_references(o, out)),
+        Object::Dictionary(dict) => dict.iter().for_each(|(_, o)| collect_references(o, out)),
+        Object::Stream(stream) => stream
+            .dict
+            .iter()
+            .for_each(|(_, o)| collect_references(o, out)),
+        _ => {}
+    }
+}
+
+/// Visit every object reachable from the trailer and report dangling references.
+fn walk_object_graph(doc: &LopdfDocument, warnings: &mut Vec<String>) {
+    let mut pending = Vec::new();
+    doc.trailer
+        .iter()
+        .for_each(|(_, o)| collect_references(o, &mut pending));
+
+    let mut visited: HashSet<lopdf::ObjectId> = HashSet::new();
+    let mut missing: HashSet<lopdf::ObjectId> = HashSet::new();
+    while let Some(id) = pending.pop() {
+        if !visited.insert(id) {
+            continue;
+        }
+        match doc.objects.get(&id) {
+            Some(obj) => collect_references(obj, &mut pending),
+            None => {
+                if missing.insert(id) {
+                    warnings.push(format!(
+                        "Object {} {} R is referenced but not defined",
+                        id.0, id.1
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Walk the page tree from the catalog, detecting cycles and checking each
+/// Pages node's `/Count` against the leaves actually found under it.
+/// Returns the number of leaf pages reached.
+fn check_page_tree(doc: &LopdfDocument, errors: &mut Vec<String>) -> usize {
+    let root_pages = doc
+        .trailer
+        .get(b"Root")
+        .ok()
+        .and_then(|r| resolve_dict(doc, r))
+        .and_then(|catalog| catalog.get(b"Pages").ok())
+        .and_then(|p| p.as_reference().ok());
+    let Some(root_id) = root_pages else {
+        errors.push("Catalog has no /Pages reference".to_string());
+        return 0;
+    };
+
+    let mut on_path: Vec<lopdf::ObjectId> = Vec::new();
+    let mut seen: HashSet<lopdf::ObjectId> = HashSet::new();
+    count_tree_leaves(doc, root_id, &mut on_path, &mut seen, errors)
+}
+
+fn count_tree_leaves(
+    doc: &LopdfDocument,
+    node_id: lopdf::ObjectId,
+    on_path: &mut Vec<lopdf::ObjectId>,
+    seen: &mut HashSet<lopdf::ObjectId>,
+    errors: &mut Vec<String>,
+) -> usize {
+    if on_path.contains(&node_id) {
+        errors.push(format!(
+            "Circular reference in page tree at object {} {} R",
+            node_id.0, node_id.1
+        ));
+        return 0;
+    }
+    if on_path.len() >= MAX_INTEGRITY_TREE_DEPTH {
+        errors.push("Page tree is too deep".to_string());
+        return 0;
+    }
+    if !seen.insert(node_id) {
+        errors.push(format!(
+            "Page tree node {} {} R appears more than once",
+            node_id.0, node_id.1
+        ));
+        return 0;
+    }
+    let Ok(node) = doc.get_object(node_id).and_then(|o| o.as_dict()) else {
+        errors.push(format!(
+            "Page tree node {} {} R is missing or not a dictionary",
+            node_id.0, node_id.1
+        ));
+        return 0;
+    };
+
+    let is_pages = matches!(name_of(doc, node, b"Type").as_deref(), Some("Pages"));
+    if !is_pages {
+        return 1;
     }
 
-    let pages = dictionary! {
-        "Type" => "Pages",
-        "Kids" => page_ids,
-        "Count" => result.page_count as i64
-    };
-    doc.objects.insert(pages_id, Object::Dictionary(pages));
+    let kids: Vec<lopdf::ObjectId> =
+        match node.get(b"Kids").ok().and_then(|k| resolve_object(doc, k)) {
+            Some(Object::Array(items)) => {
+                items.iter().filter_map(|k| k.as_reference().ok()).collect()
+            }
+            _ => {
+                errors.push(format!(
+                    "Pages node {} {} R has no /Kids array",
+                    node_id.0, node_id.1
+                ));
+                Vec::new()
+            }
+        };
 
-    let catalog_id = doc.add_object(dictionary! {
-        "Type" => "Catalog",
-        "Pages" => pages_id
-    });
-    doc.trailer.set("Root", Object::Reference(catalog_id));
+    on_path.push(node_id);
+    let leaves: usize = kids
+        .iter()
+        .map(|&kid| count_tree_leaves(doc, kid, on_path, seen, errors))
+        .sum();
+    on_path.pop();
 
-    if let Err(e) = doc.save(output_path) {
-        result.errors.push(format!("Cannot save PDF: {}", e));
-        result.page_count = 0;
+    let declared = node
+        .get(b"Count")
+        .ok()
+        .and_then(|c| resolve_object(doc, c))
+        .and_then(|c| c.as_i64().ok());
+    if declared != Some(leaves as i64) {
+        errors.push(format!(
+            "Pages node {} {} R declares /Count {} but contains {} pages",
+            node_id.0,
+            node_id.1,
+            declared.map_or("(missing)".to_string(), |c| c.to_string()),
+            leaves
+        ));
     }
+    leaves
+}
 
-    result
+/// Split a leading decimal number off `bytes`.
+fn leading_number<T: std::str::FromStr>(bytes: &[u8]) -> Option<(T, &[u8])> {
+    let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+    let number = std::str::from_utf8(&bytes[..digits]).ok()?.parse().ok()?;
+    Some((number, &bytes[digits..]))
 }
 
-// --- PDF to Images ---
+/// Parse an `N G obj` header at the start of a line; `obj` may run straight
+/// into a delimiter, as in `1 0 obj<<`.
+fn object_header(line: &[u8]) -> Option<(u32, u16)> {
+    let (id, rest) = leading_number::<u32>(line.trim_ascii_start())?;
+    let trimmed = rest.trim_ascii_start();
+    if trimmed.len() == rest.len() {
+        return None;
+    }
+    let (generation, rest) = leading_number::<u16>(trimmed)?;
+    let trimmed = rest.trim_ascii_start();
+    if trimmed.len() == rest.len() {
+        return None;
+    }
+    let after = trimmed.strip_prefix(b"obj")?;
+    match after.first() {
+        Some(b) if b.is_ascii_alphanumeric() => None,
+        _ => Some((id, generation)),
+    }
+}
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct PdfToImagesResult {
-    pub pdf_path: String,
-    pub output_dir: String,
-    pub exported_count: usize,
-    pub errors: Vec<String>,
+/// Count `N G obj` headers in the raw file, skipping stream data so binary
+/// payloads cannot fake a header. Repeated IDs come from incremental updates or
+/// corruption and are worth flagging either way.
+fn count_object_headers(bytes: &[u8]) -> HashMap<(u32, u16), usize> {
+    let mut counts = HashMap::new();
+    let mut in_stream = false;
+    for line in bytes.split(|&b| b == b'\n' || b == b'\r') {
+        let end = line.trim_ascii_end();
+        if in_stream {
+            if end.ends_with(b"endstream") || line.trim_ascii_start().starts_with(b"endstream") {
+                in_stream = false;
+            }
+            continue;
+        }
+        if let Some(key) = object_header(line) {
+            *counts.entry(key).or_insert(0) += 1;
+        }
+        if end.ends_with(b"stream") && !end.ends_with(b"endstream") {
+            in_stream = true;
+        }
+    }
+    counts
 }
 
-pub fn pdf_to_images(
-    pdf_path: &str,
-    output_dir: &str,
-    pdfium: &Pdfium,
-    format: &str,
-    dpi: u32,
-    output_stem: Option<&str>,
-    app_handle: &tauri::AppHandle,
-) -> PdfToImagesResult {
-    let mut result = PdfToImagesResult {
-        pdf_path: pdf_path.to_string(),
-        output_dir: output_dir.to_string(),
-        exported_count: 0,
+/// Load a PDF and check its structure: every reachable object, the page tree
+/// (cycles and `/Count` consistency) and a few metadata basics.
+pub fn pdf_verify_integrity(pdf_path: &str, password: Option<&str>) -> PdfIntegrityResult {
+    let mut result = PdfIntegrityResult {
+        valid: false,
+        page_count: 0,
         errors: Vec::new(),
+        warnings: Vec::new(),
     };
 
-    let out_dir = PathBuf::from(output_dir);
-    if let Err(e) = ensure_output_dir(&out_dir) {
-        result.errors.push(e);
-        return result;
-    }
-
-    let document = match pdfium.load_pdf_from_file(pdf_path, None) {
-        Ok(d) => d,
+    let bytes = match std::fs::read(pdf_path) {
+        Ok(b) => b,
         Err(e) => {
             result
                 .errors
-                .push(format!("Cannot open PDF '{}': {}", pdf_path, e));
+                .push(format!("Cannot read '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+    let loaded = match password {
+        Some(pw) => LopdfDocument::load_mem_with_password(&bytes, pw),
+        None => LopdfDocument::load_mem(&bytes),
+    };
+    let doc = match loaded {
+        Ok(d) => d,
+        Err(e) => {
+            result.errors.push(format!("Cannot parse PDF: {}", e));
             return result;
         }
     };
 
-    let pdf_stem = output_stem
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| file_stem(pdf_path));
+    let mut duplicates: Vec<((u32, u16), usize)> = count_object_headers(&bytes)
+        .into_iter()
+        .filter(|(_, n)| *n > 1)
+        .collect();
+    duplicates.sort();
+    for ((id, generation), n) in duplicates {
+        result.warnings.push(format!(
+            "Object {} {} is defined {} times",
+            id, generation, n
+        ));
+    }
 
-    // Scale factor: pdfium renders at 72 DPI by default
-    let scale = dpi as f32 / 72.0;
-    let total_pages = document.pages().len() as usize;
+    walk_object_graph(&doc, &mut result.warnings);
+    result.page_count = check_page_tree(&doc, &mut result.errors);
 
-    for (page_index, page) in document.pages().iter().enumerate() {
-        let page_w = page.width().value * scale;
-        let page_h = page.height().value * scale;
+    let pages_found = doc.get_pages().len();
+    if pages_found != result.page_count {
+        result.errors.push(format!(
+            "Page tree walk found {} pages but the document index lists {}",
+            result.page_count, pages_found
+        ));
+    }
 
-        let render_config = PdfRenderConfig::new()
-            .set_target_width(page_w as i32)
-            .set_maximum_height(page_h as i32);
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|i| resolve_dict(&doc, i));
+    match info {
+        Some(info) if info.has(b"ModDate") => {}
+        Some(_) => result
+            .warnings
+            .push("Document info has no ModDate".to_string()),
+        None => result
+            .warnings
+            .push("Document has no Info dictionary".to_string()),
+    }
 
-        match page.render_with_config(&render_config) {
-            Ok(bitmap) => {
-                let dynamic_image = bitmap.as_image();
-                let ext = if format == "jpg" { "jpg" } else { "png" };
-                let out_path =
-                    out_dir.join(format!("{}_page_{}.{}", pdf_stem, page_index + 1, ext));
+    result.valid = result.errors.is_empty();
+    result
+}
 
-                let save_result = if format == "jpg" {
-                    dynamic_image.to_rgb8().save(&out_path)
-                } else {
-                    dynamic_image.save(&out_path)
-                };
-
-                match save_result {
-                    Ok(_) => result.exported_count += 1,
-                    Err(e) => result.errors.push(format!(
-                        "Page {}: failed to save — {}",
-                        page_index + 1,
-                        e
-                    )),
-                }
+// --- Document metadata ---
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PdfDocumentMetadata {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub subject: Option<String>,
+    pub keywords: Option<String>,
+    pub creator: Option<String>,
+    pub producer: Option<String>,
+    /// Raw PDF date string (`D:YYYYMMDDHHmmSSOHH'mm'`).
+    pub creation_date: Option<String>,
+    pub mod_date: Option<String>,
+    /// The same dates as ISO 8601, when they parse.
+    pub creation_date_iso: Option<String>,
+    pub mod_date_iso: Option<String>,
+    pub errors: Vec<String>,
+}
+
+/// Convert a PDF date (`D:YYYYMMDDHHmmSSOHH'mm'`, everything after the year
+/// optional) to ISO 8601. The offset is kept when present, `Z` stays `Z`.
+fn pdf_date_to_iso(raw: &str) -> Option<String> {
+    let s = raw.trim();
+    let s = s.strip_prefix("D:").unwrap_or(s);
+    let digits = s.bytes().take_while(u8::is_ascii_digit).count();
+    if !(4..=14).contains(&digits) || digits % 2 == 1 {
+        return None;
+    }
+    let field = |i: usize, default: u32| -> u32 {
+        s.get(i..i + 2)
+            .filter(|_| i + 2 <= digits)
+            .and_then(|f| f.parse().ok())
+            .unwrap_or(default)
+    };
+    let year = s.get(..4)?;
+    let (month, day) = (field(4, 1), field(6, 1));
+    let (hour, minute, second) = (field(8, 0), field(10, 0), field(12, 0));
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let rest = &s[digits..];
+    let offset = match rest.chars().next() {
+        None => String::new(),
+        Some('Z') => "Z".to_string(),
+        Some(sign @ ('+' | '-')) => {
+            let tz: String = rest[1..].chars().filter(|&c| c != '\'').collect();
+            if !matches!(tz.len(), 2 | 4) || !tz.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
             }
-            Err(e) => {
-                result
-                    .errors
-                    .push(format!("Page {}: render failed — {}", page_index + 1, e));
+            let tz_hour: u32 = tz[..2].parse().ok()?;
+            let tz_minute: u32 = tz.get(2..).and_then(|m| m.parse().ok()).unwrap_or(0);
+            if tz_hour > 23 || tz_minute > 59 {
+                return None;
             }
+            format!("{}{:02}:{:02}", sign, tz_hour, tz_minute)
         }
-        emit_progress_simple(app_handle, page_index + 1, total_pages, pdf_path);
-    }
+        Some(_) => return None,
+    };
+
+    Some(format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}{}",
+        year, month, day, hour, minute, second, offset
+    ))
+}
+
+/// Read the `/Info` dictionary entries of a loaded document.
+fn document_metadata(doc: &LopdfDocument) -> PdfDocumentMetadata {
+    let mut result = PdfDocumentMetadata::default();
+    let Some(info) = doc
+        .trailer
+        .get(b"Info")
+        .ok()
+        .and_then(|i| resolve_dict(doc, i))
+    else {
+        result
+            .errors
+            .push("Document has no Info dictionary".to_string());
+        return result;
+    };
 
+    let text = |key: &[u8]| -> Option<String> {
+        info.get(key)
+            .ok()
+            .and_then(|o| resolve_object(doc, o))
+            .and_then(|o| o.as_str().ok())
+            .map(decode_pdf_text)
+    };
+    result.title = text(b"Title");
+    result.author = text(b"Author");
+    result.subject = text(b"Subject");
+    result.keywords = text(b"Keywords");
+    result.creator = text(b"Creator");
+    result.producer = text(b"Producer");
+    result.creation_date = text(b"CreationDate");
+    result.mod_date = text(b"ModDate");
+    result.creation_date_iso = result.creation_date.as_deref().and_then(pdf_date_to_iso);
+    result.mod_date_iso = result.mod_date.as_deref().and_then(pdf_date_to_iso);
     result
 }
 
+/// Document-level metadata from the PDF `/Info` dictionary.
+pub fn pdf_read_metadata(pdf_path: &str, password: Option<&str>) -> PdfDocumentMetadata {
+    let loaded = match password {
+        Some(pw) => std::fs::read(pdf_path)
+            .map_err(|e| format!("Cannot read '{}': {}", pdf_path, e))
+            .and_then(|bytes| {
+                LopdfDocument::load_mem_with_password(&bytes, pw)
+                    .map_err(|e| format!("Cannot parse PDF: {}", e))
+            }),
+        None => LopdfDocument::load(pdf_path).map_err(|e| format!("Cannot parse PDF: {}", e)),
+    };
+    match loaded {
+        Ok(doc) => document_metadata(&doc),
+        Err(e) => PdfDocumentMetadata {
+            errors: vec![e],
+            ..Default::default()
+        },
+    }
+}
+
 // --- PDF Compression ---
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -270,10 +2087,53 @@ pub struct PdfCompressResult {
     pub output_path: String,
     pub original_size: u64,
     pub compressed_size: u64,
+    /// Bytes saved by re-encoding images (before the size guard).
+    #[serde(default)]
+    pub image_bytes_saved: u64,
+    /// Bytes saved by Flate-compressing unfiltered streams (before the size guard).
+    #[serde(default)]
+    pub stream_bytes_saved: u64,
     pub errors: Vec<String>,
 }
 
+/// Zlib-compress every stream without a `/Filter`, keeping only results that shrink.
+/// Returns (streams compressed, bytes saved).
+fn flate_unfiltered_streams(doc: &mut LopdfDocument) -> (usize, u64) {
+    let mut compressed = 0;
+    let mut saved: u64 = 0;
+    for object in doc.objects.values_mut() {
+        let Object::Stream(stream) = object else {
+            continue;
+        };
+        if !stream.allows_compression || stream.dict.has(b"Filter") || stream.content.is_empty() {
+            continue;
+        }
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::new(6));
+        let deflated = match encoder
+            .write_all(&stream.content)
+            .and_then(|_| encoder.finish())
+        {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        if deflated.len() >= stream.content.len() {
+            continue;
+        }
+
+        saved += (stream.content.len() - deflated.len()) as u64;
+        stream
+            .dict
+            .set("Filter", Object::Name(b"FlateDecode".to_vec()));
+        stream.set_content(deflated);
+        compressed += 1;
+    }
+    (compressed, saved)
+}
+
 /// Compress a PDF by re-encoding embedded images at lower JPEG quality.
+/// With `compress_streams`, unfiltered streams (typically page content) are also
+/// Flate-compressed losslessly.
 ///
 /// Handles two main stream types:
 /// - **DCTDecode** (already JPEG): decode → re-encode at target quality
@@ -284,6 +2144,7 @@ pub struct PdfCompressResult {
 pub fn compress_pdf(
     pdf_path: &str,
     quality: u8,
+    compress_streams: bool,
     output_dir: &str,
     app_handle: &tauri::AppHandle,
 ) -> PdfCompressResult {
@@ -291,6 +2152,8 @@ pub fn compress_pdf(
         output_path: String::new(),
         original_size: 0,
         compressed_size: 0,
+        image_bytes_saved: 0,
+        stream_bytes_saved: 0,
         errors: Vec::new(),
     };
 
@@ -411,7 +2274,8 @@ pub fn compress_pdf(
                 image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg_buf, quality);
             if img.write_with_encoder(encoder).is_ok() {
                 let jpeg_data = jpeg_buf.into_inner();
-                if jpeg_data.len() < original_len {
+                let jpeg_len = jpeg_data.len();
+                if jpeg_len < original_len {
                     let new_stream = lopdf::Stream::new(
                         lopdf::Dictionary::from_iter(vec![
                             ("Type", Object::Name(b"XObject".to_vec())),
@@ -425,6 +2289,7 @@ pub fn compress_pdf(
                         ]),
                         jpeg_data,
                     );
+                    result.image_bytes_saved += (original_len - jpeg_len) as u64;
                     doc.objects.insert(*obj_id, Object::Stream(new_stream));
                     images_replaced += 1;
                 }
@@ -435,11 +2300,19 @@ pub fn compress_pdf(
         }
     }
 
+    let streams_compressed = if compress_streams {
+        let (count, saved) = flate_unfiltered_streams(&mut doc);
+        result.stream_bytes_saved = saved;
+        count
+    } else {
+        0
+    };
+
     let pdf_stem = file_stem(pdf_path);
     let output_path = out_dir.join(format!("{}-compressed.pdf", pdf_stem));
 
-    // If no images were replaced, just copy the original (nothing to gain)
-    if images_replaced == 0 {
+    // If nothing was replaced, just copy the original (nothing to gain)
+    if images_replaced == 0 && streams_compressed == 0 {
         match std::fs::copy(pdf_path, &output_path) {
             Ok(_) => {
                 result.output_path = output_path.to_string_lossy().to_string();
@@ -475,6 +2348,89 @@ pub fn compress_pdf(
     result
 }
 
+/// Losslessly shrink a PDF without touching image pixel data:
+/// drops embedded page thumbnails (`/Thumb`), prunes unreferenced and empty objects,
+/// Flate-compresses every unfiltered stream, and renumbers objects to compact the xref.
+///
+/// Size guard: if the output is larger than the original, copies the original.
+pub fn compress_pdf_lossless(
+    pdf_path: &str,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfCompressResult {
+    let mut result = PdfCompressResult {
+        output_path: String::new(),
+        original_size: 0,
+        compressed_size: 0,
+        image_bytes_saved: 0,
+        stream_bytes_saved: 0,
+        errors: Vec::new(),
+    };
+    const STEPS: usize = 4;
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    result.original_size = std::fs::metadata(pdf_path).map(|m| m.len()).unwrap_or(0);
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result.errors.push(format!("Cannot load PDF: {}", e));
+            return result;
+        }
+    };
+
+    // Thumbnails are optional per spec; viewers regenerate them on demand
+    let page_ids: Vec<lopdf::ObjectId> = doc.get_pages().values().copied().collect();
+    for page_id in page_ids {
+        if let Ok(page) = doc.get_object_mut(page_id).and_then(|o| o.as_dict_mut()) {
+            page.remove(b"Thumb");
+        }
+    }
+    emit_progress_simple(app_handle, 1, STEPS, pdf_path);
+
+    doc.prune_objects();
+    doc.delete_zero_length_streams();
+    emit_progress_simple(app_handle, 2, STEPS, pdf_path);
+
+    doc.compress();
+    emit_progress_simple(app_handle, 3, STEPS, pdf_path);
+
+    doc.renumber_objects();
+
+    let pdf_stem = file_stem(pdf_path);
+    let output_path = out_dir.join(format!("{}-lossless.pdf", pdf_stem));
+
+    match doc.save(&output_path) {
+        Ok(_) => {
+            let compressed_size = std::fs::metadata(&output_path)
+                .map(|m| m.len())
+                .unwrap_or(0);
+
+            // Size guard: if compressed is bigger, replace with original copy
+            if compressed_size >= result.original_size {
+                let _ = std::fs::copy(pdf_path, &output_path);
+                result.compressed_size = result.original_size;
+            } else {
+                result.compressed_size = compressed_size;
+            }
+            result.output_path = output_path.to_string_lossy().to_string();
+        }
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot save compressed PDF: {}", e));
+        }
+    }
+    emit_progress_simple(app_handle, STEPS, STEPS, pdf_path);
+
+    result
+}
+
 // --- PDF Password Protection ---
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -797,6 +2753,273 @@ pub fn unlock_pdf(
 mod tests {
     use super::*;
 
+    #[test]
+    fn batch_pdf_subdirs_are_unique() {
+        let paths = vec![
+            "/a/report.pdf".to_string(),
+            "/b/Report.pdf".to_string(),
+            "/c/notes.pdf".to_string(),
+        ];
+        assert_eq!(batch_pdf_subdirs(&paths), ["report", "Report-2", "notes"]);
+    }
+
+    #[test]
+    fn grid_cells_fill_page_inside_margins() {
+        let cell = grid_cell_size((600.0, 810.0), 2, 3, 50.0, 10.0).unwrap();
+        assert_eq!(cell, (245.0, 230.0));
+        assert_eq!(
+            grid_cell_origin(0, 2, 810.0, cell, 50.0, 10.0),
+            (50.0, 530.0)
+        );
+        assert_eq!(
+            grid_cell_origin(5, 2, 810.0, cell, 50.0, 10.0),
+            (305.0, 50.0)
+        );
+        assert!(grid_cell_size((600.0, 800.0), 2, 2, 300.0, 0.0).is_none());
+    }
+
+    #[test]
+    fn aspect_crop_is_centered() {
+        assert_eq!(centered_aspect_crop(400, 200, 1.0), (100, 0, 200, 200));
+        assert_eq!(centered_aspect_crop(200, 400, 2.0), (0, 150, 200, 100));
+    }
+
+    #[test]
+    fn export_dpi_bounds_are_inclusive() {
+        assert!(validate_export_dpi(36).is_ok());
+        assert!(validate_export_dpi(600).is_ok());
+        assert!(validate_export_dpi(35).is_err());
+        assert!(validate_export_dpi(601).is_err());
+    }
+
+    #[test]
+    fn flate_compresses_only_unfiltered_streams() {
+        let mut doc = LopdfDocument::with_version("1.7");
+        let content = b"0 0 m 100 100 l S\n".repeat(200);
+        let plain_id = doc.add_object(Stream::new(dictionary! {}, content.clone()));
+        let jpeg_id = doc.add_object(Stream::new(
+            dictionary! { "Filter" => "DCTDecode" },
+            vec![0xFF; 64],
+        ));
+
+        let (count, saved) = flate_unfiltered_streams(&mut doc);
+        assert_eq!(count, 1);
+        assert!(saved > 0);
+
+        let plain = doc.get_object(plain_id).unwrap().as_stream().unwrap();
+        assert_eq!(
+            plain.dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"FlateDecode"
+        );
+        assert_eq!(plain.decompressed_content().unwrap(), content);
+        let jpeg = doc.get_object(jpeg_id).unwrap().as_stream().unwrap();
+        assert_eq!(jpeg.content, vec![0xFF; 64]);
+    }
+
+    fn integrity_test_doc(declared_count: i64) -> LopdfDocument {
+        let mut doc = LopdfDocument::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let page_id = doc.add_object(dictionary! {
+            "Type" => "Page",
+            "Parent" => pages_id,
+        });
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => vec![Object::Reference(page_id)],
+                "Count" => declared_count,
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    #[test]
+    fn integrity_page_tree_counts_leaves() {
+        let mut errors = Vec::new();
+        assert_eq!(check_page_tree(&integrity_test_doc(1), &mut errors), 1);
+        assert!(errors.is_empty());
+
+        let mut errors = Vec::new();
+        check_page_tree(&integrity_test_doc(3), &mut errors);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn integrity_detects_page_tree_cycle() {
+        let mut doc = integrity_test_doc(1);
+        let page_id = doc.get_pages()[&1];
+        let root_pages = doc
+            .get_object(page_id)
+            .and_then(|o| o.as_dict())
+            .and_then(|d| d.get(b"Parent"))
+            .and_then(|p| p.as_reference())
+            .unwrap();
+        // Point the root's Kids back at itself
+        if let Ok(Object::Dictionary(root)) = doc.get_object_mut(root_pages) {
+            root.set("Kids", vec![Object::Reference(root_pages)]);
+        }
+        let mut errors = Vec::new();
+        check_page_tree(&doc, &mut errors);
+        assert!(errors.iter().any(|e| e.contains("Circular")));
+    }
+
+    #[test]
+    fn integrity_warns_on_dangling_reference() {
+        let mut doc = integrity_test_doc(1);
+        doc.trailer.set("Info", Object::Reference((999, 0)));
+        let mut warnings = Vec::new();
+        walk_object_graph(&doc, &mut warnings);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("999 0 R"));
+    }
+
+    #[test]
+    fn object_headers_count_duplicates() {
+        let counts =
+            count_object_headers(b"1 0 obj\n<<>>\nendobj\n1 0 obj\n<<>>\nendobj\n2 0 obj\n");
+        assert_eq!(counts[&(1, 0)], 2);
+        assert_eq!(counts[&(2, 0)], 1);
+    }
+
+    #[test]
+    fn object_headers_handle_tight_syntax_and_skip_streams() {
+        let counts = count_object_headers(
+            b"1 0 obj<</Length 9>>stream\n3 0 obj x\nendstream\nendobj\n\
+              2 0 obj<<>>endobj\n3 0 obj\n<<>>\nendobj\n4 0 objection\n",
+        );
+        assert_eq!(counts[&(1, 0)], 1);
+        assert_eq!(counts[&(2, 0)], 1);
+        assert_eq!(counts[&(3, 0)], 1);
+        assert!(!counts.contains_key(&(4, 0)));
+    }
+
+    #[test]
+    fn tesseract_tsv_keeps_word_rows() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t10\t20\t60\t15\t96.5\tHello\n\
+                   5\t1\t1\t1\t1\t2\t80\t20\t70\t15\t91\tworld\n\
+                   5\t1\t1\t1\t1\t3\t160\t20\t5\t15\t95\t \n";
+        let words = parse_tesseract_tsv(tsv);
+        assert_eq!(words.len(), 2);
+        assert_eq!(
+            words[0],
+            OcrWord {
+                left: 10.0,
+                top: 20.0,
+                width: 60.0,
+                height: 15.0,
+                text: "Hello".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn ocr_text_ops_flip_y_and_hide_text() {
+        let words = vec![OcrWord {
+            left: 10.0,
+            top: 20.0,
+            width: 40.0,
+            height: 10.0,
+            text: "abcd".to_string(),
+        }];
+        let ops = build_ocr_text_ops(&words, 100.0);
+        let td = ops.iter().find(|op| op.operator == "Td").unwrap();
+        assert_eq!(td.operands[1].as_float().unwrap(), 70.0);
+        let tr = ops.iter().find(|op| op.operator == "Tr").unwrap();
+        assert_eq!(tr.operands[0].as_i64().unwrap(), 3);
+        let tz = ops.iter().find(|op| op.operator == "Tz").unwrap();
+        assert_eq!(tz.operands[0].as_float().unwrap(), 200.0);
+    }
+
+    #[test]
+    fn latin1_bytes_replace_unsupported_chars() {
+        assert_eq!(pdf_latin1_bytes("café"), vec![b'c', b'a', b'f', 0xE9]);
+        assert_eq!(pdf_latin1_bytes("日"), vec![b'?']);
+    }
+
+    #[test]
+    fn text_search_respects_case_and_limit() {
+        let mut matches = Vec::new();
+        find_text_matches("Rust and rust and RUST", "rust", false, 2, 10, &mut matches);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[1].char_index, 9);
+        assert_eq!(matches[0].page_number, 2);
+
+        let mut matches = Vec::new();
+        find_text_matches("Rust and rust and RUST", "rust", true, 1, 10, &mut matches);
+        assert_eq!(matches.len(), 1);
+
+        let mut matches = Vec::new();
+        find_text_matches("aaaa", "a", true, 1, 2, &mut matches);
+        assert_eq!(matches.len(), 2);
+    }
+
+    #[test]
+    fn text_search_context_is_bounded() {
+        let text = format!("{}needle{}", "x".repeat(200), "y\n".repeat(100));
+        let mut matches = Vec::new();
+        find_text_matches(&text, "needle", true, 1, 10, &mut matches);
+        assert_eq!(matches[0].char_index, 200);
+        assert!(matches[0].context.chars().count() <= SEARCH_CONTEXT_CHARS);
+        assert!(matches[0].context.contains("needle"));
+        assert!(!matches[0].context.contains('\n'));
+    }
+
+    // --- pdf_read_metadata ---
+
+    #[test]
+    fn pdf_date_converts_to_iso() {
+        assert_eq!(
+            pdf_date_to_iso("D:20240315143000+02'00'").as_deref(),
+            Some("2024-03-15T14:30:00+02:00")
+        );
+        assert_eq!(
+            pdf_date_to_iso("D:20240315143000Z").as_deref(),
+            Some("2024-03-15T14:30:00Z")
+        );
+        assert_eq!(
+            pdf_date_to_iso("D:2024").as_deref(),
+            Some("2024-01-01T00:00:00")
+        );
+        assert_eq!(pdf_date_to_iso("D:20241315"), None);
+        assert_eq!(pdf_date_to_iso("yesterday"), None);
+    }
+
+    #[test]
+    fn document_metadata_reads_info_dictionary() {
+        let mut doc = LopdfDocument::with_version("1.7");
+        let info_id = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Report"),
+            "Author" => Object::String(
+                vec![0xFE, 0xFF, 0x00, 0x41, 0x00, 0xE9],
+                StringFormat::Hexadecimal
+            ),
+            "CreationDate" => Object::string_literal("D:20240102030405Z")
+        });
+        doc.trailer.set("Info", info_id);
+
+        let meta = document_metadata(&doc);
+        assert_eq!(meta.title.as_deref(), Some("Report"));
+        assert_eq!(meta.author.as_deref(), Some("Aé"));
+        assert_eq!(meta.subject, None);
+        assert_eq!(meta.creation_date.as_deref(), Some("D:20240102030405Z"));
+        assert_eq!(
+            meta.creation_date_iso.as_deref(),
+            Some("2024-01-02T03:04:05Z")
+        );
+        assert!(meta.errors.is_empty());
+
+        let empty = document_metadata(&LopdfDocument::with_version("1.7"));
+        assert_eq!(empty.errors.len(), 1);
+    }
+
     // --- pad_password ---
 
     #[test]
@@ -826,6 +3049,20 @@ mod tests {
         assert_eq!(padded, [0xCDu8; 32]);
     }
 
+    // --- srgb_icc_profile ---
+
+    #[test]
+    fn srgb_icc_profile_header_is_consistent() {
+        let profile = srgb_icc_profile();
+        let declared = u32::from_be_bytes([profile[0], profile[1], profile[2], profile[3]]);
+        assert_eq!(declared as usize, profile.len());
+        assert_eq!(&profile[16..20], b"RGB ");
+        assert_eq!(&profile[36..40], b"acsp");
+        let tag_count =
+            u32::from_be_bytes([profile[128], profile[129], profile[130], profile[131]]);
+        assert_eq!(tag_count, 9);
+    }
+
     // --- rc4_encrypt ---
 
     #[test]
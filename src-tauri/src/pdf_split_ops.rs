@@ -1,8 +1,11 @@
-use lopdf::{dictionary, Document as LopdfDocument, Object, ObjectId};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document as LopdfDocument, Object, ObjectId, Stream};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
+use crate::pdf_builder_ops::get_page_dimensions;
+use crate::pdf_ops::{pdf_latin1_bytes, resolve_object};
 use crate::progress::emit_progress_simple;
 use crate::utils::{ensure_output_dir, file_stem};
 
@@ -131,6 +134,71 @@ fn remap_object(
     }
 }
 
+/// Copy pages `start..=end` (1-indexed) of `source` into a fresh document.
+fn extract_page_range(
+    source: &LopdfDocument,
+    source_pages: &BTreeMap<u32, ObjectId>,
+    start: u32,
+    end: u32,
+) -> LopdfDocument {
+    extract_pages(source, source_pages, start..=end)
+}
+
+/// Copy the given 1-indexed pages of `source`, in order, into a fresh document.
+/// Unknown page numbers are skipped, so the result may have no pages at all.
+/// A page listed again gets its own page dictionary sharing the first copy's content,
+/// minus `/Annots`: widgets and links can only belong to one page.
+fn extract_pages(
+    source: &LopdfDocument,
+    source_pages: &BTreeMap<u32, ObjectId>,
+    page_numbers: impl IntoIterator<Item = u32>,
+) -> LopdfDocument {
+    let mut new_doc = LopdfDocument::with_version("1.7");
+    let pages_id = new_doc.new_object_id();
+    let mut page_refs: Vec<Object> = Vec::new();
+    let mut id_map: HashMap<ObjectId, ObjectId> = HashMap::new();
+
+    for page_num in page_numbers {
+        if let Some(&page_obj_id) = source_pages.get(&page_num) {
+            let repeated = id_map
+                .get(&page_obj_id)
+                .and_then(|copy_id| new_doc.get_object(*copy_id).ok())
+                .cloned();
+            let new_page_id = match repeated {
+                Some(mut page) => {
+                    if let Object::Dictionary(ref mut dict) = page {
+                        dict.remove(b"Annots");
+                    }
+                    new_doc.add_object(page)
+                }
+                None => copy_object_deep(source, &mut new_doc, page_obj_id, &mut id_map),
+            };
+
+            // Point the copied page's Parent to our new Pages node
+            if let Some(Object::Dictionary(ref mut dict)) = new_doc.objects.get_mut(&new_page_id) {
+                dict.set("Parent", Object::Reference(pages_id));
+            }
+
+            page_refs.push(Object::Reference(new_page_id));
+        }
+    }
+
+    let page_count = page_refs.len() as i64;
+    let pages = dictionary! {
+        "Type" => "Pages",
+        "Kids" => page_refs,
+        "Count" => page_count
+    };
+    new_doc.objects.insert(pages_id, Object::Dictionary(pages));
+
+    let catalog_id = new_doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    });
+    new_doc.trailer.set("Root", Object::Reference(catalog_id));
+    new_doc
+}
+
 pub fn split_pdf(
     pdf_path: &str,
     ranges_str: &str,
@@ -177,40 +245,7 @@ pub fn split_pdf(
     let total_ranges = ranges.len();
 
     for (idx, (start, end)) in ranges.iter().enumerate() {
-        let mut new_doc = LopdfDocument::with_version("1.7");
-        let pages_id = new_doc.new_object_id();
-        let mut page_refs: Vec<Object> = Vec::new();
-        let mut id_map: HashMap<ObjectId, ObjectId> = HashMap::new();
-
-        for page_num in *start..=*end {
-            if let Some(&page_obj_id) = source_pages.get(&page_num) {
-                let new_page_id =
-                    copy_object_deep(&source_doc, &mut new_doc, page_obj_id, &mut id_map);
-
-                // Point the copied page's Parent to our new Pages node
-                if let Some(Object::Dictionary(ref mut dict)) =
-                    new_doc.objects.get_mut(&new_page_id)
-                {
-                    dict.set("Parent", Object::Reference(pages_id));
-                }
-
-                page_refs.push(Object::Reference(new_page_id));
-            }
-        }
-
-        let page_count = page_refs.len() as i64;
-        let pages = dictionary! {
-            "Type" => "Pages",
-            "Kids" => page_refs,
-            "Count" => page_count
-        };
-        new_doc.objects.insert(pages_id, Object::Dictionary(pages));
-
-        let catalog_id = new_doc.add_object(dictionary! {
-            "Type" => "Catalog",
-            "Pages" => pages_id
-        });
-        new_doc.trailer.set("Root", Object::Reference(catalog_id));
+        let mut new_doc = extract_page_range(&source_doc, &source_pages, *start, *end);
 
         let output_filename = if *start == *end {
             format!("{}_page_{}.pdf", pdf_stem, start)
@@ -237,9 +272,1610 @@ pub fn split_pdf(
     result
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+// --- Split by bookmarks ---
+
+/// Guard against malformed outline sibling chains and name trees.
+const MAX_OUTLINE_ITEMS: usize = 10_000;
+const MAX_TITLE_CHARS: usize = 100;
+
+/// Decode a PDF text string: UTF-16BE with BOM, otherwise treated as Latin-1.
+pub(crate) fn decode_pdf_text(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16
+            .chunks_exact(2)
+            .map(|c| u16::from_be_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        bytes.iter().map(|&b| b as char).collect()
+    }
+}
+
+/// Turn a bookmark title into a safe file stem; empty results fall back to `fallback`.
+fn sanitize_bookmark_title(title: &str, fallback: &str) -> String {
+    let cleaned: String = title
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .take(MAX_TITLE_CHARS)
+        .collect();
+    let cleaned = cleaned.replace("..", "_");
+    let cleaned = cleaned.trim().trim_matches('.').trim();
+    if cleaned.is_empty() {
+        fallback.to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Look up a named destination in the catalog's `/Dests` dict or `/Names /Dests` tree.
+fn lookup_named_dest<'a>(doc: &'a LopdfDocument, name: &[u8]) -> Option<&'a Object> {
+    let catalog = doc.catalog().ok()?;
+    if let Some(dests) = catalog
+        .get(b"Dests")
+        .ok()
+        .and_then(|d| resolve_object(doc, d)?.as_dict().ok())
+    {
+        if let Ok(dest) = dests.get(name) {
+            return resolve_object(doc, dest);
+        }
+    }
+
+    let root = catalog
+        .get(b"Names")
+        .ok()
+        .and_then(|n| resolve_object(doc, n)?.as_dict().ok())
+        .and_then(|names| names.get(b"Dests").ok())
+        .and_then(|d| resolve_object(doc, d))?;
+    let mut pending = vec![root];
+    let mut visited = 0;
+    while let Some(node) = pending.pop() {
+        visited += 1;
+        if visited > MAX_OUTLINE_ITEMS {
+            break;
+        }
+        let Ok(node) = node.as_dict() else { continue };
+        if let Some(Object::Array(pairs)) =
+            node.get(b"Names").ok().and_then(|n| resolve_object(doc, n))
+        {
+            for pair in pairs.chunks_exact(2) {
+                if resolve_object(doc, &pair[0]).and_then(|k| k.as_str().ok()) == Some(name) {
+                    return resolve_object(doc, &pair[1]);
+                }
+            }
+        }
+        if let Some(Object::Array(kids)) =
+            node.get(b"Kids").ok().and_then(|k| resolve_object(doc, k))
+        {
+            pending.extend(kids.iter().filter_map(|k| resolve_object(doc, k)));
+        }
+    }
+    None
+}
+
+/// Page object referenced by an outline item's `/Dest` or GoTo `/A` action.
+fn outline_target_page(doc: &LopdfDocument, item: &lopdf::Dictionary) -> Option<ObjectId> {
+    let dest = match item.get(b"Dest") {
+        Ok(d) => resolve_object(doc, d)?,
+        Err(_) => {
+            let action = item
+                .get(b"A")
+                .ok()
+                .and_then(|a| resolve_object(doc, a))?
+                .as_dict()
+                .ok()?;
+            if action.get(b"S").and_then(|s| s.as_name()).ok() != Some(b"GoTo".as_slice()) {
+                return None;
+            }
+            resolve_object(doc, action.get(b"D").ok()?)?
+        }
+    };
+    let dest = match dest {
+        Object::Name(name) | Object::String(name, _) => lookup_named_dest(doc, name)?,
+        other => other,
+    };
+    // Named destinations may wrap the array in a dictionary under /D
+    let dest = match dest {
+        Object::Dictionary(d) => resolve_object(doc, d.get(b"D").ok()?)?,
+        other => other,
+    };
+    dest.as_array().ok()?.first()?.as_reference().ok()
+}
+
+/// Top-level outline entries as (title, 1-indexed page), in outline order.
+fn top_level_bookmarks(doc: &LopdfDocument) -> Vec<(String, u32)> {
+    let page_numbers: HashMap<ObjectId, u32> =
+        doc.get_pages().into_iter().map(|(n, id)| (id, n)).collect();
+    let first = doc
+        .catalog()
+        .ok()
+        .and_then(|c| c.get(b"Outlines").ok())
+        .and_then(|o| resolve_object(doc, o)?.as_dict().ok())
+        .and_then(|o| o.get(b"First").ok())
+        .and_then(|f| f.as_reference().ok());
+
+    let mut bookmarks = Vec::new();
+    let mut seen: HashSet<ObjectId> = HashSet::new();
+    let mut current = first;
+    while let Some(item_id) = current {
+        if !seen.insert(item_id) || seen.len() > MAX_OUTLINE_ITEMS {
+            break;
+        }
+        let Ok(item) = doc.get_object(item_id).and_then(|o| o.as_dict()) else {
+            break;
+        };
+        let title = item
+            .get(b"Title")
+            .ok()
+            .and_then(|t| resolve_object(doc, t)?.as_str().ok())
+            .map(decode_pdf_text)
+            .unwrap_or_default();
+        if let Some(page) = outline_target_page(doc, item).and_then(|id| page_numbers.get(&id)) {
+            bookmarks.push((title, *page));
+        }
+        current = item.get(b"Next").and_then(|n| n.as_reference()).ok();
+    }
+    bookmarks
+}
+
+/// Page ranges for each chapter: leading pages before the first bookmark become a
+/// "front-matter" part; bookmarks sharing a page keep the first title.
+fn bookmark_ranges(bookmarks: &[(String, u32)], total_pages: u32) -> Vec<(String, u32, u32)> {
+    let mut starts: Vec<(String, u32)> = Vec::new();
+    let mut sorted: Vec<&(String, u32)> = bookmarks
+        .iter()
+        .filter(|(_, page)| (1..=total_pages).contains(page))
+        .collect();
+    sorted.sort_by_key(|(_, page)| *page);
+    for (title, page) in sorted {
+        if starts.last().map(|(_, p)| *p) != Some(*page) {
+            starts.push((title.clone(), *page));
+        }
+    }
+
+    let mut ranges = Vec::new();
+    if let Some((_, first_page)) = starts.first() {
+        if *first_page > 1 {
+            ranges.push(("front-matter".to_string(), 1, first_page - 1));
+        }
+    }
+    for (i, (title, page)) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).map_or(total_pages, |(_, next)| next - 1);
+        ranges.push((title.clone(), *page, end));
+    }
+    ranges
+}
+
+/// Split a PDF into one file per top-level bookmark (chapter), named after its title.
+pub fn pdf_split_by_bookmarks(
+    pdf_path: &str,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfSplitResult {
+    let mut result = PdfSplitResult {
+        output_files: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let source_doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let source_pages = source_doc.get_pages();
+    let total_pages = source_pages.len() as u32;
+    let ranges = bookmark_ranges(&top_level_bookmarks(&source_doc), total_pages);
+    if ranges.is_empty() {
+        result
+            .errors
+            .push("PDF has no top-level bookmarks pointing to pages".to_string());
+        return result;
+    }
+
+    let total_ranges = ranges.len();
+    let mut used_names: HashSet<String> = HashSet::new();
+    for (idx, (title, start, end)) in ranges.iter().enumerate() {
+        let base = sanitize_bookmark_title(title, &format!("chapter-{}", idx + 1));
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !used_names.insert(name.to_lowercase()) {
+            name = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        let mut new_doc = extract_page_range(&source_doc, &source_pages, *start, *end);
+        let output_path = out_dir.join(format!("{}.pdf", name));
+        match save_split(&mut new_doc, &output_path) {
+            Ok(path) => result.output_files.push(path),
+            Err(e) => result.errors.push(format!("'{}': {}", title, e)),
+        }
+        emit_progress_simple(app_handle, idx + 1, total_ranges, pdf_path);
+    }
+
+    result
+}
+
+fn save_split(doc: &mut LopdfDocument, output_path: &Path) -> Result<String, String> {
+    doc.save(output_path)
+        .map_err(|e| format!("failed to save — {}", e))?;
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+// --- Odd / even split ---
+
+/// Write `{stem}-odd.pdf` and `{stem}-even.pdf` for manual duplex printing.
+/// The even file is written even when it ends up with no pages, so both
+/// outputs always exist side by side.
+pub fn split_odd_even(
+    pdf_path: &str,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfSplitResult {
+    let mut result = PdfSplitResult {
+        output_files: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let source_doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+    let source_pages = source_doc.get_pages();
+    let total_pages = source_pages.len() as u32;
+    if total_pages == 0 {
+        result.errors.push("PDF has no pages".to_string());
+        return result;
+    }
+
+    let stem = file_stem(pdf_path);
+    for (idx, (label, first)) in [("odd", 1), ("even", 2)].into_iter().enumerate() {
+        let pages = (first..=total_pages).step_by(2);
+        let mut new_doc = extract_pages(&source_doc, &source_pages, pages);
+        let output_path = out_dir.join(format!("{}-{}.pdf", stem, label));
+        match save_split(&mut new_doc, &output_path) {
+            Ok(path) => result.output_files.push(path),
+            Err(e) => result.errors.push(format!("{} pages: {}", label, e)),
+        }
+        emit_progress_simple(app_handle, idx + 1, 2, pdf_path);
+    }
+
+    result
+}
+
+// --- Page editing ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfPageEditResult {
+    pub output_path: String,
+    pub page_count: usize,
+    pub errors: Vec<String>,
+}
+
+/// Guard against malformed page trees whose Parent links form a cycle.
+const MAX_PAGE_TREE_DEPTH: usize = 64;
+
+/// Unlink a page from its parent's Kids array and decrement Count on every ancestor.
+fn detach_page(doc: &mut LopdfDocument, page_id: ObjectId) -> Result<(), String> {
+    let parent_id = doc
+        .get_object(page_id)
+        .and_then(|o| o.as_dict())
+        .and_then(|d| d.get(b"Parent"))
+        .and_then(|p| p.as_reference())
+        .map_err(|e| format!("Page has no parent node: {}", e))?;
+
+    let parent = doc
+        .get_object_mut(parent_id)
+        .and_then(|o| o.as_dict_mut())
+        .map_err(|e| format!("Invalid page tree: {}", e))?;
+    let kids = parent
+        .get_mut(b"Kids")
+        .and_then(|k| k.as_array_mut())
+        .map_err(|e| format!("Invalid page tree: {}", e))?;
+    kids.retain(|kid| kid.as_reference().ok() != Some(page_id));
+
+    let mut current = Some(parent_id);
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        let Some(node_id) = current else { break };
+        let node = match doc.get_object_mut(node_id).and_then(|o| o.as_dict_mut()) {
+            Ok(n) => n,
+            Err(_) => break,
+        };
+        if let Ok(count) = node.get(b"Count").and_then(|c| c.as_i64()) {
+            node.set("Count", (count - 1).max(0));
+        }
+        current = node.get(b"Parent").and_then(|p| p.as_reference()).ok();
+    }
+
+    Ok(())
+}
+
+/// Remove the given 1-based pages and save to `{stem}-trimmed.pdf`.
+/// Out-of-range page numbers are reported individually; the rest are still removed.
+pub fn delete_pdf_pages(
+    pdf_path: &str,
+    page_numbers: &[usize],
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfPageEditResult {
+    let mut result = PdfPageEditResult {
+        output_path: String::new(),
+        page_count: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let pages = doc.get_pages();
+    let total_pages = pages.len();
+
+    let mut to_delete: Vec<ObjectId> = Vec::new();
+    for &page_num in page_numbers.iter().collect::<BTreeSet<_>>() {
+        match pages.get(&(page_num as u32)) {
+            Some(&page_id) if page_num > 0 => to_delete.push(page_id),
+            _ => result.errors.push(format!(
+                "Page {} is out of range (1-{})",
+                page_num, total_pages
+            )),
+        }
+    }
+
+    if to_delete.len() >= total_pages {
+        result
+            .errors
+            .push("Cannot delete every page of the document".to_string());
+        return result;
+    }
+
+    let total = to_delete.len();
+    for (idx, page_id) in to_delete.into_iter().enumerate() {
+        if let Err(e) = detach_page(&mut doc, page_id) {
+            result.errors.push(e);
+        }
+        emit_progress_simple(app_handle, idx + 1, total, pdf_path);
+    }
+
+    // Drop the removed pages and any resources only they referenced
+    doc.prune_objects();
+    result.page_count = doc.get_pages().len();
+
+    let output_path = out_dir.join(format!("{}-trimmed.pdf", file_stem(pdf_path)));
+    match doc.save(&output_path) {
+        Ok(_) => result.output_path = output_path.to_string_lossy().to_string(),
+        Err(e) => result.errors.push(format!("Cannot save PDF: {}", e)),
+    }
+
+    result
+}
+
+/// Page attributes a page may inherit from its ancestors in the page tree.
+const INHERITABLE_PAGE_KEYS: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Look up a page attribute, walking up the Parent chain for inherited values.
+fn inherited_attribute(doc: &LopdfDocument, page_id: ObjectId, key: &[u8]) -> Option<Object> {
+    let mut current = Some(page_id);
+    for _ in 0..MAX_PAGE_TREE_DEPTH {
+        let node = doc.get_object(current?).and_then(|o| o.as_dict()).ok()?;
+        if let Ok(value) = node.get(key) {
+            return Some(value.clone());
+        }
+        current = node.get(b"Parent").and_then(|p| p.as_reference()).ok();
+    }
+    None
+}
+
+/// Replace the page tree with a single flat Pages node holding `page_ids` in order.
+/// Inherited attributes are copied onto each page first so nothing is lost when
+/// intermediate nodes disappear; a page listed twice is cloned (sharing its content).
+/// Returns the new page count.
+fn rebuild_page_tree(doc: &mut LopdfDocument, page_ids: &[ObjectId]) -> Result<usize, String> {
+    let root_pages_id = doc
+        .catalog()
+        .and_then(|c| c.get(b"Pages"))
+        .and_then(|p| p.as_reference())
+        .map_err(|e| format!("Invalid page tree: {}", e))?;
+
+    let mut kids: Vec<Object> = Vec::with_capacity(page_ids.len());
+    let mut seen: BTreeSet<ObjectId> = BTreeSet::new();
+
+    for &page_id in page_ids {
+        let mut page = doc
+            .get_object(page_id)
+            .and_then(|o| o.as_dict())
+            .map_err(|e| format!("Invalid page object: {}", e))?
+            .clone();
+        for key in INHERITABLE_PAGE_KEYS {
+            if page.get(key).is_err() {
+                if let Some(value) = inherited_attribute(doc, page_id, key) {
+                    page.set(key.to_vec(), value);
+                }
+            }
+        }
+        page.set("Parent", Object::Reference(root_pages_id));
+
+        let target_id = if seen.insert(page_id) {
+            doc.objects.insert(page_id, Object::Dictionary(page));
+            page_id
+        } else {
+            doc.add_object(page)
+        };
+        kids.push(Object::Reference(target_id));
+    }
+
+    let count = kids.len();
+    doc.objects.insert(
+        root_pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => count as i64
+        }),
+    );
+    // Drop intermediate nodes and pages that are no longer listed
+    doc.prune_objects();
+
+    Ok(count)
+}
+
+/// Insert blank pages. Each entry of `after_pages` adds one blank page after that
+/// 1-based page (0 = before page 1). `page_format` is "a4", "letter", or "match"
+/// to copy the MediaBox of the neighbouring page. Saves to `{stem}-with-blanks.pdf`.
+pub fn insert_blank_pdf_pages(
+    pdf_path: &str,
+    after_pages: &[usize],
+    page_format: &str,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfPageEditResult {
+    let mut result = PdfPageEditResult {
+        output_path: String::new(),
+        page_count: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let original_ids: Vec<ObjectId> = doc.get_pages().values().copied().collect();
+    let total_pages = original_ids.len();
+    if total_pages == 0 {
+        result.errors.push("PDF has no pages".to_string());
+        return result;
+    }
+
+    // blanks_after[i] = number of blank pages to insert after original page i (index 0 = before page 1)
+    let mut blanks_after = vec![0usize; total_pages + 1];
+    for &after in after_pages {
+        if after > total_pages {
+            result.errors.push(format!(
+                "Position {} is out of range (0-{})",
+                after, total_pages
+            ));
+            continue;
+        }
+        blanks_after[after] += 1;
+    }
+
+    let content_id = doc.add_object(Stream::new(dictionary! {}, Vec::new()));
+    let mut new_order: Vec<ObjectId> = Vec::with_capacity(total_pages + after_pages.len());
+
+    for (position, &count) in blanks_after.iter().enumerate() {
+        if position > 0 {
+            new_order.push(original_ids[position - 1]);
+        }
+        if count == 0 {
+            continue;
+        }
+
+        // "match" copies the preceding page, or the first page when inserting at the front
+        let media_box = if page_format == "match" {
+            let neighbour = original_ids[position.saturating_sub(1)];
+            inherited_attribute(&doc, neighbour, b"MediaBox")
+                .unwrap_or_else(|| media_box_object(get_page_dimensions("a4", "portrait")))
+        } else {
+            media_box_object(get_page_dimensions(page_format, "portrait"))
+        };
+
+        for _ in 0..count {
+            let blank_id = doc.add_object(dictionary! {
+                "Type" => "Page",
+                "MediaBox" => media_box.clone(),
+                "Resources" => dictionary! {},
+                "Contents" => content_id
+            });
+            new_order.push(blank_id);
+        }
+        emit_progress_simple(app_handle, position + 1, total_pages + 1, pdf_path);
+    }
+
+    match rebuild_page_tree(&mut doc, &new_order) {
+        Ok(count) => result.page_count = count,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    }
+
+    let output_path = out_dir.join(format!("{}-with-blanks.pdf", file_stem(pdf_path)));
+    match doc.save(&output_path) {
+        Ok(_) => result.output_path = output_path.to_string_lossy().to_string(),
+        Err(e) => result.errors.push(format!("Cannot save PDF: {}", e)),
+    }
+
+    result
+}
+
+/// Rewrite the page order: `new_order` lists 1-based source pages. Repeated entries
+/// clone a page and omitted pages are dropped. Saves to `{stem}-reordered.pdf`.
+pub fn pdf_page_reorder(
+    pdf_path: &str,
+    new_order: &[usize],
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfPageEditResult {
+    let mut result = PdfPageEditResult {
+        output_path: String::new(),
+        page_count: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let pages = doc.get_pages();
+    let total_pages = pages.len();
+
+    let mut page_ids: Vec<ObjectId> = Vec::with_capacity(new_order.len());
+    for &page_num in new_order {
+        match pages.get(&(page_num as u32)) {
+            Some(&page_id) if page_num > 0 => page_ids.push(page_id),
+            _ => result.errors.push(format!(
+                "Page {} is out of range (1-{})",
+                page_num, total_pages
+            )),
+        }
+    }
+
+    if page_ids.is_empty() {
+        result
+            .errors
+            .push("New page order contains no valid pages".to_string());
+        return result;
+    }
+    emit_progress_simple(app_handle, 1, 2, pdf_path);
+
+    match rebuild_page_tree(&mut doc, &page_ids) {
+        Ok(count) => result.page_count = count,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    }
+
+    let output_path = out_dir.join(format!("{}-reordered.pdf", file_stem(pdf_path)));
+    match doc.save(&output_path) {
+        Ok(_) => result.output_path = output_path.to_string_lossy().to_string(),
+        Err(e) => result.errors.push(format!("Cannot save PDF: {}", e)),
+    }
+    emit_progress_simple(app_handle, 2, 2, pdf_path);
+
+    result
+}
+
+// --- Page labels ---
+
+/// One `/PageLabels` range, starting at a 1-based page and running until the next range.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PageLabelRange {
+    pub start_page: usize,
+    /// "D", "r", "R", "a", "A", or "" for prefix-only labels.
+    pub style: String,
+    pub prefix: Option<String>,
+    pub start_value: u32,
+}
+
+/// PDF text string: plain bytes for ASCII, UTF-16BE with a BOM otherwise.
+fn pdf_text_string(text: &str) -> Object {
+    if text.is_ascii() {
+        return Object::string_literal(text);
+    }
+    let mut bytes = vec![0xFE, 0xFF];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+    Object::String(bytes, lopdf::StringFormat::Hexadecimal)
+}
+
+/// Build the `/PageLabels` number tree. Ranges are sorted by start page; when none
+/// starts at page 1, a plain decimal range is added there since the tree must cover it.
+fn build_page_labels(ranges: &[PageLabelRange], total_pages: usize) -> Result<Object, String> {
+    if ranges.is_empty() {
+        return Err("No page label ranges given".to_string());
+    }
+    let mut sorted: Vec<&PageLabelRange> = ranges.iter().collect();
+    sorted.sort_by_key(|r| r.start_page);
+
+    let mut nums: Vec<Object> = Vec::with_capacity(sorted.len() * 2 + 2);
+    if sorted[0].start_page != 1 {
+        nums.push(Object::Integer(0));
+        nums.push(Object::Dictionary(dictionary! { "S" => "D" }));
+    }
+    let mut previous = 0;
+    for range in sorted {
+        if range.start_page == 0 || range.start_page > total_pages {
+            return Err(format!(
+                "Label start page {} is out of range (1-{})",
+                range.start_page, total_pages
+            ));
+        }
+        if range.start_page == previous {
+            return Err(format!(
+                "Two label ranges start on page {}",
+                range.start_page
+            ));
+        }
+        previous = range.start_page;
+
+        let mut label = lopdf::Dictionary::new();
+        match range.style.as_str() {
+            "" => {}
+            "D" | "r" | "R" | "a" | "A" => {
+                label.set("S", Object::Name(range.style.as_bytes().to_vec()))
+            }
+            other => return Err(format!("Unknown page label style: {}", other)),
+        }
+        if let Some(prefix) = range.prefix.as_deref().filter(|p| !p.is_empty()) {
+            label.set("P", pdf_text_string(prefix));
+        }
+        if range.start_value > 1 {
+            label.set("St", range.start_value as i64);
+        }
+        nums.push(Object::Integer(range.start_page as i64 - 1));
+        nums.push(Object::Dictionary(label));
+    }
+
+    Ok(Object::Dictionary(dictionary! { "Nums" => nums }))
+}
+
+/// Set the catalog's `/PageLabels` (e.g. Roman front matter, Arabic body) and save to
+/// `{stem}-labeled.pdf`. Existing labels are replaced.
+pub fn pdf_add_page_labels(
+    pdf_path: &str,
+    labels: &[PageLabelRange],
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfPageEditResult {
+    let mut result = PdfPageEditResult {
+        output_path: String::new(),
+        page_count: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+    let total_pages = doc.get_pages().len();
+    emit_progress_simple(app_handle, 1, 2, pdf_path);
+
+    let page_labels = match build_page_labels(labels, total_pages) {
+        Ok(tree) => tree,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+    match doc.catalog_mut() {
+        Ok(catalog) => catalog.set("PageLabels", page_labels),
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot read PDF catalog: {}", e));
+            return result;
+        }
+    }
+
+    let output_path = out_dir.join(format!("{}-labeled.pdf", file_stem(pdf_path)));
+    match doc.save(&output_path) {
+        Ok(_) => {
+            result.output_path = output_path.to_string_lossy().to_string();
+            result.page_count = total_pages;
+        }
+        Err(e) => result.errors.push(format!("Cannot save PDF: {}", e)),
+    }
+    emit_progress_simple(app_handle, 2, 2, pdf_path);
+
+    result
+}
+
+// --- Page duplication ---
+
+/// Upper bound on the page count produced by `duplicate_pdf_pages`.
+const MAX_DUPLICATED_PAGES: usize = 10_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfDuplicateResult {
+    pub output_path: String,
+    pub output_page_count: usize,
+    pub errors: Vec<String>,
+}
+
+/// Expand `(page, repeat_count)` pairs into the output page sequence, where a
+/// listed page appears `repeat_count + 1` times in a row. Invalid or repeated
+/// entries are reported and ignored.
+fn duplicated_page_sequence(
+    page_repeats: &[(usize, usize)],
+    total_pages: usize,
+) -> Result<(Vec<u32>, Vec<String>), String> {
+    let mut repeats: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut errors = Vec::new();
+    for &(page, count) in page_repeats {
+        if page == 0 || page > total_pages {
+            errors.push(format!(
+                "Page {} is out of range (document has {} pages)",
+                page, total_pages
+            ));
+        } else if repeats.insert(page, count).is_some() {
+            errors.push(format!("Page {} is listed more than once", page));
+        }
+    }
+
+    let output_pages = (1..=total_pages)
+        .map(|page| {
+            repeats
+                .get(&page)
+                .map_or(1, |count| count.saturating_add(1))
+        })
+        .try_fold(0usize, |acc, n| acc.checked_add(n))
+        .filter(|&n| n <= MAX_DUPLICATED_PAGES)
+        .ok_or_else(|| {
+            format!(
+                "Duplicated document would exceed {} pages",
+                MAX_DUPLICATED_PAGES
+            )
+        })?;
+
+    let mut sequence = Vec::with_capacity(output_pages);
+    for page in 1..=total_pages as u32 {
+        let copies = repeats.get(&(page as usize)).map_or(1, |count| count + 1);
+        sequence.extend(std::iter::repeat_n(page, copies));
+    }
+    Ok((sequence, errors))
+}
+
+/// Build a copy of the PDF in which selected pages are repeated in place.
+pub fn duplicate_pdf_pages(
+    pdf_path: &str,
+    page_repeats: &[(usize, usize)],
+    output_dir: &str,
+) -> PdfDuplicateResult {
+    let mut result = PdfDuplicateResult {
+        output_path: String::new(),
+        output_page_count: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let source_doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+    let source_pages = source_doc.get_pages();
+
+    let sequence = match duplicated_page_sequence(page_repeats, source_pages.len()) {
+        Ok((sequence, errors)) => {
+            result.errors.extend(errors);
+            sequence
+        }
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
+    let mut new_doc = extract_pages(&source_doc, &source_pages, sequence);
+    let output_path = out_dir.join(format!("{}-duplicated.pdf", file_stem(pdf_path)));
+    match save_split(&mut new_doc, &output_path) {
+        Ok(path) => {
+            result.output_path = path;
+            result.output_page_count = new_doc.get_pages().len();
+        }
+        Err(e) => result.errors.push(e),
+    }
+
+    result
+}
+
+// --- Margin crop ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PdfCropMarginsResult {
+    pub output_path: String,
+    pub pages_processed: usize,
+    pub errors: Vec<String>,
+}
+
+/// Read a PDF rectangle as `[llx, lly, urx, ury]`, normalising swapped corners.
+fn pdf_rect(doc: &LopdfDocument, obj: &Object) -> Option<[f32; 4]> {
+    let values: Vec<f32> = resolve_object(doc, obj)?
+        .as_array()
+        .ok()?
+        .iter()
+        .map(|v| resolve_object(doc, v)?.as_float().ok())
+        .collect::<Option<_>>()?;
+    let [x0, y0, x1, y1] = <[f32; 4]>::try_from(values).ok()?;
+    Some([x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)])
+}
+
+/// Shrink `media_box` by `margin` points on every side.
+fn inset_rect(media_box: [f32; 4], margin: f32) -> Result<[f32; 4], String> {
+    let [llx, lly, urx, ury] = media_box;
+    let cropped = [llx + margin, lly + margin, urx - margin, ury - margin];
+    if cropped[2] <= cropped[0] || cropped[3] <= cropped[1] {
+        return Err(format!(
+            "a {} pt margin leaves nothing of a {}×{} pt page",
+            margin,
+            urx - llx,
+            ury - lly
+        ));
+    }
+    Ok(cropped)
+}
+
+/// Set every page's CropBox to its MediaBox minus `margin_points` on each edge.
+/// Pages whose MediaBox is unreadable or too small are reported and left as is.
+pub fn pdf_crop_margins(
+    pdf_path: &str,
+    margin_points: f32,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> PdfCropMarginsResult {
+    let mut result = PdfCropMarginsResult {
+        output_path: String::new(),
+        pages_processed: 0,
+        errors: Vec::new(),
+    };
+
+    if !margin_points.is_finite() || margin_points < 0.0 {
+        result
+            .errors
+            .push("Margin must be a non-negative number of points".to_string());
+        return result;
+    }
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let pages = doc.get_pages();
+    let total = pages.len();
+    for (idx, (page_num, page_id)) in pages.into_iter().enumerate() {
+        let crop_box = inherited_attribute(&doc, page_id, b"MediaBox")
+            .and_then(|media| pdf_rect(&doc, &media))
+            .ok_or_else(|| "missing or invalid MediaBox".to_string())
+            .and_then(|media| inset_rect(media, margin_points));
+        let applied = crop_box.and_then(|crop_box| {
+            doc.get_dictionary_mut(page_id)
+                .map_err(|e| e.to_string())?
+                .set("CropBox", crop_box.map(Object::Real).to_vec());
+            Ok(())
+        });
+        match applied {
+            Ok(()) => result.pages_processed += 1,
+            Err(e) => result.errors.push(format!("Page {}: {}", page_num, e)),
+        }
+        emit_progress_simple(app_handle, idx + 1, total, pdf_path);
+    }
+
+    let output_path = out_dir.join(format!("{}-cropped.pdf", file_stem(pdf_path)));
+    match doc.save(&output_path) {
+        Ok(_) => result.output_path = output_path.to_string_lossy().to_string(),
+        Err(e) => result.errors.push(format!("Cannot save PDF: {}", e)),
+    }
+
+    result
+}
+
+// --- Signature field placeholder ---
+
+const DEFAULT_SIGNATURE_LABEL: &str = "Sign here";
+
+/// Form XObject drawn for an unsigned signature widget: a dashed border with the label inside.
+fn signature_appearance(width: f32, height: f32, label: &str) -> Result<Stream, String> {
+    let font_size = (height * 0.3).clamp(6.0, 12.0);
+    let grey = || vec![Object::Real(0.4), Object::Real(0.4), Object::Real(0.4)];
+    let operations = vec![
+        Operation::new("q", vec![]),
+        Operation::new("RG", grey()),
+        Operation::new("w", vec![Object::Real(1.0)]),
+        Operation::new(
+            "d",
+            vec![
+                Object::Array(vec![Object::Real(3.0), Object::Real(3.0)]),
+                Object::Integer(0),
+            ],
+        ),
+        Operation::new(
+            "re",
+            vec![
+                Object::Real(0.5),
+                Object::Real(0.5),
+                Object::Real(width - 1.0),
+                Object::Real(height - 1.0),
+            ],
+        ),
+        Operation::new("S", vec![]),
+        Operation::new("BT", vec![]),
+        Operation::new("rg", grey()),
+        Operation::new(
+            "Tf",
+            vec![Object::Name(b"Helv".to_vec()), Object::Real(font_size)],
+        ),
+        Operation::new(
+            "Td",
+            vec![
+                Object::Real(4.0),
+                Object::Real(((height - font_size) / 2.0).max(1.0)),
+            ],
+        ),
+        Operation::new("Tj", vec![Object::string_literal(pdf_latin1_bytes(label))]),
+        Operation::new("ET", vec![]),
+        Operation::new("Q", vec![]),
+    ];
+    let content = Content { operations }
+        .encode()
+        .map_err(|e| format!("Cannot encode signature appearance: {}", e))?;
+
+    Ok(Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Form",
+            "BBox" => vec![
+                Object::Real(0.0),
+                Object::Real(0.0),
+                Object::Real(width),
+                Object::Real(height),
+            ],
+            "Resources" => dictionary! {
+                "Font" => dictionary! {
+                    "Helv" => dictionary! {
+                        "Type" => "Font",
+                        "Subtype" => "Type1",
+                        "BaseFont" => "Helvetica",
+                        "Encoding" => "WinAnsiEncoding"
+                    }
+                }
+            }
+        },
+        content,
+    ))
+}
+
+/// Append `value` to the array stored under `key` in the dictionary `owner_id`,
+/// following an indirect array and creating the entry when it is missing.
+/// Returns the new array length.
+fn append_to_array_entry(
+    doc: &mut LopdfDocument,
+    owner_id: ObjectId,
+    key: &[u8],
+    value: Object,
+) -> Result<usize, String> {
+    let entry = doc
+        .get_dictionary(owner_id)
+        .map_err(|e| format!("Cannot read PDF object: {}", e))?
+        .get(key)
+        .ok()
+        .cloned();
+
+    if let Some(Object::Reference(array_id)) = entry {
+        let array = doc
+            .get_object_mut(array_id)
+            .and_then(|o| o.as_array_mut())
+            .map_err(|e| format!("Cannot read PDF array: {}", e))?;
+        array.push(value);
+        return Ok(array.len());
+    }
+
+    let mut items = match entry {
+        Some(Object::Array(items)) => items,
+        _ => Vec::new(),
+    };
+    items.push(value);
+    let len = items.len();
+    doc.get_dictionary_mut(owner_id)
+        .map_err(|e| format!("Cannot read PDF object: {}", e))?
+        .set(key.to_vec(), items);
+    Ok(len)
+}
+
+/// Add an empty `/Sig` widget at `rect` (x, y, width, height in points) on a
+/// 1-indexed page and register it in the AcroForm. Returns the widget object id.
+fn add_signature_field(
+    doc: &mut LopdfDocument,
+    page_number: usize,
+    rect: [f32; 4],
+    label: &str,
+) -> Result<ObjectId, String> {
+    let [x, y, width, height] = rect;
+    if rect.iter().any(|v| !v.is_finite()) || width <= 0.0 || height <= 0.0 {
+        return Err("Signature field needs a positive width and height".to_string());
+    }
+
+    let pages = doc.get_pages();
+    let page_id = u32::try_from(page_number)
+        .ok()
+        .and_then(|n| pages.get(&n).copied())
+        .ok_or_else(|| {
+            format!(
+                "Page {} is out of range (document has {} pages)",
+                page_number,
+                pages.len()
+            )
+        })?;
+
+    let appearance_id = doc.add_object(signature_appearance(width, height, label)?);
+    let widget_id = doc.add_object(dictionary! {
+        "Type" => "Annot",
+        "Subtype" => "Widget",
+        "FT" => "Sig",
+        "Rect" => vec![
+            Object::Real(x),
+            Object::Real(y),
+            Object::Real(x + width),
+            Object::Real(y + height),
+        ],
+        "F" => 4i64,
+        "P" => page_id,
+        "AP" => dictionary! { "N" => appearance_id }
+    });
+    append_to_array_entry(doc, page_id, b"Annots", Object::Reference(widget_id))?;
+
+    let existing_form = doc
+        .catalog()
+        .ok()
+        .and_then(|c| c.get(b"AcroForm").ok())
+        .cloned();
+    let acroform_id = match existing_form {
+        Some(Object::Reference(id)) => id,
+        Some(Object::Dictionary(dict)) => doc.add_object(dict),
+        _ => doc.add_object(lopdf::Dictionary::new()),
+    };
+    doc.catalog_mut()
+        .map_err(|e| format!("Cannot read PDF catalog: {}", e))?
+        .set("AcroForm", acroform_id);
+
+    let field_count =
+        append_to_array_entry(doc, acroform_id, b"Fields", Object::Reference(widget_id))?;
+    let acroform = doc
+        .get_dictionary_mut(acroform_id)
+        .map_err(|e| format!("Cannot read AcroForm: {}", e))?;
+    let sig_flags = acroform
+        .get(b"SigFlags")
+        .and_then(|o| o.as_i64())
+        .unwrap_or(0);
+    acroform.set("SigFlags", sig_flags | 1);
+
+    doc.get_dictionary_mut(widget_id)
+        .map_err(|e| format!("Cannot read signature widget: {}", e))?
+        .set(
+            "T",
+            Object::string_literal(format!("Signature{}", field_count)),
+        );
+
+    Ok(widget_id)
+}
+
+/// Place an unsigned signature field on one page. The field is a visual
+/// placeholder only; no cryptographic signature is applied.
+pub fn pdf_add_signature_field(
+    pdf_path: &str,
+    page_number: usize,
+    rect: [f32; 4],
+    label: Option<&str>,
+    output_dir: &str,
+) -> PdfPageEditResult {
+    let mut result = PdfPageEditResult {
+        output_path: String::new(),
+        page_count: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let mut doc = match LopdfDocument::load(pdf_path) {
+        Ok(d) => d,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot load PDF '{}': {}", pdf_path, e));
+            return result;
+        }
+    };
+
+    let label = label
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .unwrap_or(DEFAULT_SIGNATURE_LABEL);
+    if let Err(e) = add_signature_field(&mut doc, page_number, rect, label) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let output_path = out_dir.join(format!("{}-signature.pdf", file_stem(pdf_path)));
+    match doc.save(&output_path) {
+        Ok(_) => {
+            result.output_path = output_path.to_string_lossy().to_string();
+            result.page_count = doc.get_pages().len();
+        }
+        Err(e) => result.errors.push(format!("Cannot save PDF: {}", e)),
+    }
+
+    result
+}
+
+fn media_box_object((width, height): (f32, f32)) -> Object {
+    Object::Array(vec![
+        Object::Integer(0),
+        Object::Integer(0),
+        Object::Real(width),
+        Object::Real(height),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(
+        start_page: usize,
+        style: &str,
+        prefix: Option<&str>,
+        start_value: u32,
+    ) -> PageLabelRange {
+        PageLabelRange {
+            start_page,
+            style: style.to_string(),
+            prefix: prefix.map(str::to_string),
+            start_value,
+        }
+    }
+
+    #[test]
+    fn page_labels_build_sorted_number_tree() {
+        let ranges = vec![label(5, "D", None, 1), label(1, "r", None, 1)];
+        let tree = build_page_labels(&ranges, 10).unwrap();
+        let nums = tree
+            .as_dict()
+            .unwrap()
+            .get(b"Nums")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(nums.len(), 4);
+        assert_eq!(nums[0].as_i64().unwrap(), 0);
+        assert_eq!(
+            nums[1]
+                .as_dict()
+                .unwrap()
+                .get(b"S")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"r"
+        );
+        assert_eq!(nums[2].as_i64().unwrap(), 4);
+    }
+
+    #[test]
+    fn page_labels_cover_first_page_and_keep_prefix() {
+        let tree = build_page_labels(&[label(3, "", Some("A-"), 7)], 5).unwrap();
+        let nums = tree
+            .as_dict()
+            .unwrap()
+            .get(b"Nums")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(nums[0].as_i64().unwrap(), 0);
+        let custom = nums[3].as_dict().unwrap();
+        assert!(custom.get(b"S").is_err());
+        assert_eq!(custom.get(b"P").unwrap().as_str().unwrap(), b"A-");
+        assert_eq!(custom.get(b"St").unwrap().as_i64().unwrap(), 7);
+    }
+
+    #[test]
+    fn page_labels_reject_bad_ranges() {
+        assert!(build_page_labels(&[label(9, "D", None, 1)], 5).is_err());
+        assert!(build_page_labels(&[label(1, "x", None, 1)], 5).is_err());
+        assert!(build_page_labels(&[label(2, "D", None, 1), label(2, "r", None, 1)], 5).is_err());
+    }
+
+    /// Build an in-memory document with `n` empty pages under a single Pages node.
+    fn build_test_doc(n: usize) -> LopdfDocument {
+        let mut doc = LopdfDocument::with_version("1.7");
+        let pages_id = doc.new_object_id();
+        let kids: Vec<Object> = (0..n)
+            .map(|_| {
+                Object::Reference(doc.add_object(dictionary! {
+                    "Type" => "Page",
+                    "Parent" => pages_id,
+                    "MediaBox" => vec![
+                        Object::Integer(0),
+                        Object::Integer(0),
+                        Object::Integer(612),
+                        Object::Integer(792),
+                    ]
+                }))
+            })
+            .collect();
+        doc.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => kids,
+                "Count" => n as i64
+            }),
+        );
+        let catalog_id = doc.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id
+        });
+        doc.trailer.set("Root", catalog_id);
+        doc
+    }
+
+    #[test]
+    fn duplicated_sequence_repeats_pages_in_place() {
+        let (sequence, errors) = duplicated_page_sequence(&[(2, 2), (9, 1)], 3).unwrap();
+        assert_eq!(sequence, vec![1, 2, 2, 2, 3]);
+        assert_eq!(errors.len(), 1);
+
+        let (_, errors) = duplicated_page_sequence(&[(1, 1), (1, 3)], 2).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(duplicated_page_sequence(&[(1, usize::MAX)], 2).is_err());
+    }
+
+    #[test]
+    fn repeated_pages_get_distinct_page_objects() {
+        let source = build_test_doc(2);
+        let doc = extract_pages(&source, &source.get_pages(), [1, 1, 2]);
+        let pages = doc.get_pages();
+        assert_eq!(pages.len(), 3);
+        assert_ne!(pages[&1], pages[&2]);
+    }
+
+    #[test]
+    fn repeated_pages_drop_annotations() {
+        let mut source = build_test_doc(1);
+        let page_id = source.get_pages()[&1];
+        let annot_id = source.add_object(dictionary! {
+            "Type" => "Annot",
+            "Subtype" => "Link",
+            "P" => page_id
+        });
+        if let Ok(Object::Dictionary(page)) = source.get_object_mut(page_id) {
+            page.set("Annots", vec![Object::Reference(annot_id)]);
+        }
+
+        let doc = extract_pages(&source, &source.get_pages(), [1, 1]);
+        let pages = doc.get_pages();
+        let annots = |n: u32| doc.get_dictionary(pages[&n]).unwrap().has(b"Annots");
+        assert!(annots(1));
+        assert!(!annots(2));
+    }
+
+    #[test]
+    fn margin_crop_insets_media_box() {
+        let doc = build_test_doc(1);
+        let media = pdf_rect(
+            &doc,
+            &Object::Array(vec![
+                Object::Integer(612),
+                Object::Integer(792),
+                Object::Real(0.0),
+                Object::Integer(0),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(media, [0.0, 0.0, 612.0, 792.0]);
+        assert_eq!(inset_rect(media, 36.0).unwrap(), [36.0, 36.0, 576.0, 756.0]);
+        assert!(inset_rect(media, 306.0).is_err());
+        assert!(pdf_rect(&doc, &Object::Array(vec![Object::Integer(1)])).is_none());
+    }
+
+    #[test]
+    fn odd_even_extraction_keeps_alternate_pages() {
+        let source = build_test_doc(5);
+        let source_pages = source.get_pages();
+        let odd = extract_pages(&source, &source_pages, (1..=5).step_by(2));
+        let even = extract_pages(&source, &source_pages, (2..=5).step_by(2));
+        assert_eq!(odd.get_pages().len(), 3);
+        assert_eq!(even.get_pages().len(), 2);
+
+        let single = build_test_doc(1);
+        let empty = extract_pages(&single, &single.get_pages(), (2..=1).step_by(2));
+        assert!(empty.get_pages().is_empty());
+    }
+
+    #[test]
+    fn signature_field_registers_widget_on_page_and_acroform() {
+        let mut doc = build_test_doc(2);
+        let widget_id =
+            add_signature_field(&mut doc, 2, [72.0, 72.0, 200.0, 50.0], "Sign").unwrap();
+
+        let page_id = doc.get_pages()[&2];
+        let annots = doc
+            .get_dictionary(page_id)
+            .unwrap()
+            .get(b"Annots")
+            .unwrap()
+            .as_array()
+            .unwrap();
+        assert_eq!(annots, &vec![Object::Reference(widget_id)]);
+
+        let widget = doc.get_dictionary(widget_id).unwrap();
+        assert_eq!(widget.get(b"FT").unwrap().as_name().unwrap(), b"Sig");
+        assert_eq!(widget.get(b"T").unwrap().as_str().unwrap(), b"Signature1");
+
+        let acroform_id = doc
+            .catalog()
+            .unwrap()
+            .get(b"AcroForm")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let acroform = doc.get_dictionary(acroform_id).unwrap();
+        assert_eq!(
+            acroform.get(b"Fields").unwrap().as_array().unwrap().len(),
+            1
+        );
+        assert_eq!(acroform.get(b"SigFlags").unwrap().as_i64().unwrap(), 1);
+
+        let second = add_signature_field(&mut doc, 1, [10.0, 10.0, 80.0, 30.0], "B").unwrap();
+        let name = doc.get_dictionary(second).unwrap().get(b"T").unwrap();
+        assert_eq!(name.as_str().unwrap(), b"Signature2");
+    }
+
+    #[test]
+    fn signature_field_rejects_bad_page_or_rect() {
+        let mut doc = build_test_doc(1);
+        assert!(add_signature_field(&mut doc, 0, [0.0, 0.0, 10.0, 10.0], "x").is_err());
+        assert!(add_signature_field(&mut doc, 2, [0.0, 0.0, 10.0, 10.0], "x").is_err());
+        assert!(add_signature_field(&mut doc, 1, [0.0, 0.0, 0.0, 10.0], "x").is_err());
+        assert!(add_signature_field(&mut doc, 1, [f32::NAN, 0.0, 10.0, 10.0], "x").is_err());
+    }
+
+    #[test]
+    fn bookmark_ranges_cover_every_page() {
+        let bookmarks = vec![
+            ("Intro".to_string(), 3),
+            ("Part B".to_string(), 7),
+            ("Duplicate".to_string(), 7),
+            ("Out of range".to_string(), 99),
+        ];
+        assert_eq!(
+            bookmark_ranges(&bookmarks, 10),
+            vec![
+                ("front-matter".to_string(), 1, 2),
+                ("Intro".to_string(), 3, 6),
+                ("Part B".to_string(), 7, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn bookmark_titles_are_sanitized() {
+        assert_eq!(
+            sanitize_bookmark_title("Ch. 1: Intro/Setup", "x"),
+            "Ch. 1_ Intro_Setup"
+        );
+        assert_eq!(sanitize_bookmark_title("../..", "chapter-1"), "chapter-1");
+        assert_eq!(sanitize_bookmark_title("   ", "chapter-2"), "chapter-2");
+    }
+
+    #[test]
+    fn pdf_text_decodes_utf16_titles() {
+        assert_eq!(decode_pdf_text(&[0xFE, 0xFF, 0x00, 0x41, 0x00, 0xE9]), "Aé");
+        assert_eq!(decode_pdf_text(b"Plain"), "Plain");
+    }
+
+    #[test]
+    fn outline_items_resolve_to_page_numbers() {
+        let mut doc = build_test_doc(4);
+        let pages = doc.get_pages();
+        let outlines_id = doc.new_object_id();
+        let second = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("Second"),
+            "Parent" => outlines_id,
+            "Dest" => vec![Object::Reference(pages[&3]), Object::Name(b"Fit".to_vec())],
+        });
+        let first = doc.add_object(dictionary! {
+            "Title" => Object::string_literal("First"),
+            "Parent" => outlines_id,
+            "Next" => second,
+            "A" => dictionary! {
+                "S" => "GoTo",
+                "D" => vec![Object::Reference(pages[&2]), Object::Name(b"Fit".to_vec())],
+            },
+        });
+        doc.objects.insert(
+            outlines_id,
+            Object::Dictionary(dictionary! { "Type" => "Outlines", "First" => first }),
+        );
+        let catalog_id = doc.trailer.get(b"Root").unwrap().as_reference().unwrap();
+        if let Ok(Object::Dictionary(catalog)) = doc.get_object_mut(catalog_id) {
+            catalog.set("Outlines", outlines_id);
+        }
+
+        assert_eq!(
+            top_level_bookmarks(&doc),
+            vec![("First".to_string(), 2), ("Second".to_string(), 3)]
+        );
+    }
+
+    #[test]
+    fn detach_page_updates_kids_and_count() {
+        let mut doc = build_test_doc(3);
+        let pages = doc.get_pages();
+        let (first, second) = (pages[&1], pages[&2]);
+
+        detach_page(&mut doc, second).unwrap();
+
+        let remaining = doc.get_pages();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(remaining[&1], first);
+        assert!(!remaining.values().any(|&id| id == second));
+        let catalog_pages = doc.catalog().unwrap().get(b"Pages").unwrap();
+        let count = doc
+            .get_dictionary(catalog_pages.as_reference().unwrap())
+            .unwrap()
+            .get(b"Count")
+            .unwrap()
+            .as_i64()
+            .unwrap();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn rebuild_page_tree_clones_duplicates_and_keeps_inherited_box() {
+        let mut doc = build_test_doc(2);
+        let pages = doc.get_pages();
+        let (first, second) = (pages[&1], pages[&2]);
+        // Move the MediaBox of page 2 up to the Pages node so it is inherited
+        if let Ok(page) = doc.get_object_mut(second).and_then(|o| o.as_dict_mut()) {
+            page.remove(b"MediaBox");
+        }
+        let root = doc
+            .catalog()
+            .unwrap()
+            .get(b"Pages")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        if let Ok(node) = doc.get_object_mut(root).and_then(|o| o.as_dict_mut()) {
+            node.set("MediaBox", media_box_object((100.0, 200.0)));
+        }
+
+        let count = rebuild_page_tree(&mut doc, &[second, first, second]).unwrap();
+        assert_eq!(count, 3);
+
+        let reordered: Vec<ObjectId> = doc.get_pages().values().copied().collect();
+        assert_eq!(reordered[0], second);
+        assert_eq!(reordered[1], first);
+        assert_ne!(reordered[2], second);
+        let cloned_box = doc
+            .get_dictionary(reordered[2])
+            .unwrap()
+            .get(b"MediaBox")
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .clone();
+        assert_eq!(cloned_box[3].as_float().unwrap(), 200.0);
+    }
 
     #[test]
     fn parse_ranges_simple() {
@@ -2,6 +2,7 @@ use image::codecs::jpeg::JpegEncoder;
 use lopdf::content::{Content, Operation};
 use lopdf::{dictionary, Document as LopdfDocument, Object, Stream};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Cursor;
 use std::path::PathBuf;
 
@@ -295,31 +296,42 @@ pub fn watermark_pdf_image(
     let q_id = doc.add_object(Object::Stream(Stream::new(dictionary! {}, b"q\n".to_vec())));
     let big_q_id = doc.add_object(Object::Stream(Stream::new(dictionary! {}, b"Q\n".to_vec())));
 
+    // The image XObject is stored once; overlay streams are shared by pages of the same size
+    let mut overlay_by_size: HashMap<(u32, u32), lopdf::ObjectId> = HashMap::new();
+
     for (idx, &page_id) in page_ids.iter().enumerate() {
         let (page_w, page_h) = get_page_dimensions(&doc, page_id);
+        let size_key = (page_w.to_bits(), page_h.to_bits());
+
+        let content_id = match overlay_by_size.get(&size_key) {
+            Some(&id) => id,
+            None => {
+                // Compute watermark draw dimensions
+                let draw_w = page_w * scale_clamped;
+                let draw_h = draw_w * aspect_ratio;
+                let margin = WATERMARK_MARGIN_PT;
+
+                let operations =
+                    build_image_watermark_ops(position, draw_w, draw_h, page_w, page_h, margin);
+
+                let content_ops = Content { operations };
+                let content_bytes = match content_ops.encode() {
+                    Ok(b) => b,
+                    Err(e) => {
+                        result
+                            .errors
+                            .push(format!("Content encode error on page: {}", e));
+                        continue;
+                    }
+                };
 
-        // Compute watermark draw dimensions
-        let draw_w = page_w * scale_clamped;
-        let draw_h = draw_w * aspect_ratio;
-        let margin = WATERMARK_MARGIN_PT;
-
-        let operations =
-            build_image_watermark_ops(position, draw_w, draw_h, page_w, page_h, margin);
-
-        let content_ops = Content { operations };
-        let content_bytes = match content_ops.encode() {
-            Ok(b) => b,
-            Err(e) => {
-                result
-                    .errors
-                    .push(format!("Content encode error on page: {}", e));
-                continue;
+                let content_stream = Stream::new(dictionary! {}, content_bytes);
+                let id = doc.add_object(Object::Stream(content_stream));
+                overlay_by_size.insert(size_key, id);
+                id
             }
         };
 
-        let content_stream = Stream::new(dictionary! {}, content_bytes);
-        let content_id = doc.add_object(Object::Stream(content_stream));
-
         // Inject watermark resources into the page (handles indirect refs)
         let entries = vec![
             ("ExtGState", "WmGs", gs_id),
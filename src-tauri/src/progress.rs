@@ -18,7 +18,19 @@ pub fn emit_progress(
     total: usize,
     current_file: &str,
 ) {
-    let done = processed.fetch_add(1, Ordering::Relaxed) + 1;
+    emit_progress_steps(app_handle, processed, 1, total, current_file);
+}
+
+/// Like `emit_progress`, but advances the counter by `steps` — for batches where
+/// one file produces several outputs and `total` counts outputs.
+pub fn emit_progress_steps(
+    app_handle: &tauri::AppHandle,
+    processed: &AtomicUsize,
+    steps: usize,
+    total: usize,
+    current_file: &str,
+) {
+    let done = processed.fetch_add(steps, Ordering::Relaxed) + steps;
     let filename = std::path::Path::new(current_file)
         .file_name()
         .and_then(|f| f.to_str())
@@ -34,6 +46,64 @@ pub fn emit_progress(
     );
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SeamProgressPayload {
+    pub current_file: String,
+    pub seams_removed: usize,
+    pub seams_total: usize,
+}
+
+/// Emit a `"seam-carve-progress"` event while a single file is being seam carved,
+/// since one file can take far longer than a regular batch step.
+pub fn emit_seam_progress(
+    app_handle: &tauri::AppHandle,
+    current_file: &str,
+    seams_removed: usize,
+    seams_total: usize,
+) {
+    let filename = std::path::Path::new(current_file)
+        .file_name()
+        .and_then(|f| f.to_str())
+        .unwrap_or(current_file)
+        .to_string();
+    let _ = app_handle.emit(
+        "seam-carve-progress",
+        SeamProgressPayload {
+            current_file: filename,
+            seams_removed,
+            seams_total,
+        },
+    );
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchPdfProgressPayload {
+    pub file_index: usize,
+    pub total_files: usize,
+    pub pdf_path: String,
+    pub exported_pages: usize,
+}
+
+/// Emit a `"batch-pdf-progress"` event once a PDF of a multi-file export is done.
+/// Page-level `"processing-progress"` events keep flowing for the file itself.
+pub fn emit_batch_pdf_progress(
+    app_handle: &tauri::AppHandle,
+    file_index: usize,
+    total_files: usize,
+    pdf_path: &str,
+    exported_pages: usize,
+) {
+    let _ = app_handle.emit(
+        "batch-pdf-progress",
+        BatchPdfProgressPayload {
+            file_index,
+            total_files,
+            pdf_path: pdf_path.to_string(),
+            exported_pages,
+        },
+    );
+}
+
 /// Convenience wrapper for sequential loops where no `AtomicUsize` is needed.
 /// Simply emits progress with the given completed/total values.
 pub fn emit_progress_simple(
@@ -1,9 +1,14 @@
-use image::{ImageBuffer, Rgba};
-use qrcode::QrCode;
+use image::{DynamicImage, ImageBuffer, Rgba, RgbaImage};
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Document as LopdfDocument, Object, Stream};
+use qrcode::{EcLevel, QrCode, Version};
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::utils::ensure_output_dir;
+use crate::pdf_builder_ops::get_page_dimensions;
+use crate::pdf_ops::pdf_latin1_bytes;
+use crate::utils::{add_flate_gray_image_xobject, add_image_xobject_page, ensure_output_dir};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct QrResult {
@@ -27,14 +32,88 @@ pub fn generate_qr(text: &str, size: u32, output_dir: &str) -> QrResult {
         return result;
     }
 
-    let code = match QrCode::new(text.as_bytes()) {
-        Ok(c) => c,
+    let img = match render_qr(text, size, EcLevel::M) {
+        Ok(img) => img,
         Err(e) => {
-            result.errors.push(format!("QR encoding failed: {}", e));
+            result.errors.push(e);
             return result;
         }
     };
 
+    let output_path = out_dir.join("qrcode.png");
+    match img.save(&output_path) {
+        Ok(_) => {
+            result.output_path = output_path.to_string_lossy().to_string();
+        }
+        Err(e) => {
+            result.errors.push(format!("Cannot save QR image: {}", e));
+        }
+    }
+
+    result
+}
+
+/// Parse an error correction level name ("L", "M", "Q", "H"). Defaults to M.
+pub fn parse_ec_level(level: Option<&str>) -> Result<EcLevel, String> {
+    match level.map(|l| l.to_ascii_uppercase()).as_deref() {
+        None | Some("M") => Ok(EcLevel::M),
+        Some("L") => Ok(EcLevel::L),
+        Some("Q") => Ok(EcLevel::Q),
+        Some("H") => Ok(EcLevel::H),
+        Some(other) => Err(format!("Unknown error correction level: {}", other)),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QrMatrixResult {
+    pub modules: Vec<Vec<bool>>,
+    pub version: u32,
+    pub ec_level: String,
+    pub module_count: u32,
+}
+
+fn ec_level_name(level: EcLevel) -> &'static str {
+    match level {
+        EcLevel::L => "L",
+        EcLevel::M => "M",
+        EcLevel::Q => "Q",
+        EcLevel::H => "H",
+    }
+}
+
+/// Encode `text` and return the raw module matrix (rows of columns, `true` = dark)
+/// for callers that render the code themselves. The quiet zone is not included.
+pub fn generate_qr_data(
+    text: &str,
+    error_correction: Option<&str>,
+) -> Result<QrMatrixResult, String> {
+    let ec_level = parse_ec_level(error_correction)?;
+    let code = QrCode::with_error_correction_level(text.as_bytes(), ec_level)
+        .map_err(|e| format!("QR encoding failed: {}", e))?;
+
+    let module_count = code.width();
+    let modules = code
+        .to_colors()
+        .chunks(module_count)
+        .map(|row| row.iter().map(|&c| c == qrcode::Color::Dark).collect())
+        .collect();
+    let version = match code.version() {
+        Version::Normal(v) | Version::Micro(v) => v as u32,
+    };
+
+    Ok(QrMatrixResult {
+        modules,
+        version,
+        ec_level: ec_level_name(code.error_correction_level()).to_string(),
+        module_count: module_count as u32,
+    })
+}
+
+/// Render a QR code centered on a white `size × size` canvas.
+fn render_qr(text: &str, size: u32, ec_level: EcLevel) -> Result<RgbaImage, String> {
+    let code = QrCode::with_error_correction_level(text.as_bytes(), ec_level)
+        .map_err(|e| format!("QR encoding failed: {}", e))?;
+
     let module_count = code.width() as u32;
     let module_size = (size / (module_count + 8)).max(1);
     let margin = (size.saturating_sub(module_count * module_size)) / 2;
@@ -61,15 +140,379 @@ pub fn generate_qr(text: &str, size: u32, output_dir: &str) -> QrResult {
         }
     }
 
-    let output_path = out_dir.join("qrcode.png");
-    match img.save(&output_path) {
-        Ok(_) => {
-            result.output_path = output_path.to_string_lossy().to_string();
-        }
+    Ok(img)
+}
+
+// --- Bulk generation ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QrBatchItem {
+    pub text: String,
+    pub filename: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QrBatchResult {
+    pub generated: Vec<String>,
+    pub errors: Vec<(String, String)>,
+}
+
+/// Resolve an item's filename to a plain `.png`-suffixed name inside the output dir.
+/// Names containing path components are rejected to keep writes inside `output_dir`.
+fn qr_output_name(filename: &str) -> Result<String, String> {
+    let trimmed = filename.trim();
+    let is_plain = Path::new(trimmed)
+        .file_name()
+        .map(|n| n.to_string_lossy() == trimmed)
+        .unwrap_or(false);
+    if trimmed.is_empty() || !is_plain {
+        return Err("Invalid file name".to_string());
+    }
+    if Path::new(trimmed).extension().is_some() {
+        Ok(trimmed.to_string())
+    } else {
+        Ok(format!("{}.png", trimmed))
+    }
+}
+
+/// Generate one QR code per item in parallel, each saved under its own filename.
+pub fn bulk_qr_generate(
+    items: &[QrBatchItem],
+    size: u32,
+    error_correction: Option<&str>,
+    output_dir: &str,
+) -> QrBatchResult {
+    let out_dir = PathBuf::from(output_dir);
+    let setup = ensure_output_dir(&out_dir).and_then(|_| parse_ec_level(error_correction));
+    let ec_level = match setup {
+        Ok(level) => level,
         Err(e) => {
-            result.errors.push(format!("Cannot save QR image: {}", e));
+            return QrBatchResult {
+                generated: Vec::new(),
+                errors: items
+                    .iter()
+                    .map(|item| (item.filename.clone(), e.clone()))
+                    .collect(),
+            };
+        }
+    };
+
+    let outcomes: Vec<Result<String, (String, String)>> = items
+        .par_iter()
+        .map(|item| {
+            let name = qr_output_name(&item.filename).map_err(|e| (item.filename.clone(), e))?;
+            let img =
+                render_qr(&item.text, size, ec_level).map_err(|e| (item.filename.clone(), e))?;
+            let output_path = out_dir.join(&name);
+            img.save(&output_path).map_err(|e| {
+                (
+                    item.filename.clone(),
+                    format!("Cannot save QR image: {}", e),
+                )
+            })?;
+            Ok(output_path.to_string_lossy().to_string())
+        })
+        .collect();
+
+    let mut result = QrBatchResult {
+        generated: Vec::new(),
+        errors: Vec::new(),
+    };
+    for outcome in outcomes {
+        match outcome {
+            Ok(path) => result.generated.push(path),
+            Err(e) => result.errors.push(e),
         }
     }
+    result
+}
+
+// --- Print-ready PDF ---
+
+const PT_PER_MM: f32 = 72.0 / 25.4;
+/// Raster resolution of the embedded QR image.
+const QR_PDF_DPI: f32 = 600.0;
+/// Crop marks start this far outside the trim box (the bleed) and run for `CROP_MARK_LEN_MM`.
+const CROP_MARK_OFFSET_MM: f32 = 3.0;
+const CROP_MARK_LEN_MM: f32 = 5.0;
+const CROP_MARK_WIDTH_PT: f32 = 0.25;
+const LABEL_FONT_SIZE: f32 = 10.0;
+const LABEL_GAP_MM: f32 = 10.0;
+/// Rough Helvetica advance width in em, used to center the label.
+const LABEL_AVG_CHAR_WIDTH_EM: f32 = 0.55;
+
+fn mm_to_pt(mm: f32) -> f32 {
+    mm * PT_PER_MM
+}
+
+/// Thin corner marks around the square trim box at (`x`, `y`) with side `size` (points).
+fn crop_mark_ops(x: f32, y: f32, size: f32) -> Vec<Operation> {
+    let offset = mm_to_pt(CROP_MARK_OFFSET_MM);
+    let len = mm_to_pt(CROP_MARK_LEN_MM);
+    let mut ops = vec![
+        Operation::new("q", vec![]),
+        Operation::new("w", vec![Object::Real(CROP_MARK_WIDTH_PT)]),
+        Operation::new("G", vec![Object::Integer(0)]),
+    ];
+    let mut line = |x1: f32, y1: f32, x2: f32, y2: f32| {
+        ops.push(Operation::new(
+            "m",
+            vec![Object::Real(x1), Object::Real(y1)],
+        ));
+        ops.push(Operation::new(
+            "l",
+            vec![Object::Real(x2), Object::Real(y2)],
+        ));
+        ops.push(Operation::new("S", vec![]));
+    };
+    for (cx, sx) in [(x, -1.0), (x + size, 1.0)] {
+        for (cy, sy) in [(y, -1.0), (y + size, 1.0)] {
+            line(cx + sx * offset, cy, cx + sx * (offset + len), cy);
+            line(cx, cy + sy * offset, cx, cy + sy * (offset + len));
+        }
+    }
+    ops.push(Operation::new("Q", vec![]));
+    ops
+}
 
+/// Helvetica label centered horizontally on `center_x` with its baseline at `baseline_y`.
+fn label_ops(text: &str, center_x: f32, baseline_y: f32) -> Vec<Operation> {
+    let width = text.chars().count() as f32 * LABEL_AVG_CHAR_WIDTH_EM * LABEL_FONT_SIZE;
+    vec![
+        Operation::new("BT", vec![]),
+        Operation::new(
+            "Tf",
+            vec![
+                Object::Name(b"QrF1".to_vec()),
+                Object::Real(LABEL_FONT_SIZE),
+            ],
+        ),
+        Operation::new(
+            "Td",
+            vec![
+                Object::Real(center_x - width / 2.0),
+                Object::Real(baseline_y),
+            ],
+        ),
+        Operation::new(
+            "Tj",
+            vec![Object::String(
+                pdf_latin1_bytes(text),
+                lopdf::StringFormat::Literal,
+            )],
+        ),
+        Operation::new("ET", vec![]),
+    ]
+}
+
+/// Generate a single-page PDF with a QR code of `qr_size_mm` (quiet zone included)
+/// centered on an A4 or Letter page, with optional crop marks and a caption.
+pub fn generate_qr_pdf(
+    text: &str,
+    page_format: &str,
+    qr_size_mm: f32,
+    include_crop_marks: bool,
+    label_text: Option<&str>,
+    output_path: &str,
+) -> QrResult {
+    let qr_px = (qr_size_mm / 25.4 * QR_PDF_DPI).round() as u32;
+    let mut result = QrResult {
+        output_path: String::new(),
+        size: qr_px,
+        errors: Vec::new(),
+    };
+
+    match build_qr_pdf(
+        text,
+        page_format,
+        qr_size_mm,
+        qr_px,
+        include_crop_marks,
+        label_text,
+        output_path,
+    ) {
+        Ok(path) => result.output_path = path,
+        Err(e) => result.errors.push(e),
+    }
     result
 }
+
+fn build_qr_pdf(
+    text: &str,
+    page_format: &str,
+    qr_size_mm: f32,
+    qr_px: u32,
+    include_crop_marks: bool,
+    label_text: Option<&str>,
+    output_path: &str,
+) -> Result<String, String> {
+    let (page_w, page_h) = get_page_dimensions(page_format, "portrait");
+    let qr_pt = mm_to_pt(qr_size_mm);
+    let mark_extent = mm_to_pt(CROP_MARK_OFFSET_MM + CROP_MARK_LEN_MM);
+    if qr_pt + 2.0 * mark_extent > page_w.min(page_h) {
+        return Err(format!(
+            "QR size of {} mm does not fit on a {} page",
+            qr_size_mm, page_format
+        ));
+    }
+
+    let output = PathBuf::from(output_path);
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        ensure_output_dir(parent)?;
+    }
+
+    // Lossless grayscale keeps module edges sharp; JPEG would ring around them
+    let img = DynamicImage::ImageRgba8(render_qr(text, qr_px, EcLevel::M)?).to_luma8();
+    let qr_x = (page_w - qr_pt) / 2.0;
+    let qr_y = (page_h - qr_pt) / 2.0;
+    let mut doc = LopdfDocument::with_version("1.7");
+    let pages_id = doc.new_object_id();
+    let image_id = add_flate_gray_image_xobject(&mut doc, &img)?;
+    let page_id = add_image_xobject_page(
+        &mut doc,
+        pages_id,
+        image_id,
+        page_w,
+        page_h,
+        [qr_x, qr_y, qr_pt, qr_pt],
+    )?;
+
+    let mut extra_ops = Vec::new();
+    if include_crop_marks {
+        extra_ops.extend(crop_mark_ops(qr_x, qr_y, qr_pt));
+    }
+    let label = label_text.map(str::trim).filter(|l| !l.is_empty());
+    if let Some(label) = label {
+        let baseline = qr_y - mm_to_pt(LABEL_GAP_MM) - LABEL_FONT_SIZE;
+        extra_ops.extend(label_ops(label, page_w / 2.0, baseline));
+    }
+
+    if !extra_ops.is_empty() {
+        let bytes = Content {
+            operations: extra_ops,
+        }
+        .encode()
+        .map_err(|e| format!("Content encode error: {}", e))?;
+        let extra_id = doc.add_object(Stream::new(dictionary! {}, bytes));
+        let font_id = doc.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => "Helvetica",
+            "Encoding" => "WinAnsiEncoding"
+        });
+        if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
+            let image_content = page.get(b"Contents").ok().cloned();
+            if let Some(image_content) = image_content {
+                page.set("Contents", vec![image_content, Object::Reference(extra_id)]);
+            }
+            if let Ok(Object::Dictionary(resources)) = page.get_mut(b"Resources") {
+                resources.set("Font", dictionary! { "QrF1" => font_id });
+            }
+        }
+    }
+
+    // Trim box is the QR itself; the bleed extends to where the crop marks begin
+    let bleed = mm_to_pt(CROP_MARK_OFFSET_MM);
+    if let Ok(Object::Dictionary(page)) = doc.get_object_mut(page_id) {
+        page.set(
+            "TrimBox",
+            vec![
+                Object::Real(qr_x),
+                Object::Real(qr_y),
+                Object::Real(qr_x + qr_pt),
+                Object::Real(qr_y + qr_pt),
+            ],
+        );
+        page.set(
+            "BleedBox",
+            vec![
+                Object::Real(qr_x - bleed),
+                Object::Real(qr_y - bleed),
+                Object::Real(qr_x + qr_pt + bleed),
+                Object::Real(qr_y + qr_pt + bleed),
+            ],
+        );
+    }
+
+    doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![Object::Reference(page_id)],
+            "Count" => 1_i64
+        }),
+    );
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    });
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+
+    doc.save(&output)
+        .map_err(|e| format!("Cannot save PDF: {}", e))?;
+    Ok(output.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_name_appends_png_when_missing() {
+        assert_eq!(qr_output_name("SN-001").unwrap(), "SN-001.png");
+        assert_eq!(qr_output_name("label.jpg").unwrap(), "label.jpg");
+    }
+
+    #[test]
+    fn output_name_rejects_path_components() {
+        assert!(qr_output_name("../escape").is_err());
+        assert!(qr_output_name("nested/name").is_err());
+        assert!(qr_output_name("  ").is_err());
+    }
+
+    #[test]
+    fn qr_data_is_square_matrix_without_quiet_zone() {
+        let data = generate_qr_data("https://example.com", Some("h")).unwrap();
+        assert_eq!(data.ec_level, "H");
+        assert_eq!(data.module_count, 17 + 4 * data.version);
+        assert_eq!(data.modules.len(), data.module_count as usize);
+        assert!(data
+            .modules
+            .iter()
+            .all(|row| row.len() == data.module_count as usize));
+        // Finder pattern corner is dark, so the matrix starts at the symbol edge
+        assert!(data.modules[0][0]);
+        assert!(generate_qr_data("x", Some("Z")).is_err());
+    }
+
+    #[test]
+    fn crop_marks_stay_outside_trim_box() {
+        let ops = crop_mark_ops(100.0, 100.0, 50.0);
+        // q, w, G, then 8 lines of (m, l, S), then Q
+        assert_eq!(ops.len(), 3 + 8 * 3 + 1);
+        for op in ops
+            .iter()
+            .filter(|op| op.operator == "m" || op.operator == "l")
+        {
+            let coords: Vec<f32> = op
+                .operands
+                .iter()
+                .filter_map(|o| o.as_float().ok())
+                .collect();
+            let inside = |v: f32| v > 100.0 && v < 150.0;
+            assert!(!(inside(coords[0]) && inside(coords[1])));
+        }
+    }
+
+    #[test]
+    fn mm_conversion_matches_print_points() {
+        assert!((mm_to_pt(25.4) - 72.0).abs() < 1e-4);
+        assert!((mm_to_pt(1.0) - 2.8346).abs() < 1e-3);
+    }
+
+    #[test]
+    fn render_qr_matches_requested_size() {
+        let img = render_qr("https://example.com", 256, EcLevel::M).unwrap();
+        assert!(img.width() <= 256 && img.width() == img.height());
+    }
+}
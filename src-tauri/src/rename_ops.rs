@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
 use std::path::{Path, PathBuf};
 use time::OffsetDateTime;
 
@@ -19,7 +21,18 @@ pub struct RenameEntry {
 }
 
 fn sanitize_filename(name: &str) -> Result<(), String> {
-    if name.contains('/') || name.contains('\\') || name.contains("..") {
+    // `:` covers Windows drive-relative names like `C:evil.jpg`, which `join` would
+    // resolve outside the target directory.
+    let single_component = matches!(
+        Path::new(name).components().collect::<Vec<_>>().as_slice(),
+        [std::path::Component::Normal(_)]
+    );
+    if name.contains('/')
+        || name.contains('\\')
+        || name.contains("..")
+        || name.contains(':')
+        || (!name.is_empty() && !single_component)
+    {
         return Err(format!(
             "Generated filename '{}' contains invalid characters",
             name
@@ -31,8 +44,64 @@ fn sanitize_filename(name: &str) -> Result<(), String> {
     Ok(())
 }
 
+const DEFAULT_HASH_LENGTH: usize = 8;
+/// Length of a BLAKE3 digest in hex.
+const HASH_HEX_LENGTH: usize = 64;
+
+/// Whether `pattern` uses `{hash}` or `{hash:N}`; other text such as `{hashtag}`
+/// is left literal.
+fn has_hash_token(pattern: &str) -> bool {
+    pattern.contains("{hash}") || pattern.contains("{hash:")
+}
+
+/// BLAKE3 hash of a file's contents as lowercase hex.
+fn file_hash_hex(path: &str) -> Result<String, String> {
+    let mut file = File::open(path).map_err(|e| format!("Cannot open '{}': {}", path, e))?;
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(&mut file, &mut hasher).map_err(|e| format!("Cannot read '{}': {}", path, e))?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Replace `{hash}` and `{hash:N}` tokens with the first 8 (or N) hex characters of `full_hash`.
+fn replace_hash_tokens(pattern: &str, full_hash: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find("{hash") {
+        let token = &rest[start + "{hash".len()..];
+        if !(token.is_empty() || token.starts_with('}') || token.starts_with(':')) {
+            // Not a hash token (e.g. `{hashtag}`): keep it literally
+            output.push_str(&rest[..start + "{hash".len()]);
+            rest = token;
+            continue;
+        }
+        output.push_str(&rest[..start]);
+        let end = token
+            .find('}')
+            .ok_or_else(|| "Unterminated {hash} token".to_string())?;
+        let length = match &token[..end] {
+            "" => DEFAULT_HASH_LENGTH,
+            spec => spec
+                .strip_prefix(':')
+                .and_then(|n| n.parse::<usize>().ok())
+                .filter(|n| (1..=full_hash.len()).contains(n))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid hash token '{{hash{}}}' (length must be 1-{})",
+                        spec,
+                        full_hash.len()
+                    )
+                })?,
+        };
+        output.push_str(&full_hash[..length]);
+        rest = &token[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
 /// Bulk rename files using a pattern.
-/// Supported tokens: {name} (original stem), {index} (counter), {date} (YYYY-MM-DD), {ext} (extension).
+/// Supported tokens: {name} (original stem), {index} (counter), {date} (YYYY-MM-DD), {ext} (extension),
+/// {hash} / {hash:N} (first 8 or N hex characters of the BLAKE3 content hash).
 /// Files are copied (not moved) to the output directory with the new name.
 pub fn bulk_rename(
     input_paths: &[String],
@@ -55,6 +124,14 @@ pub fn bulk_rename(
 
     let today = today_date();
 
+    let uses_hash = has_hash_token(pattern);
+    if uses_hash {
+        if let Err(e) = replace_hash_tokens(pattern, &"0".repeat(HASH_HEX_LENGTH)) {
+            result.errors.push(e);
+            return result;
+        }
+    }
+
     let total = input_paths.len();
     for (i, input_path) in input_paths.iter().enumerate() {
         let path = Path::new(input_path);
@@ -62,7 +139,21 @@ pub fn bulk_rename(
         let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
         let index = start_index + i as u32;
 
-        let new_stem = pattern
+        // Expand hash tokens first so braces in original names are left alone
+        let hashed_pattern = if uses_hash {
+            match file_hash_hex(input_path).and_then(|hash| replace_hash_tokens(pattern, &hash)) {
+                Ok(p) => p,
+                Err(e) => {
+                    result.errors.push(e);
+                    emit_progress_simple(app_handle, i + 1, total, input_path);
+                    continue;
+                }
+            }
+        } else {
+            pattern.to_string()
+        };
+
+        let new_stem = hashed_pattern
             .replace("{name}", original_stem)
             .replace("{index}", &format!("{:03}", index))
             .replace("{date}", &today)
@@ -109,6 +200,118 @@ pub fn bulk_rename(
     result
 }
 
+// --- Rename from map ---
+
+#[derive(Deserialize)]
+struct RenameMapRow {
+    original: String,
+    new: String,
+}
+
+/// Parse an explicit rename map: CSV with `original,new` headers, or a JSON object
+/// of `"old name": "new name"` pairs. Both sides must be bare filenames.
+fn parse_rename_map(contents: &str, is_csv: bool) -> Result<Vec<(String, String)>, String> {
+    let pairs: Vec<(String, String)> = if is_csv {
+        csv::Reader::from_reader(contents.as_bytes())
+            .deserialize::<RenameMapRow>()
+            .map(|row| {
+                row.map(|r| (r.original.trim().to_string(), r.new.trim().to_string()))
+                    .map_err(|e| format!("Invalid rename CSV: {}", e))
+            })
+            .collect::<Result<_, _>>()?
+    } else {
+        serde_json::from_str::<BTreeMap<String, String>>(contents)
+            .map_err(|e| format!("Invalid rename JSON: {}", e))?
+            .into_iter()
+            .collect()
+    };
+
+    for (original, new) in &pairs {
+        for name in [original, new] {
+            if sanitize_filename(name).is_err() {
+                return Err(format!(
+                    "Rename map entry '{}' -> '{}' has an invalid filename '{}'",
+                    original, new, name
+                ));
+            }
+        }
+    }
+    Ok(pairs)
+}
+
+/// Copy files from `source_dir` to `output_dir` under the names given by the map
+/// file at `map_path`. Map entries without a matching file are skipped silently;
+/// a target name used twice is reported and only the first copy is made.
+pub fn bulk_rename_from_map(
+    map_path: &str,
+    source_dir: &str,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> RenameResult {
+    let mut result = RenameResult {
+        renamed_count: 0,
+        results: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let is_csv = Path::new(map_path)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+    let pairs = match std::fs::read_to_string(map_path)
+        .map_err(|e| format!("Cannot read rename map: {}", e))
+        .and_then(|contents| parse_rename_map(&contents, is_csv))
+    {
+        Ok(p) => p,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let source = Path::new(source_dir);
+    let mut used_names = HashSet::new();
+    let total = pairs.len();
+    for (i, (original, new)) in pairs.into_iter().enumerate() {
+        let input_path = source.join(&original);
+        if !input_path.is_file() {
+            emit_progress_simple(app_handle, i + 1, total, &input_path.to_string_lossy());
+            continue;
+        }
+        if !used_names.insert(new.to_lowercase()) {
+            result.errors.push(format!(
+                "'{}' is mapped to '{}' more than once",
+                original, new
+            ));
+            emit_progress_simple(app_handle, i + 1, total, &input_path.to_string_lossy());
+            continue;
+        }
+
+        match std::fs::copy(&input_path, out_dir.join(&new)) {
+            Ok(_) => {
+                result.results.push(RenameEntry {
+                    original_name: original,
+                    new_name: new,
+                });
+                result.renamed_count += 1;
+            }
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Failed to copy '{}': {}", input_path.display(), e))
+            }
+        }
+        emit_progress_simple(app_handle, i + 1, total, &input_path.to_string_lossy());
+    }
+
+    result
+}
+
 /// Get today's date as YYYY-MM-DD using the `time` crate.
 fn today_date() -> String {
     let now = OffsetDateTime::now_utc();
@@ -132,6 +335,91 @@ mod tests {
         assert!(sanitize_filename("").is_err());
     }
 
+    #[test]
+    fn sanitize_rejects_drive_relative_names() {
+        assert!(sanitize_filename("C:evil.jpg").is_err());
+        assert!(sanitize_filename("C:").is_err());
+        assert!(sanitize_filename(".").is_err());
+        assert!(parse_rename_map(r#"{"a.png": "C:a.png"}"#, false).is_err());
+        assert!(parse_rename_map("original,new\nD:a.png,a.png\n", true).is_err());
+    }
+
+    #[test]
+    fn rename_map_errors_name_the_entry() {
+        let err = parse_rename_map(r#"{"a.png": "../b.png"}"#, false).unwrap_err();
+        assert!(err.starts_with("Rename map entry 'a.png' -> '../b.png'"));
+    }
+
+    #[test]
+    fn rename_map_reads_csv_and_json() {
+        let csv = "original,new\nIMG_1.jpg, beach.jpg\nIMG_2.jpg,sunset.jpg\n";
+        assert_eq!(
+            parse_rename_map(csv, true).unwrap(),
+            vec![
+                ("IMG_1.jpg".to_string(), "beach.jpg".to_string()),
+                ("IMG_2.jpg".to_string(), "sunset.jpg".to_string()),
+            ]
+        );
+        let json = r#"{"b.png": "two.png", "a.png": "one.png"}"#;
+        assert_eq!(
+            parse_rename_map(json, false).unwrap(),
+            vec![
+                ("a.png".to_string(), "one.png".to_string()),
+                ("b.png".to_string(), "two.png".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_map_rejects_paths_and_bad_headers() {
+        assert!(parse_rename_map(r#"{"a.png": "../a.png"}"#, false).is_err());
+        assert!(parse_rename_map(r#"{"sub/a.png": "a.png"}"#, false).is_err());
+        assert!(parse_rename_map("from,to\na,b\n", true).is_err());
+    }
+
+    const SAMPLE_HASH: &str = "a1b2c3d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8f90";
+
+    #[test]
+    fn hash_token_defaults_to_eight_chars() {
+        assert_eq!(
+            replace_hash_tokens("{hash}-{name}", SAMPLE_HASH).unwrap(),
+            "a1b2c3d4-{name}"
+        );
+    }
+
+    #[test]
+    fn hash_token_accepts_custom_length() {
+        assert_eq!(
+            replace_hash_tokens("{hash:16}", SAMPLE_HASH).unwrap(),
+            "a1b2c3d4e5f60718"
+        );
+        assert!(replace_hash_tokens("{hash:0}", SAMPLE_HASH).is_err());
+        assert!(replace_hash_tokens("{hash:99}", SAMPLE_HASH).is_err());
+        assert!(replace_hash_tokens("{hash:abc}", SAMPLE_HASH).is_err());
+        assert!(replace_hash_tokens("{hash", SAMPLE_HASH).is_err());
+    }
+
+    #[test]
+    fn hash_lookalikes_stay_literal() {
+        assert!(!has_hash_token("{hashtag}-{name}"));
+        assert!(has_hash_token("{name}-{hash:4}"));
+        assert_eq!(
+            replace_hash_tokens("{hashtag}-{hash:4}", SAMPLE_HASH).unwrap(),
+            "{hashtag}-a1b2"
+        );
+    }
+
+    #[test]
+    fn file_hash_matches_blake3_of_contents() {
+        let dir = std::env::temp_dir().join("rustine-rename-hash-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sample.txt");
+        std::fs::write(&path, b"hello").unwrap();
+        let hash = file_hash_hex(path.to_str().unwrap()).unwrap();
+        assert_eq!(hash, blake3::hash(b"hello").to_hex().to_string());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn today_date_format() {
         let date = today_date();
@@ -1,11 +1,15 @@
-use image::{DynamicImage, GenericImageView, RgbaImage};
+use image::codecs::gif::GifDecoder;
+use image::{AnimationDecoder, DynamicImage, RgbaImage};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
 
+use crate::gif_ops::{gif_delay_cs, open_gif_encoder, AnimationResult};
 use crate::progress::emit_progress_simple;
-use crate::utils::ensure_output_dir;
+use crate::utils::{ensure_output_dir, file_stem};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SpriteSheetResult {
@@ -23,20 +27,89 @@ struct AtlasFrame {
     pub y: u32,
     pub w: u32,
     pub h: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame_delay_cs: Option<u16>,
+    /// Where the sprite's pixels start once centered in its cell.
+    #[serde(skip)]
+    pub content_x: u32,
+    #[serde(skip)]
+    pub content_y: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AtlasFormat {
+    Default,
+    Phaser3,
+}
+
+impl AtlasFormat {
+    fn parse(format: Option<&str>) -> Result<Self, String> {
+        match format.unwrap_or("default") {
+            "default" => Ok(AtlasFormat::Default),
+            "phaser3" => Ok(AtlasFormat::Phaser3),
+            other => Err(format!("Unknown atlas format: {}", other)),
+        }
+    }
+}
+
+/// Layout and atlas settings shared by every sprite sheet source.
+struct SheetOptions<'a> {
+    columns: u32,
+    padding: u32,
+    background: [u8; 4],
+    animation_groups: &'a [AnimationGroup],
+    atlas_format: AtlasFormat,
+    /// Fixed cell size; sprites are scaled to fit instead of measuring the largest one.
+    uniform_cell: Option<(u32, u32)>,
+}
+
+/// Sprite sheets are currently always written as PNG.
+const SHEET_FORMAT: &str = "png";
+
+/// Default sheet background: transparent for formats with alpha, white for JPEG
+/// (which would otherwise flatten transparent pixels to black).
+fn default_background(format: &str) -> [u8; 4] {
+    match format {
+        "jpg" | "jpeg" => [255, 255, 255, 255],
+        _ => [0, 0, 0, 0],
+    }
+}
+
+/// Named animation made of sprite indices (in input order).
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnimationGroup {
+    pub name: String,
+    pub frame_indices: Vec<usize>,
+}
+
+/// Frame delay used for animations whose sprites carry no timing of their own.
+const DEFAULT_ANIMATION_DELAY_CS: u16 = 10;
+
+#[derive(Debug, Serialize)]
+struct AtlasAnimation {
+    frames: Vec<String>,
+    delay_cs: u16,
 }
 
 #[derive(Debug, Serialize)]
 struct AtlasJson {
     frames: HashMap<String, AtlasFrame>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    animations: HashMap<String, AtlasAnimation>,
 }
 
 /// Generate a sprite sheet from multiple images arranged in a grid.
-/// All images are resized to match the largest width/height found.
+/// Cells match the largest width/height found, or `uniform_cell` when given.
 /// Outputs the spritesheet PNG and a JSON atlas file.
+#[allow(clippy::too_many_arguments)]
 pub fn generate_spritesheet(
     image_paths: &[String],
     columns: u32,
     padding: u32,
+    background_color: Option<[u8; 4]>,
+    animation_groups: &[AnimationGroup],
+    atlas_format: Option<&str>,
+    uniform_cell: Option<(u32, u32)>,
     output_dir: &str,
     app_handle: &tauri::AppHandle,
 ) -> SpriteSheetResult {
@@ -54,14 +127,20 @@ pub fn generate_spritesheet(
         return result;
     }
 
+    let atlas_format = match AtlasFormat::parse(atlas_format) {
+        Ok(f) => f,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
     let out_dir = PathBuf::from(output_dir);
     if let Err(e) = ensure_output_dir(&out_dir) {
         result.errors.push(e);
         return result;
     }
 
-    let cols = columns.max(1);
-
     // Load all images in parallel
     let loaded: Vec<(String, Result<DynamicImage, String>)> = image_paths
         .par_iter()
@@ -77,11 +156,19 @@ pub fn generate_spritesheet(
         })
         .collect();
 
+    // Track where each input landed so animation indices survive failed loads
     let mut images: Vec<(String, DynamicImage)> = Vec::new();
+    let mut sprite_index: Vec<Option<usize>> = Vec::with_capacity(loaded.len());
     for (name, img_result) in loaded {
         match img_result {
-            Ok(img) => images.push((name, img)),
-            Err(e) => result.errors.push(e),
+            Ok(img) => {
+                sprite_index.push(Some(images.len()));
+                images.push((name, img));
+            }
+            Err(e) => {
+                sprite_index.push(None);
+                result.errors.push(e);
+            }
         }
     }
 
@@ -90,17 +177,162 @@ pub fn generate_spritesheet(
         return result;
     }
 
-    // Find max cell dimensions
-    let max_w = images
-        .iter()
-        .map(|(_, img)| img.width())
-        .max()
-        .unwrap_or(64);
-    let max_h = images
+    let sprites: Vec<(String, DynamicImage, Option<u16>)> = images
+        .into_iter()
+        .map(|(name, img)| (name, img, None))
+        .collect();
+    let groups = remap_animation_groups(animation_groups, &sprite_index, &mut result.errors);
+    let options = SheetOptions {
+        columns: columns.max(1),
+        padding,
+        background: background_color.unwrap_or_else(|| default_background(SHEET_FORMAT)),
+        animation_groups: &groups,
+        atlas_format,
+        uniform_cell,
+    };
+    write_spritesheet(
+        &sprites,
+        &options,
+        &out_dir,
+        "spritesheet",
+        app_handle,
+        &mut result,
+    );
+    result
+}
+
+/// Extract every GIF frame (composited to the full canvas) straight into a sprite sheet.
+/// Sprites are named `frame_0001`, `frame_0002`, … and carry their delay in the atlas.
+pub fn sprite_sheet_from_gif(
+    gif_path: &str,
+    columns: u32,
+    padding: u32,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> SpriteSheetResult {
+    let mut result = SpriteSheetResult {
+        image_path: String::new(),
+        atlas_path: String::new(),
+        sprite_count: 0,
+        sheet_width: 0,
+        sheet_height: 0,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let file = match File::open(gif_path) {
+        Ok(f) => f,
+        Err(e) => {
+            result.errors.push(format!("Cannot open GIF: {}", e));
+            return result;
+        }
+    };
+    let decoder = match GifDecoder::new(BufReader::new(file)) {
+        Ok(d) => d,
+        Err(e) => {
+            result.errors.push(format!("Cannot decode GIF: {}", e));
+            return result;
+        }
+    };
+
+    let mut sprites: Vec<(String, DynamicImage, Option<u16>)> = Vec::new();
+    for frame in decoder.into_frames() {
+        match frame {
+            Ok(frame) => {
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                let delay_ms = numer as f32 / denom.max(1) as f32;
+                let delay_cs = (delay_ms / 10.0).round().clamp(0.0, 65535.0) as u16;
+                let name = format!("frame_{:04}", sprites.len() + 1);
+                sprites.push((
+                    name,
+                    DynamicImage::ImageRgba8(frame.into_buffer()),
+                    Some(delay_cs),
+                ));
+            }
+            Err(e) => {
+                result
+                    .errors
+                    .push(format!("Frame {}: {}", sprites.len() + 1, e));
+                break;
+            }
+        }
+    }
+
+    if sprites.is_empty() {
+        result.errors.push("GIF contains no frames".to_string());
+        return result;
+    }
+
+    let base_name = format!("{}-spritesheet", file_stem(gif_path));
+    let options = SheetOptions {
+        columns: columns.max(1),
+        padding,
+        background: default_background(SHEET_FORMAT),
+        animation_groups: &[],
+        atlas_format: AtlasFormat::Default,
+        uniform_cell: None,
+    };
+    write_spritesheet(
+        &sprites,
+        &options,
+        &out_dir,
+        &base_name,
+        app_handle,
+        &mut result,
+    );
+    result
+}
+
+/// Translate input indices to sprite indices, dropping frames that failed to load
+/// and reporting indices that point past the input list.
+fn remap_animation_groups(
+    groups: &[AnimationGroup],
+    sprite_index: &[Option<usize>],
+    errors: &mut Vec<String>,
+) -> Vec<AnimationGroup> {
+    groups
         .iter()
-        .map(|(_, img)| img.height())
-        .max()
-        .unwrap_or(64);
+        .map(|group| {
+            let frame_indices = group
+                .frame_indices
+                .iter()
+                .filter_map(|&i| match sprite_index.get(i) {
+                    Some(mapped) => *mapped,
+                    None => {
+                        errors.push(format!(
+                            "Animation '{}': frame index {} is out of range",
+                            group.name, i
+                        ));
+                        None
+                    }
+                })
+                .collect();
+            AnimationGroup {
+                name: group.name.clone(),
+                frame_indices,
+            }
+        })
+        .collect()
+}
+
+/// Lay sprites out on a grid and save `{base_name}.png` plus its `{base_name}.json` atlas.
+fn write_spritesheet(
+    images: &[(String, DynamicImage, Option<u16>)],
+    options: &SheetOptions,
+    out_dir: &Path,
+    base_name: &str,
+    app_handle: &tauri::AppHandle,
+    result: &mut SpriteSheetResult,
+) {
+    let cols = options.columns;
+    let padding = options.padding;
+
+    let (max_w, max_h) = cell_size(images, options.uniform_cell);
 
     let count = images.len() as u32;
     let rows = count.div_ceil(cols);
@@ -108,29 +340,29 @@ pub fn generate_spritesheet(
     let sheet_width = cols * max_w + (cols + 1) * padding;
     let sheet_height = rows * max_h + (rows + 1) * padding;
 
-    let mut sheet = RgbaImage::new(sheet_width, sheet_height);
-
-    // Fill with transparent
-    for pixel in sheet.pixels_mut() {
-        *pixel = image::Rgba([0, 0, 0, 0]);
-    }
+    let mut sheet =
+        RgbaImage::from_pixel(sheet_width, sheet_height, image::Rgba(options.background));
 
     let mut atlas_frames: Vec<(String, AtlasFrame)> = Vec::new();
 
     let total_sprites = images.len();
-    for (i, (name, img)) in images.iter().enumerate() {
+    for (i, (name, img, delay_cs)) in images.iter().enumerate() {
         let col = (i as u32) % cols;
         let row = (i as u32) / cols;
 
         let x = padding + col * (max_w + padding);
         let y = padding + row * (max_h + padding);
 
+        let rgba = match options.uniform_cell {
+            Some((cell_w, cell_h)) => fit_to_cell(img, cell_w, cell_h),
+            None => img.to_rgba8(),
+        };
+
         // Center the image within the cell if smaller than max
-        let (iw, ih) = img.dimensions();
+        let (iw, ih) = rgba.dimensions();
         let offset_x = (max_w.saturating_sub(iw)) / 2;
         let offset_y = (max_h.saturating_sub(ih)) / 2;
 
-        let rgba = img.to_rgba8();
         image::imageops::overlay(
             &mut sheet,
             &rgba,
@@ -138,22 +370,36 @@ pub fn generate_spritesheet(
             (y + offset_y) as i64,
         );
 
-        atlas_frames.push((
-            name.clone(),
+        // Uniform cells are declared up front, so every frame reports the full cell
+        let frame = if options.uniform_cell.is_some() {
+            AtlasFrame {
+                x,
+                y,
+                w: max_w,
+                h: max_h,
+                frame_delay_cs: *delay_cs,
+                content_x: x,
+                content_y: y,
+            }
+        } else {
             AtlasFrame {
                 x,
                 y,
                 w: iw.min(max_w),
                 h: ih.min(max_h),
-            },
-        ));
+                frame_delay_cs: *delay_cs,
+                content_x: x + offset_x,
+                content_y: y + offset_y,
+            }
+        };
+        atlas_frames.push((name.clone(), frame));
 
         result.sprite_count += 1;
         emit_progress_simple(app_handle, i + 1, total_sprites, name);
     }
 
     // Save spritesheet PNG
-    let image_path = out_dir.join("spritesheet.png");
+    let image_path = out_dir.join(format!("{}.{}", base_name, SHEET_FORMAT));
     match sheet.save(&image_path) {
         Ok(_) => {
             result.image_path = image_path.to_string_lossy().to_string();
@@ -164,13 +410,24 @@ pub fn generate_spritesheet(
             result
                 .errors
                 .push(format!("Cannot save spritesheet: {}", e));
-            return result;
+            return;
         }
     }
 
     // Build and save JSON atlas
-    let atlas_json = build_atlas_json(atlas_frames);
-    let atlas_path = out_dir.join("spritesheet.json");
+    let atlas_json = match options.atlas_format {
+        AtlasFormat::Default => {
+            let animations = build_atlas_animations(images, options.animation_groups);
+            build_atlas_json(atlas_frames, animations)
+        }
+        AtlasFormat::Phaser3 => build_phaser3_atlas_json(
+            &atlas_frames,
+            &format!("{}.{}", base_name, SHEET_FORMAT),
+            sheet_width,
+            sheet_height,
+        ),
+    };
+    let atlas_path = out_dir.join(format!("{}.json", base_name));
     match std::fs::write(&atlas_path, atlas_json) {
         Ok(_) => {
             result.atlas_path = atlas_path.to_string_lossy().to_string();
@@ -179,13 +436,538 @@ pub fn generate_spritesheet(
             result.errors.push(format!("Cannot save atlas JSON: {}", e));
         }
     }
+}
 
-    result
+/// Cell dimensions: the declared uniform cell, or the largest sprite on each axis.
+fn cell_size(
+    images: &[(String, DynamicImage, Option<u16>)],
+    uniform_cell: Option<(u32, u32)>,
+) -> (u32, u32) {
+    if let Some(cell) = uniform_cell {
+        return cell;
+    }
+    let max_w = images
+        .iter()
+        .map(|(_, img, _)| img.width())
+        .max()
+        .unwrap_or(64);
+    let max_h = images
+        .iter()
+        .map(|(_, img, _)| img.height())
+        .max()
+        .unwrap_or(64);
+    (max_w, max_h)
+}
+
+/// Scale a sprite (up or down) to fit inside the cell, preserving aspect ratio.
+fn fit_to_cell(img: &DynamicImage, cell_w: u32, cell_h: u32) -> RgbaImage {
+    if img.width() == cell_w && img.height() <= cell_h
+        || img.height() == cell_h && img.width() <= cell_w
+    {
+        return img.to_rgba8();
+    }
+    img.resize(cell_w, cell_h, image::imageops::FilterType::Lanczos3)
+        .to_rgba8()
 }
 
-fn build_atlas_json(frames: Vec<(String, AtlasFrame)>) -> String {
+/// Resolve animation groups to sprite names. The delay is the mean of the member
+/// frames' own delays, or the default when the sprites carry no timing.
+fn build_atlas_animations(
+    images: &[(String, DynamicImage, Option<u16>)],
+    groups: &[AnimationGroup],
+) -> HashMap<String, AtlasAnimation> {
+    groups
+        .iter()
+        .map(|group| {
+            let members: Vec<&(String, DynamicImage, Option<u16>)> = group
+                .frame_indices
+                .iter()
+                .filter_map(|&i| images.get(i))
+                .collect();
+            let delays: Vec<u32> = members
+                .iter()
+                .filter_map(|(_, _, delay)| delay.map(u32::from))
+                .collect();
+            let delay_cs = if delays.is_empty() {
+                DEFAULT_ANIMATION_DELAY_CS
+            } else {
+                (delays.iter().sum::<u32>() as f32 / delays.len() as f32).round() as u16
+            };
+            let animation = AtlasAnimation {
+                frames: members.iter().map(|(name, _, _)| name.clone()).collect(),
+                delay_cs,
+            };
+            (group.name.clone(), animation)
+        })
+        .collect()
+}
+
+/// Phaser 3 "JSON Array" atlas. Frames point at the sprite pixels inside each cell.
+fn build_phaser3_atlas_json(
+    frames: &[(String, AtlasFrame)],
+    image_name: &str,
+    sheet_width: u32,
+    sheet_height: u32,
+) -> String {
+    let frames: Vec<serde_json::Value> = frames
+        .iter()
+        .map(|(name, f)| {
+            serde_json::json!({
+                "filename": name,
+                "frame": { "x": f.content_x, "y": f.content_y, "w": f.w, "h": f.h },
+                "rotated": false,
+                "trimmed": false,
+                "spriteSourceSize": { "x": 0, "y": 0, "w": f.w, "h": f.h },
+                "sourceSize": { "w": f.w, "h": f.h },
+            })
+        })
+        .collect();
+    let atlas = serde_json::json!({
+        "frames": frames,
+        "meta": {
+            "app": "Rust-ine",
+            "image": image_name,
+            "size": { "w": sheet_width, "h": sheet_height },
+            "scale": "1",
+        },
+    });
+    serde_json::to_string_pretty(&atlas).unwrap_or_else(|_| "{}".to_string())
+}
+
+fn build_atlas_json(
+    frames: Vec<(String, AtlasFrame)>,
+    animations: HashMap<String, AtlasAnimation>,
+) -> String {
     let atlas = AtlasJson {
         frames: frames.into_iter().collect(),
+        animations,
     };
     serde_json::to_string_pretty(&atlas).unwrap_or_else(|_| "{}".to_string())
 }
+
+// --- CSS export ---
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SpriteCssResult {
+    pub output_path: String,
+    pub sprite_count: usize,
+}
+
+/// A frame rectangle read back from an atlas file.
+#[derive(Debug, PartialEq)]
+struct CssFrame {
+    name: String,
+    x: i64,
+    y: i64,
+    w: i64,
+    h: i64,
+}
+
+fn rect_field(value: &serde_json::Value, key: &str) -> Option<i64> {
+    value
+        .get(key)
+        .and_then(|v| v.as_f64())
+        .map(|v| v.round() as i64)
+}
+
+/// Read frames from either atlas layout: ours (`frames` is a name-keyed object) or
+/// Phaser 3 (`frames` is an array of `{ filename, frame: { x, y, w, h } }`).
+/// Frames are sorted by name so the generated CSS is stable.
+fn parse_atlas_frames(json: &str) -> Result<Vec<CssFrame>, String> {
+    let atlas: serde_json::Value =
+        serde_json::from_str(json).map_err(|e| format!("Invalid atlas JSON: {}", e))?;
+    let rect = |name: String, r: &serde_json::Value| -> Result<CssFrame, String> {
+        match (
+            rect_field(r, "x"),
+            rect_field(r, "y"),
+            rect_field(r, "w"),
+            rect_field(r, "h"),
+        ) {
+            (Some(x), Some(y), Some(w), Some(h)) => Ok(CssFrame { name, x, y, w, h }),
+            _ => Err(format!("Frame '{}' is missing x/y/w/h", name)),
+        }
+    };
+
+    let mut frames = match atlas.get("frames") {
+        Some(serde_json::Value::Object(map)) => map
+            .iter()
+            .map(|(name, r)| rect(name.clone(), r))
+            .collect::<Result<Vec<_>, _>>()?,
+        Some(serde_json::Value::Array(list)) => list
+            .iter()
+            .map(|entry| {
+                let name = entry
+                    .get("filename")
+                    .and_then(|n| n.as_str())
+                    .ok_or_else(|| "Phaser frame is missing its filename".to_string())?;
+                let r = entry
+                    .get("frame")
+                    .ok_or_else(|| format!("Frame '{}' has no rectangle", name))?;
+                rect(name.to_string(), r)
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => return Err("Atlas has no frames".to_string()),
+    };
+    frames.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(frames)
+}
+
+/// Make a frame name usable in a class selector.
+fn css_class_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn build_sprite_css(frames: &[CssFrame], sheet_url: &str) -> String {
+    let sheet_url = sheet_url.replace('\\', "/").replace('\'', "%27");
+    let mut css = String::from(".sprite { display: inline-block; }\n");
+    for frame in frames {
+        css.push_str(&format!(
+            ".sprite-{} {{ width: {}px; height: {}px; background: url('{}') {}px {}px no-repeat; }}\n",
+            css_class_name(&frame.name),
+            frame.w,
+            frame.h,
+            sheet_url,
+            -frame.x,
+            -frame.y,
+        ));
+    }
+    css
+}
+
+/// Write a stylesheet with one `.sprite-{name}` class per atlas frame.
+/// `sheet_image_path` is used verbatim as the URL the page will load the sheet from.
+pub fn export_sprites_css(
+    sheet_image_path: &str,
+    atlas_path: &str,
+    output_path: &str,
+) -> Result<SpriteCssResult, String> {
+    let json =
+        std::fs::read_to_string(atlas_path).map_err(|e| format!("Cannot read atlas: {}", e))?;
+    let frames = parse_atlas_frames(&json)?;
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    std::fs::write(output_path, build_sprite_css(&frames, sheet_image_path))
+        .map_err(|e| format!("Cannot write CSS: {}", e))?;
+    Ok(SpriteCssResult {
+        output_path: output_path.to_string(),
+        sprite_count: frames.len(),
+    })
+}
+
+// --- Sprite sheet to GIF ---
+
+/// Pick frames in playback order: `order` by name (unknown names are reported and
+/// skipped, repeats are allowed), or every frame alphabetically.
+fn order_frames<'a>(
+    frames: &'a [CssFrame],
+    order: Option<&[String]>,
+    errors: &mut Vec<String>,
+) -> Vec<&'a CssFrame> {
+    let Some(order) = order else {
+        return frames.iter().collect();
+    };
+    order
+        .iter()
+        .filter_map(|name| {
+            let frame = frames.iter().find(|f| &f.name == name);
+            if frame.is_none() {
+                errors.push(format!("Frame '{}' is not in the atlas", name));
+            }
+            frame
+        })
+        .collect()
+}
+
+/// Cut a frame out of the sheet, rejecting rectangles that fall outside it.
+fn crop_atlas_frame(sheet: &DynamicImage, frame: &CssFrame) -> Result<RgbaImage, String> {
+    let fits = frame.x >= 0
+        && frame.y >= 0
+        && frame.w > 0
+        && frame.h > 0
+        && frame.x + frame.w <= sheet.width() as i64
+        && frame.y + frame.h <= sheet.height() as i64;
+    if !fits {
+        return Err(format!(
+            "Frame '{}' lies outside the {}x{} sheet",
+            frame.name,
+            sheet.width(),
+            sheet.height()
+        ));
+    }
+    Ok(sheet
+        .crop_imm(
+            frame.x as u32,
+            frame.y as u32,
+            frame.w as u32,
+            frame.h as u32,
+        )
+        .to_rgba8())
+}
+
+/// Rebuild an animated GIF from a sheet and its atlas (ours or Phaser 3).
+/// Frames of different sizes are centered on a canvas fitting the largest one.
+pub fn spritesheet_to_gif(
+    sheet_path: &str,
+    atlas_path: &str,
+    frame_order: Option<&[String]>,
+    delay_ms: u16,
+    loop_count: u16,
+    output_dir: &str,
+    app_handle: &tauri::AppHandle,
+) -> AnimationResult {
+    let mut result = AnimationResult {
+        output_path: String::new(),
+        frame_count: 0,
+        format: "gif".to_string(),
+        original_avg_delay_cs: None,
+        new_avg_delay_cs: None,
+        errors: Vec::new(),
+    };
+
+    let out_dir = PathBuf::from(output_dir);
+    if let Err(e) = ensure_output_dir(&out_dir) {
+        result.errors.push(e);
+        return result;
+    }
+
+    let frames = match std::fs::read_to_string(atlas_path)
+        .map_err(|e| format!("Cannot read atlas: {}", e))
+        .and_then(|json| parse_atlas_frames(&json))
+    {
+        Ok(f) => f,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+    let sheet = match image::open(sheet_path) {
+        Ok(img) => img,
+        Err(e) => {
+            result
+                .errors
+                .push(format!("Cannot open sprite sheet: {}", e));
+            return result;
+        }
+    };
+
+    let images: Vec<RgbaImage> = order_frames(&frames, frame_order, &mut result.errors)
+        .into_iter()
+        .filter_map(|frame| match crop_atlas_frame(&sheet, frame) {
+            Ok(img) => Some(img),
+            Err(e) => {
+                result.errors.push(e);
+                None
+            }
+        })
+        .collect();
+    if images.is_empty() {
+        result.errors.push("No frames to animate".to_string());
+        return result;
+    }
+
+    let width = images.iter().map(|img| img.width()).max().unwrap_or(1);
+    let height = images.iter().map(|img| img.height()).max().unwrap_or(1);
+    let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+        result.errors.push(format!(
+            "Frame dimensions {}x{} exceed GIF maximum of {}x{}",
+            width,
+            height,
+            u16::MAX,
+            u16::MAX
+        ));
+        return result;
+    };
+
+    let output_path = out_dir.join(format!("{}.gif", file_stem(sheet_path)));
+    let mut encoder = match open_gif_encoder(&output_path, gif_width, gif_height, loop_count) {
+        Ok(enc) => enc,
+        Err(e) => {
+            result.errors.push(e);
+            return result;
+        }
+    };
+
+    let delay_cs = gif_delay_cs(delay_ms);
+    let total = images.len();
+    for (i, img) in images.into_iter().enumerate() {
+        let mut canvas = RgbaImage::new(width, height);
+        image::imageops::overlay(
+            &mut canvas,
+            &img,
+            ((width - img.width()) / 2) as i64,
+            ((height - img.height()) / 2) as i64,
+        );
+        let mut pixels = canvas.into_raw();
+        let mut frame = gif::Frame::from_rgba_speed(gif_width, gif_height, &mut pixels, 30);
+        frame.delay = delay_cs;
+        match encoder.write_frame(&frame) {
+            Ok(()) => result.frame_count += 1,
+            Err(e) => result
+                .errors
+                .push(format!("Frame {}: write error — {}", i + 1, e)),
+        }
+        emit_progress_simple(app_handle, i + 1, total, sheet_path);
+    }
+
+    result.output_path = output_path.to_string_lossy().to_string();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn animation_indices_skip_failed_loads() {
+        let groups = vec![AnimationGroup {
+            name: "walk".to_string(),
+            frame_indices: vec![0, 1, 2, 9],
+        }];
+        let mut errors = Vec::new();
+        let remapped = remap_animation_groups(&groups, &[Some(0), None, Some(1)], &mut errors);
+        assert_eq!(remapped[0].frame_indices, vec![0, 1]);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    fn animation_delay_falls_back_to_default() {
+        let blank = || DynamicImage::new_rgba8(1, 1);
+        let images = vec![
+            ("a".to_string(), blank(), None),
+            ("b".to_string(), blank(), Some(6)),
+            ("c".to_string(), blank(), Some(9)),
+        ];
+        let groups = vec![
+            AnimationGroup {
+                name: "idle".to_string(),
+                frame_indices: vec![0],
+            },
+            AnimationGroup {
+                name: "run".to_string(),
+                frame_indices: vec![1, 2],
+            },
+        ];
+        let animations = build_atlas_animations(&images, &groups);
+        assert_eq!(animations["idle"].delay_cs, DEFAULT_ANIMATION_DELAY_CS);
+        assert_eq!(animations["run"].delay_cs, 8);
+        assert_eq!(animations["run"].frames, vec!["b", "c"]);
+    }
+
+    #[test]
+    fn gif_frames_follow_requested_order() {
+        let frames = parse_atlas_frames(
+            r#"{"frames":{"b":{"x":2,"y":0,"w":2,"h":2},"a":{"x":0,"y":0,"w":2,"h":2}}}"#,
+        )
+        .unwrap();
+        let mut errors = Vec::new();
+        let names =
+            |picked: Vec<&CssFrame>| picked.iter().map(|f| f.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(order_frames(&frames, None, &mut errors)), ["a", "b"]);
+        let order = ["b".to_string(), "zz".to_string(), "b".to_string()];
+        assert_eq!(
+            names(order_frames(&frames, Some(&order), &mut errors)),
+            ["b", "b"]
+        );
+        assert_eq!(errors.len(), 1);
+
+        let sheet = DynamicImage::new_rgba8(3, 2);
+        assert_eq!(
+            crop_atlas_frame(&sheet, &frames[0]).unwrap().dimensions(),
+            (2, 2)
+        );
+        assert!(crop_atlas_frame(&sheet, &frames[1]).is_err());
+    }
+
+    #[test]
+    fn phaser3_atlas_lists_frames_in_order() {
+        let frame = |x, y| AtlasFrame {
+            x,
+            y,
+            w: 16,
+            h: 8,
+            frame_delay_cs: None,
+            content_x: x + 2,
+            content_y: y,
+        };
+        let frames = vec![
+            ("hero_0".to_string(), frame(0, 0)),
+            ("hero_1".to_string(), frame(20, 0)),
+        ];
+        let json: serde_json::Value =
+            serde_json::from_str(&build_phaser3_atlas_json(&frames, "sheet.png", 40, 8)).unwrap();
+        assert_eq!(json["frames"][1]["filename"], "hero_1");
+        assert_eq!(json["frames"][1]["frame"]["x"], 22);
+        assert_eq!(json["frames"][0]["sourceSize"]["w"], 16);
+        assert_eq!(json["meta"]["size"]["w"], 40);
+        assert_eq!(json["meta"]["scale"], "1");
+    }
+
+    #[test]
+    fn uniform_cell_ignores_natural_sizes() {
+        let images = vec![
+            ("small".to_string(), DynamicImage::new_rgba8(8, 8), None),
+            ("tall".to_string(), DynamicImage::new_rgba8(10, 200), None),
+        ];
+        assert_eq!(cell_size(&images, None), (10, 200));
+        assert_eq!(cell_size(&images, Some((32, 32))), (32, 32));
+
+        let fitted = fit_to_cell(&images[1].1, 32, 32);
+        assert!(fitted.width() <= 32 && fitted.height() == 32);
+        let grown = fit_to_cell(&images[0].1, 32, 16);
+        assert_eq!(grown.dimensions(), (16, 16));
+    }
+
+    #[test]
+    fn css_export_reads_both_atlas_layouts() {
+        let ours = r#"{"frames": {"walk 2": {"x": 40, "y": 0, "w": 32, "h": 32},
+                                  "idle": {"x": 4, "y": 4, "w": 16, "h": 24}}}"#;
+        let frames = parse_atlas_frames(ours).unwrap();
+        assert_eq!(frames[0].name, "idle");
+        let css = build_sprite_css(&frames, "img/sheet.png");
+        assert!(css.starts_with(".sprite { display: inline-block; }"));
+        assert!(css.contains(
+            ".sprite-walk-2 { width: 32px; height: 32px; background: url('img/sheet.png') -40px 0px no-repeat; }"
+        ));
+
+        let phaser = r#"{"frames": [{"filename": "hero", "frame": {"x": 2, "y": 6, "w": 8, "h": 8}}],
+                         "meta": {"image": "sheet.png"}}"#;
+        let frames = parse_atlas_frames(phaser).unwrap();
+        assert_eq!(
+            frames,
+            vec![CssFrame {
+                name: "hero".to_string(),
+                x: 2,
+                y: 6,
+                w: 8,
+                h: 8
+            }]
+        );
+        assert!(parse_atlas_frames(r#"{"meta": {}}"#).is_err());
+    }
+
+    #[test]
+    fn atlas_format_parsing() {
+        assert_eq!(AtlasFormat::parse(None).unwrap(), AtlasFormat::Default);
+        assert_eq!(
+            AtlasFormat::parse(Some("phaser3")).unwrap(),
+            AtlasFormat::Phaser3
+        );
+        assert!(AtlasFormat::parse(Some("unity")).is_err());
+    }
+
+    #[test]
+    fn default_background_is_white_only_for_jpeg() {
+        assert_eq!(default_background("png"), [0, 0, 0, 0]);
+        assert_eq!(default_background("jpg"), [255, 255, 255, 255]);
+        assert_eq!(default_background("jpeg"), [255, 255, 255, 255]);
+    }
+}
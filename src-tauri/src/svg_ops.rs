@@ -1,4 +1,4 @@
-use image::ImageFormat;
+use image::{ImageFormat, RgbaImage};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
@@ -22,8 +22,7 @@ pub fn rasterize_svg(
 
     let svg_data = fs::read(input_path).map_err(|e| format!("Cannot read SVG file: {}", e))?;
 
-    let tree = resvg::usvg::Tree::from_data(&svg_data, &resvg::usvg::Options::default())
-        .map_err(|e| format!("Cannot parse SVG: {}", e))?;
+    let tree = parse_svg(&svg_data)?;
 
     let original_size = tree.size();
     let scale = target_width as f32 / original_size.width();
@@ -70,3 +69,52 @@ pub fn rasterize_svg(
         height: target_height,
     })
 }
+
+/// Parse SVG source into a render tree.
+pub(crate) fn parse_svg(svg_data: &[u8]) -> Result<resvg::usvg::Tree, String> {
+    resvg::usvg::Tree::from_data(svg_data, &resvg::usvg::Options::default())
+        .map_err(|e| format!("Cannot parse SVG: {}", e))
+}
+
+/// Render an SVG centered on a transparent `size × size` canvas, preserving its
+/// aspect ratio. Returns straight (non-premultiplied) RGBA.
+pub(crate) fn render_svg_square(tree: &resvg::usvg::Tree, size: u32) -> Result<RgbaImage, String> {
+    let svg_size = tree.size();
+    let side = size as f32;
+    let scale = (side / svg_size.width()).min(side / svg_size.height());
+    let dx = (side - svg_size.width() * scale) / 2.0;
+    let dy = (side - svg_size.height() * scale) / 2.0;
+
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size, size)
+        .ok_or_else(|| "Cannot create pixel buffer".to_string())?;
+    let transform = resvg::tiny_skia::Transform::from_scale(scale, scale).post_translate(dx, dy);
+    resvg::render(tree, transform, &mut pixmap.as_mut());
+
+    let data: Vec<u8> = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(size, size, data).ok_or_else(|| "Cannot create image buffer".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_svg_square_letterboxes_and_demultiplies() {
+        let svg = br##"<svg xmlns="http://www.w3.org/2000/svg" width="10" height="20">
+            <rect width="10" height="20" fill="#ff0000" fill-opacity="0.5"/>
+        </svg>"##;
+        let tree = parse_svg(svg).unwrap();
+        let img = render_svg_square(&tree, 20).unwrap();
+        assert_eq!(img.dimensions(), (20, 20));
+        assert_eq!(img.get_pixel(0, 10).0[3], 0);
+        let center = img.get_pixel(10, 10).0;
+        assert!(center[0] >= 250 && (126..=129).contains(&center[3]));
+    }
+}
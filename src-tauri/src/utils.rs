@@ -1,8 +1,11 @@
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 use image::codecs::jpeg::JpegEncoder;
 use lopdf::content::{Content, Operation};
 use lopdf::{dictionary, Document as LopdfDocument, Object, Stream};
+use std::collections::HashSet;
 use std::fs;
-use std::io::Cursor;
+use std::io::{Cursor, Write};
 use std::path::Path;
 
 /// Create the output directory if it does not exist.
@@ -26,6 +29,30 @@ pub fn file_size(path: &str) -> u64 {
     fs::metadata(path).map(|m| m.len()).unwrap_or(0)
 }
 
+/// Write `contents` to a sibling temp file, then rename it over `path`, so readers never
+/// see a half-written file. The temp file is removed if anything fails.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    use std::io::Write;
+
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| "Output path has no file name".to_string())?;
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, std::process::id()));
+
+    let written = fs::File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&temp_path, path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Cannot write '{}': {}", path.display(), e));
+    }
+    Ok(())
+}
+
 /// Get the file stem (name without extension), falling back to "output".
 pub fn file_stem(path: &str) -> String {
     Path::new(path)
@@ -44,8 +71,118 @@ pub fn get_extension(path: &str) -> String {
         .unwrap_or_else(|| "png".to_string())
 }
 
+/// JPEG quality used for photographs when the caller lets the quality be detected.
+pub const PHOTO_JPEG_QUALITY: u8 = 85;
+/// JPEG quality for diagrams and screenshots, where artifacts around edges hurt legibility.
+pub const DIAGRAM_JPEG_QUALITY: u8 = 95;
+
+/// Longest side of the sample the diagram detection looks at, bounding its cost.
+const DIAGRAM_SAMPLE_SIDE: u32 = 256;
+const DIAGRAM_MAX_COLORS: usize = 64;
+/// Share of horizontally adjacent sample pixels that must be identical to call it flat.
+const DIAGRAM_UNIFORM_SHARE: f32 = 0.6;
+
+/// Heuristic: few distinct colors or large flat areas suggest a diagram or screenshot.
+fn looks_like_diagram(img: &image::RgbImage) -> bool {
+    let sample = image::imageops::thumbnail(
+        img,
+        img.width().min(DIAGRAM_SAMPLE_SIDE),
+        img.height().min(DIAGRAM_SAMPLE_SIDE),
+    );
+
+    let mut colors = HashSet::new();
+    let mut same = 0usize;
+    let mut pairs = 0usize;
+    for row in sample.rows() {
+        let mut prev: Option<&image::Rgb<u8>> = None;
+        for px in row {
+            if colors.len() <= DIAGRAM_MAX_COLORS {
+                colors.insert(px.0);
+            }
+            if let Some(prev) = prev {
+                pairs += 1;
+                if prev == px {
+                    same += 1;
+                }
+            }
+            prev = Some(px);
+        }
+    }
+
+    colors.len() < DIAGRAM_MAX_COLORS
+        || (pairs > 0 && same as f32 / pairs as f32 >= DIAGRAM_UNIFORM_SHARE)
+}
+
+/// Add `img` to the document as a JPEG image XObject and return its id.
+/// `jpeg_quality` of `None` picks `DIAGRAM_JPEG_QUALITY` for diagram-like images and
+/// `PHOTO_JPEG_QUALITY` otherwise.
+pub fn add_jpeg_image_xobject(
+    doc: &mut LopdfDocument,
+    img: &image::RgbImage,
+    jpeg_quality: Option<u8>,
+) -> Result<lopdf::ObjectId, String> {
+    let jpeg_quality = jpeg_quality.unwrap_or_else(|| {
+        if looks_like_diagram(img) {
+            DIAGRAM_JPEG_QUALITY
+        } else {
+            PHOTO_JPEG_QUALITY
+        }
+    });
+
+    let mut jpeg_buf: Vec<u8> = Vec::new();
+    let mut cursor = Cursor::new(&mut jpeg_buf);
+    let encoder = JpegEncoder::new_with_quality(&mut cursor, jpeg_quality);
+    img.write_with_encoder(encoder)
+        .map_err(|e| format!("JPEG encode failed: {}", e))?;
+
+    let image_stream = Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => img.width() as i64,
+            "Height" => img.height() as i64,
+            "ColorSpace" => "DeviceRGB",
+            "BitsPerComponent" => 8_i64,
+            "Filter" => "DCTDecode"
+        },
+        jpeg_buf,
+    );
+    Ok(doc.add_object(image_stream))
+}
+
+/// Add `img` as a lossless FlateDecode DeviceGray image XObject and return its id.
+/// Suited to hard-edged graphics such as QR codes, where JPEG would ring.
+pub fn add_flate_gray_image_xobject(
+    doc: &mut LopdfDocument,
+    img: &image::GrayImage,
+) -> Result<lopdf::ObjectId, String> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(img.as_raw())
+        .map_err(|e| format!("Image compression failed: {}", e))?;
+    let data = encoder
+        .finish()
+        .map_err(|e| format!("Image compression failed: {}", e))?;
+
+    let image_stream = Stream::new(
+        dictionary! {
+            "Type" => "XObject",
+            "Subtype" => "Image",
+            "Width" => img.width() as i64,
+            "Height" => img.height() as i64,
+            "ColorSpace" => "DeviceGray",
+            "BitsPerComponent" => 8_i64,
+            "Interpolate" => false,
+            "Filter" => "FlateDecode"
+        },
+        data,
+    );
+    Ok(doc.add_object(image_stream))
+}
+
 /// Embed an image file as a single PDF page with JPEG encoding.
-/// Returns the ObjectId of the created page.
+/// `jpeg_quality` of `None` picks `DIAGRAM_JPEG_QUALITY` for diagram-like images and
+/// `PHOTO_JPEG_QUALITY` otherwise. Returns the ObjectId of the created page.
 pub fn embed_image_as_pdf_page(
     doc: &mut LopdfDocument,
     pages_id: lopdf::ObjectId,
@@ -53,20 +190,13 @@ pub fn embed_image_as_pdf_page(
     page_w: f32,
     page_h: f32,
     margin: f32,
-    jpeg_quality: u8,
+    jpeg_quality: Option<u8>,
 ) -> Result<lopdf::ObjectId, String> {
     let img = image::open(image_path)
         .map_err(|e| format!("Cannot open image '{}': {}", image_path, e))?
         .into_rgb8();
 
     let (img_w, img_h) = (img.width(), img.height());
-
-    let mut jpeg_buf: Vec<u8> = Vec::new();
-    let mut cursor = Cursor::new(&mut jpeg_buf);
-    let encoder = JpegEncoder::new_with_quality(&mut cursor, jpeg_quality);
-    img.write_with_encoder(encoder)
-        .map_err(|e| format!("JPEG encode failed: {}", e))?;
-
     let available_w = page_w - 2.0 * margin;
     let available_h = page_h - 2.0 * margin;
 
@@ -79,19 +209,44 @@ pub fn embed_image_as_pdf_page(
     let draw_x = margin + (available_w - draw_w) / 2.0;
     let draw_y = margin + (available_h - draw_h) / 2.0;
 
-    let image_stream = Stream::new(
-        dictionary! {
-            "Type" => "XObject",
-            "Subtype" => "Image",
-            "Width" => img_w as i64,
-            "Height" => img_h as i64,
-            "ColorSpace" => "DeviceRGB",
-            "BitsPerComponent" => 8_i64,
-            "Filter" => "DCTDecode"
-        },
-        jpeg_buf,
-    );
-    let image_id = doc.add_object(image_stream);
+    embed_image_at_position(
+        doc,
+        pages_id,
+        &img,
+        page_w,
+        page_h,
+        [draw_x, draw_y, draw_w, draw_h],
+        jpeg_quality,
+    )
+}
+
+/// Add a page of `page_w × page_h` points showing `img` stretched into
+/// `draw_rect` (`[x, y, width, height]` in points, origin bottom-left).
+/// Returns the ObjectId of the created page.
+pub fn embed_image_at_position(
+    doc: &mut LopdfDocument,
+    pages_id: lopdf::ObjectId,
+    img: &image::RgbImage,
+    page_w: f32,
+    page_h: f32,
+    draw_rect: [f32; 4],
+    jpeg_quality: Option<u8>,
+) -> Result<lopdf::ObjectId, String> {
+    let image_id = add_jpeg_image_xobject(doc, img, jpeg_quality)?;
+    add_image_xobject_page(doc, pages_id, image_id, page_w, page_h, draw_rect)
+}
+
+/// Add a page of `page_w × page_h` points drawing the image XObject `image_id`
+/// into `draw_rect` (`[x, y, width, height]` in points). Returns the page id.
+pub fn add_image_xobject_page(
+    doc: &mut LopdfDocument,
+    pages_id: lopdf::ObjectId,
+    image_id: lopdf::ObjectId,
+    page_w: f32,
+    page_h: f32,
+    draw_rect: [f32; 4],
+) -> Result<lopdf::ObjectId, String> {
+    let [draw_x, draw_y, draw_w, draw_h] = draw_rect;
 
     let content_ops = Content {
         operations: vec![
@@ -139,6 +294,70 @@ pub fn embed_image_as_pdf_page(
     Ok(doc.add_object(page))
 }
 
+/// Write a one-page PDF to `output_path` with the image drawn at an exact
+/// position and size, all in PDF points. Returns the output path.
+#[allow(clippy::too_many_arguments)]
+pub fn image_to_pdf_page(
+    image_path: &str,
+    page_width_pt: f32,
+    page_height_pt: f32,
+    x_pt: f32,
+    y_pt: f32,
+    draw_width_pt: f32,
+    draw_height_pt: f32,
+    jpeg_quality: u8,
+    output_path: &str,
+) -> Result<String, String> {
+    let dims = [page_width_pt, page_height_pt, draw_width_pt, draw_height_pt];
+    if dims.iter().any(|d| !d.is_finite() || *d <= 0.0) {
+        return Err("Page and image sizes must be positive".to_string());
+    }
+    if !x_pt.is_finite() || !y_pt.is_finite() {
+        return Err("Image position must be a finite number".to_string());
+    }
+    if !(1..=100).contains(&jpeg_quality) {
+        return Err(format!(
+            "JPEG quality {} is out of range (1-100)",
+            jpeg_quality
+        ));
+    }
+
+    let img = image::open(image_path)
+        .map_err(|e| format!("Cannot open image '{}': {}", image_path, e))?
+        .into_rgb8();
+
+    let mut doc = LopdfDocument::with_version("1.7");
+    let pages_id = doc.new_object_id();
+    let page_id = embed_image_at_position(
+        &mut doc,
+        pages_id,
+        &img,
+        page_width_pt,
+        page_height_pt,
+        [x_pt, y_pt, draw_width_pt, draw_height_pt],
+        Some(jpeg_quality),
+    )?;
+
+    let pages = dictionary! {
+        "Type" => "Pages",
+        "Kids" => vec![Object::Reference(page_id)],
+        "Count" => 1_i64
+    };
+    doc.objects.insert(pages_id, Object::Dictionary(pages));
+    let catalog_id = doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id
+    });
+    doc.trailer.set("Root", Object::Reference(catalog_id));
+
+    if let Some(parent) = Path::new(output_path).parent() {
+        ensure_output_dir(parent)?;
+    }
+    doc.save(output_path)
+        .map_err(|e| format!("Cannot save PDF: {}", e))?;
+    Ok(output_path.to_string())
+}
+
 /// Parse a hex color string (#RRGGBB or RRGGBB) into (r, g, b) u8 components.
 /// Falls back to the provided default on invalid input.
 pub fn parse_hex_color(hex: &str, default: (u8, u8, u8)) -> (u8, u8, u8) {
@@ -185,6 +404,103 @@ mod tests {
         assert_eq!(file_stem("C:\\photos\\README"), "README");
     }
 
+    #[test]
+    fn diagram_detection_separates_flat_and_noisy_images() {
+        let flat = image::RgbImage::from_fn(300, 200, |x, _| {
+            if x < 150 {
+                image::Rgb([255, 255, 255])
+            } else {
+                image::Rgb([30, 60, 200])
+            }
+        });
+        assert!(looks_like_diagram(&flat));
+
+        let mut seed = 12345u32;
+        let noisy = image::RgbImage::from_fn(300, 200, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            let [a, b, c, _] = seed.to_le_bytes();
+            image::Rgb([a, b, c])
+        });
+        assert!(!looks_like_diagram(&noisy));
+    }
+
+    #[test]
+    fn write_atomic_replaces_file_without_leftovers() {
+        let dir = std::env::temp_dir().join("rustine-atomic-write-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("out.json");
+        fs::write(&target, b"old").unwrap();
+
+        write_atomic(&target, b"new").unwrap();
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn image_to_pdf_page_places_image_at_given_rect() {
+        let dir = std::env::temp_dir().join("rustine-image-to-pdf-page-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let image_path = dir.join("logo.png");
+        image::RgbImage::from_pixel(8, 4, image::Rgb([200, 10, 10]))
+            .save(&image_path)
+            .unwrap();
+        let output_path = dir.join("out.pdf");
+
+        let saved = image_to_pdf_page(
+            image_path.to_str().unwrap(),
+            612.0,
+            792.0,
+            72.0,
+            600.0,
+            144.0,
+            72.0,
+            90,
+            output_path.to_str().unwrap(),
+        )
+        .unwrap();
+
+        let doc = LopdfDocument::load(&saved).unwrap();
+        let (_, page_id) = doc.get_pages().into_iter().next().unwrap();
+        let content = Content::decode(&doc.get_page_content(page_id).unwrap()).unwrap();
+        let cm = content
+            .operations
+            .iter()
+            .find(|op| op.operator == "cm")
+            .unwrap();
+        let values: Vec<f32> = cm.operands.iter().map(|o| o.as_float().unwrap()).collect();
+        assert_eq!(values, vec![144.0, 0.0, 0.0, 72.0, 72.0, 600.0]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn flate_gray_xobject_round_trips_pixels() {
+        let img =
+            image::GrayImage::from_fn(4, 2, |x, _| image::Luma([if x % 2 == 0 { 0 } else { 255 }]));
+        let mut doc = LopdfDocument::with_version("1.7");
+        let id = add_flate_gray_image_xobject(&mut doc, &img).unwrap();
+        let stream = doc.get_object(id).unwrap().as_stream().unwrap();
+        assert_eq!(
+            stream.dict.get(b"Filter").unwrap().as_name().unwrap(),
+            b"FlateDecode"
+        );
+        assert_eq!(
+            stream.dict.get(b"ColorSpace").unwrap().as_name().unwrap(),
+            b"DeviceGray"
+        );
+        assert_eq!(stream.decompressed_content().unwrap(), img.into_raw());
+    }
+
+    #[test]
+    fn image_to_pdf_page_rejects_bad_sizes() {
+        assert!(image_to_pdf_page("x.png", 0.0, 792.0, 0.0, 0.0, 10.0, 10.0, 90, "o.pdf").is_err());
+        assert!(
+            image_to_pdf_page("x.png", 612.0, 792.0, 0.0, 0.0, 10.0, 10.0, 0, "o.pdf").is_err()
+        );
+    }
+
     #[test]
     fn file_stem_empty_fallback() {
         assert_eq!(file_stem(""), "output");
@@ -21,7 +21,7 @@ interface RenameResult {
   errors: string[];
 }
 
-const TOKENS = ["{name}", "{index}", "{date}", "{ext}"];
+const TOKENS = ["{name}", "{index}", "{date}", "{ext}", "{hash}"];
 
 export function BulkRenameTab() {
   const { t } = useT();
@@ -88,7 +88,7 @@ export function FaviconTab() {
   return (
     <div className="space-y-5">
       <DropZone
-        accept="png,jpg,jpeg,bmp,tiff,tif,webp"
+        accept="png,jpg,jpeg,bmp,tiff,tif,webp,svg"
         label={t("dropzone.images_favicon")}
         sublabel={t("dropzone.sublabel_favicon")}
         onFilesSelected={handleFilesSelected}
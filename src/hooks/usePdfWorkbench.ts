@@ -98,13 +98,25 @@ interface PdfToImagesResult {
   pdf_path: string;
   output_dir: string;
   exported_count: number;
+  page_infos: PageImageInfo[];
   errors: string[];
 }
 
+interface PageImageInfo {
+  page_number: number;
+  output_path: string;
+  width: number;
+  height: number;
+  success: boolean;
+  error: string | null;
+}
+
 interface PdfCompressResult {
   output_path: string;
   original_size: number;
   compressed_size: number;
+  image_bytes_saved: number;
+  stream_bytes_saved: number;
   errors: string[];
 }
 
@@ -265,7 +277,7 @@ export function usePdfWorkbench() {
               const thumbMap = new Map<number, string>();
               for (const t of thumbnails) {
                 if (t.source_path === pdfPath && t.thumbnail_b64) {
-                  thumbMap.set(t.page_number, `data:image/jpeg;base64,${t.thumbnail_b64}`);
+                  thumbMap.set(t.page_number, `data:${t.thumbnail_mime};base64,${t.thumbnail_b64}`);
                 }
               }
               setPages((prev) =>
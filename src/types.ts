@@ -15,6 +15,7 @@ export interface BatchProgress {
   completed: number;
   total: number;
   results: ProcessingResult[];
+  notes?: string[];
 }
 
 export interface PdfExtractionResult {
@@ -78,6 +79,7 @@ export interface PageThumbnail {
   source_path: string;
   page_number: number;
   thumbnail_b64: string;
+  thumbnail_mime: string;
   source_type: "pdf" | "image";
 }
 
@@ -93,6 +95,8 @@ export interface MergePdfOptions {
   margin_px: number;
   image_quality: number;
   output_path: string;
+  generate_toc?: boolean;
+  generate_index?: boolean;
 }
 
 export interface MergePdfResult {